@@ -1,15 +1,37 @@
 use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use crate::aliases;
+use crate::apk;
+use crate::config;
+use crate::batch::{self, BatchDeviceResult};
+use crate::device_info::{self, DeviceDetails, InfoCache};
+use crate::display;
+use crate::error::{MdtError, MdtResult};
+use crate::groups;
+use crate::history;
+use crate::perf;
+use crate::ports;
+use crate::sessions::{ActiveSession, SessionKind};
+use crate::screenshot::{self, ScreenshotFormat, ScreenshotOptions, ScreenshotPreview};
+use crate::process::{self, ProcessInfo};
+use crate::remote_fs::{self, RemoteFileEntry};
 use crate::tools;
-use crossbeam_channel::Sender;
+use crate::transfer::{
+    self, ChecksumAlgorithm, DirectoryEntry, DirectoryTransferRequest, DirectoryTransferResult,
+    EntryCommandBuilder, RemoteFile, SizeProbe, TransferRequest,
+};
+use crossbeam_channel::{Receiver, Sender, TrySendError};
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::io::Read;
 use std::net::{TcpListener, TcpStream};
+use std::process::Command;
 use std::sync::{
-    atomic::{AtomicBool, Ordering},
+    atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
     Arc, Mutex, OnceLock,
 };
 use std::thread;
-use std::time::{Duration, SystemTime};
+use std::time::{Duration, Instant, SystemTime};
 use tungstenite::Message;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -17,6 +39,8 @@ pub struct Device {
     pub id: String,
     pub status: String,
     pub model: Option<String>,
+    /// 用户设置的设备别名
+    pub alias: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -24,10 +48,152 @@ pub struct DeviceList {
     pub devices: Vec<Device>,
 }
 
+/// 录屏进程：单段直接持有 adb 子进程；分段模式由后台任务在每段结束后立即开始下一段
+enum RecordProcess {
+    Single(std::process::Child),
+    Segmented {
+        stop_flag: Arc<AtomicBool>,
+        remote_paths: Arc<Mutex<Vec<String>>>,
+        /// 当前分段 screenrecord 在设备上的 pid，0 表示尚未启动或已结束
+        current_pid: Arc<AtomicU32>,
+        task: tauri::async_runtime::JoinHandle<()>,
+    },
+    /// scrcpy 客户端直接录制到本地文件
+    Scrcpy {
+        child: std::process::Child,
+        local_path: String,
+        /// scrcpy 的错误输出，录制失败时用于说明原因
+        stderr: Arc<Mutex<String>>,
+    },
+}
+
+/// scrcpy 录制时保留的错误输出长度（字节）
+const SCRCPY_STDERR_KEEP_BYTES: usize = 16 * 1024;
+
+/// 录屏后端，默认 `Auto`：安装了 scrcpy 且参数兼容时使用 scrcpy，否则使用设备端 screenrecord；
+/// 使用 scrcpy 时 `adb_start_screenrecord` 返回的是本地文件路径而不是设备端路径
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum RecordBackend {
+    #[default]
+    Auto,
+    Screenrecord,
+    Scrcpy,
+}
+
 struct ScreenRecordSession {
-    child: std::process::Child,
+    process: RecordProcess,
     remote_path: String,
     start_time: u64,
+    display_id: Option<u32>,
+    options: RecordOptions,
+}
+
+/// 录屏参数，对应 `screenrecord --bit-rate/--size/--time-limit/--rotate`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RecordOptions {
+    /// 码率（bps），如 8000000
+    pub bit_rate: Option<u32>,
+    /// 分辨率，如 `1280x720`
+    pub size: Option<String>,
+    /// 最长录制时间（秒），设备端上限为 180
+    pub time_limit_secs: Option<u32>,
+    /// 旋转 90°，部分新系统已不支持该参数
+    pub rotate: bool,
+    /// 分段录制：每段达到 180 秒上限后自动开始下一段，停止时合并
+    pub segmented: bool,
+    pub backend: RecordBackend,
+    /// 不录制声音，仅对 scrcpy 后端有效
+    pub no_audio: bool,
+}
+
+impl RecordOptions {
+    fn to_args(&self) -> MdtResult<Vec<String>> {
+        let mut args = Vec::new();
+        if let Some(bit_rate) = self.bit_rate {
+            if bit_rate == 0 {
+                return Err(MdtError::invalid_argument("bitRate", "码率必须大于 0"));
+            }
+            args.push("--bit-rate".to_string());
+            args.push(bit_rate.to_string());
+        }
+        if let Some(size) = &self.size {
+            display::validate_size(size)?;
+            args.push("--size".to_string());
+            args.push(size.clone());
+        }
+        if let Some(limit) = self.time_limit_secs {
+            if self.segmented {
+                return Err(MdtError::invalid_argument("timeLimitSecs", "分段录制不支持设置录制时长"));
+            }
+            if limit == 0 || limit > 180 {
+                return Err(MdtError::invalid_argument("timeLimitSecs", "录制时长应在 1-180 秒之间"));
+            }
+            args.push("--time-limit".to_string());
+            args.push(limit.to_string());
+        }
+        if self.rotate {
+            args.push("--rotate".to_string());
+        }
+        Ok(args)
+    }
+
+    /// 转换为 scrcpy 客户端参数；scrcpy 只支持限制最长边，`size` 取宽高中的较大值
+    fn to_scrcpy_args(&self) -> MdtResult<Vec<String>> {
+        if self.segmented || self.rotate {
+            return Err(MdtError::invalid_argument("backend", "scrcpy 录制不支持分段与旋转参数"));
+        }
+        let mut args = Vec::new();
+        if let Some(bit_rate) = self.bit_rate {
+            if bit_rate == 0 {
+                return Err(MdtError::invalid_argument("bitRate", "码率必须大于 0"));
+            }
+            args.push(format!("--video-bit-rate={}", bit_rate));
+        }
+        if let Some(size) = &self.size {
+            display::validate_size(size)?;
+            let max_size = size.split('x').filter_map(|v| v.parse::<u32>().ok()).max().unwrap_or(0);
+            args.push(format!("--max-size={}", max_size));
+        }
+        if let Some(limit) = self.time_limit_secs {
+            if limit == 0 {
+                return Err(MdtError::invalid_argument("timeLimitSecs", "录制时长必须大于 0"));
+            }
+            args.push(format!("--time-limit={}", limit));
+        }
+        if self.no_audio {
+            args.push("--no-audio".to_string());
+        }
+        Ok(args)
+    }
+
+    /// `Auto` 时判断是否改用 scrcpy：已安装客户端且未使用 screenrecord 独有的参数
+    fn prefers_scrcpy(&self) -> bool {
+        match self.backend {
+            RecordBackend::Scrcpy => true,
+            RecordBackend::Screenrecord => false,
+            RecordBackend::Auto => {
+                !self.segmented && !self.rotate && tools::resolve_tool_path("scrcpy").is_some()
+            }
+        }
+    }
+}
+
+/// 停止录屏后的结果及录制参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordingResult {
+    pub path: String,
+    /// 开始录制的秒级时间戳
+    pub start_time: u64,
+    pub duration_secs: u64,
+    pub display_id: Option<u32>,
+    pub options: RecordOptions,
+    /// 实际使用的录制后端
+    pub backend: RecordBackend,
+    /// 分段录制未能合并（缺少 ffmpeg）时各段的本地路径，`path` 为第一段
+    pub segments: Vec<String>,
 }
 
 struct MirrorStreamSession {
@@ -35,8 +201,206 @@ struct MirrorStreamSession {
     device_id: Option<String>,
     forward_port: u16,
     stop_flag: Arc<AtomicBool>,
-    clients: Arc<Mutex<Vec<Sender<Vec<u8>>>>>,
+    clients: Arc<Mutex<Vec<MirrorClient>>>,
     url: String,
+    start_time: u64,
+}
+
+/// 每个镜像客户端最多排队的数据块数（每块最大 16KB）
+const MIRROR_CLIENT_QUEUE_CAPACITY: usize = 256;
+/// 队列已满且超过该时长没有成功写出数据的客户端会被断开
+const MIRROR_CLIENT_STALL_TIMEOUT_MS: u64 = 5000;
+
+/// 镜像客户端的统计信息，由分发线程和客户端写线程共同更新
+struct MirrorClientStats {
+    sent_chunks: AtomicU64,
+    sent_bytes: AtomicU64,
+    dropped_chunks: AtomicU64,
+    dropped_bytes: AtomicU64,
+    last_write_at: AtomicU64,
+}
+
+struct MirrorClient {
+    id: u64,
+    peer: String,
+    connected_at: u64,
+    tx: Sender<Vec<u8>>,
+    /// 分发线程持有的接收端副本，队列满时用于丢弃最旧的数据块
+    rx: Receiver<Vec<u8>>,
+    stats: Arc<MirrorClientStats>,
+}
+
+impl MirrorClient {
+    /// 投递数据块，返回 false 表示客户端已断开或严重滞后。
+    /// 队列满时不能只丢弃部分数据块（会破坏 GOP 导致花屏直到下一个关键帧），
+    /// 而是清空队列并改为投递预缓冲区中从最近关键帧开始的完整数据，让客户端重新同步。
+    /// `cache` 已包含本数据块。
+    fn deliver(&self, chunk: &[u8], cache: &H264Prebuffer) -> bool {
+        match self.tx.try_send(chunk.to_vec()) {
+            Ok(()) => true,
+            Err(TrySendError::Disconnected(_)) => false,
+            Err(TrySendError::Full(_)) => {
+                let last_write = self.stats.last_write_at.load(Ordering::Relaxed);
+                if tools::now_millis().saturating_sub(last_write) > MIRROR_CLIENT_STALL_TIMEOUT_MS {
+                    println!("[mirror] client {} ({}) stalled, disconnecting", self.id, self.peer);
+                    return false;
+                }
+                let resync = cache.snapshot();
+                if resync.is_empty() {
+                    println!("[mirror] client {} ({}) lagging without keyframe, disconnecting", self.id, self.peer);
+                    return false;
+                }
+                while let Ok(dropped) = self.rx.try_recv() {
+                    self.stats.dropped_chunks.fetch_add(1, Ordering::Relaxed);
+                    self.stats.dropped_bytes.fetch_add(dropped.len() as u64, Ordering::Relaxed);
+                }
+                self.tx.try_send(resync).is_ok()
+            }
+        }
+    }
+
+    fn snapshot(&self) -> MirrorClientInfo {
+        let last_write = self.stats.last_write_at.load(Ordering::Relaxed);
+        MirrorClientInfo {
+            id: self.id,
+            peer: self.peer.clone(),
+            connected_at: self.connected_at,
+            queued_chunks: self.rx.len(),
+            sent_chunks: self.stats.sent_chunks.load(Ordering::Relaxed),
+            sent_bytes: self.stats.sent_bytes.load(Ordering::Relaxed),
+            dropped_chunks: self.stats.dropped_chunks.load(Ordering::Relaxed),
+            dropped_bytes: self.stats.dropped_bytes.load(Ordering::Relaxed),
+            lag_ms: tools::now_millis().saturating_sub(last_write),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MirrorClientInfo {
+    pub id: u64,
+    pub peer: String,
+    pub connected_at: u64,
+    pub queued_chunks: usize,
+    pub sent_chunks: u64,
+    pub sent_bytes: u64,
+    pub dropped_chunks: u64,
+    pub dropped_bytes: u64,
+    /// 距离上次成功写出数据的毫秒数
+    pub lag_ms: u64,
+}
+
+/// 镜像预缓冲区：按 H.264 NAL 单元解析码流，始终保留最近一个关键帧起的完整 GOP，
+/// 保证后加入的客户端拿到的数据以 SPS/PPS + IDR 开头，可以立即解码。
+struct H264Prebuffer {
+    limit: usize,
+    /// 尚未遇到下一个起始码的数据（当前 NAL 单元可能还不完整）
+    pending: Vec<u8>,
+    /// 下次查找起始码的偏移，避免重复扫描
+    scan_offset: usize,
+    sps: Option<Vec<u8>>,
+    pps: Option<Vec<u8>>,
+    /// 以最近的 SPS/PPS + IDR 开头的完整 NAL 单元序列
+    gop: Vec<u8>,
+}
+
+impl H264Prebuffer {
+    const NAL_IDR: u8 = 5;
+    const NAL_SPS: u8 = 7;
+    const NAL_PPS: u8 = 8;
+
+    fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            pending: Vec::new(),
+            scan_offset: 0,
+            sps: None,
+            pps: None,
+            gop: Vec::new(),
+        }
+    }
+
+    /// 查找 `00 00 01` 起始码，返回 (起始码开始位置, NAL 头位置)；兼容 4 字节起始码
+    fn find_start_code(data: &[u8], from: usize) -> Option<(usize, usize)> {
+        if data.len() < 3 {
+            return None;
+        }
+        (from..data.len() - 2)
+            .find(|&i| data[i] == 0 && data[i + 1] == 0 && data[i + 2] == 1)
+            .map(|i| {
+                let start = if i > 0 && data[i - 1] == 0 { i - 1 } else { i };
+                (start, i + 3)
+            })
+    }
+
+    fn push(&mut self, chunk: &[u8]) {
+        self.pending.extend_from_slice(chunk);
+
+        // 丢弃第一个起始码之前的无效数据
+        if self.scan_offset == 0 {
+            match Self::find_start_code(&self.pending, 0) {
+                Some((start, header)) => {
+                    self.pending.drain(0..start);
+                    self.scan_offset = header - start;
+                }
+                None => {
+                    let keep = self.pending.len().min(3);
+                    let excess = self.pending.len() - keep;
+                    self.pending.drain(0..excess);
+                    return;
+                }
+            }
+        }
+
+        while let Some((start, header)) = Self::find_start_code(&self.pending, self.scan_offset) {
+            let nal: Vec<u8> = self.pending.drain(0..start).collect();
+            self.handle_nal(nal);
+            self.scan_offset = header - start;
+        }
+        self.scan_offset = self.pending.len().saturating_sub(3).max(self.scan_offset);
+    }
+
+    fn handle_nal(&mut self, nal: Vec<u8>) {
+        let header = match Self::find_start_code(&nal, 0).and_then(|(_, h)| nal.get(h)) {
+            Some(byte) => *byte,
+            None => return,
+        };
+        match header & 0x1f {
+            Self::NAL_SPS => self.sps = Some(nal),
+            Self::NAL_PPS => self.pps = Some(nal),
+            Self::NAL_IDR => {
+                self.gop.clear();
+                if let Some(sps) = &self.sps {
+                    self.gop.extend_from_slice(sps);
+                }
+                if let Some(pps) = &self.pps {
+                    self.gop.extend_from_slice(pps);
+                }
+                self.gop.extend_from_slice(&nal);
+            }
+            _ => {
+                if self.gop.is_empty() {
+                    return;
+                }
+                self.gop.extend_from_slice(&nal);
+                // GOP 过大时无法按字节裁剪，直接丢弃并等待下一个关键帧
+                if self.gop.len() > self.limit {
+                    self.gop.clear();
+                }
+            }
+        }
+    }
+
+    /// 新客户端的初始数据：完整 GOP 加上当前未结束的 NAL，之后的实时数据可以直接衔接
+    fn snapshot(&self) -> Vec<u8> {
+        if self.gop.is_empty() {
+            return Vec::new();
+        }
+        let mut data = Vec::with_capacity(self.gop.len() + self.pending.len());
+        data.extend_from_slice(&self.gop);
+        data.extend_from_slice(&self.pending);
+        data
+    }
 }
 
 fn screen_recordings() -> &'static Mutex<HashMap<String, ScreenRecordSession>> {
@@ -49,37 +413,275 @@ fn mirror_streams() -> &'static Mutex<HashMap<String, MirrorStreamSession>> {
     STORE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
-fn device_key(device_id: &Option<String>) -> String {
-    device_id.clone().unwrap_or_else(|| "default".to_string())
+/// 正在运行的镜像会话占用的本地转发端口
+pub(crate) fn active_mirror_ports() -> Vec<u16> {
+    mirror_streams()
+        .lock()
+        .map(|store| store.values().map(|session| session.forward_port).collect())
+        .unwrap_or_default()
 }
 
-fn adb_shell(device_id: &Option<String>, args: &[&str]) -> Result<String, String> {
-    use std::process::Command;
+/// 当前的录屏与投屏会话
+pub(crate) fn active_sessions() -> MdtResult<Vec<ActiveSession>> {
+    let mut sessions = Vec::new();
+    for (device, session) in screen_recordings()
+        .lock()
+        .map_err(|_| MdtError::lock("screenrecord"))?
+        .iter()
+    {
+        let kind = match session.process {
+            RecordProcess::Scrcpy { .. } => SessionKind::ScrcpyRecord,
+            _ => SessionKind::Screenrecord,
+        };
+        let mut active = ActiveSession::new("android", device, kind, session.start_time);
+        active.output = Some(session.remote_path.clone());
+        sessions.push(active);
+    }
+    for (device, session) in mirror_streams()
+        .lock()
+        .map_err(|_| MdtError::lock("mirror"))?
+        .iter()
+    {
+        let mut active = ActiveSession::new("android", device, SessionKind::Mirror, session.start_time);
+        active.url = Some(session.url.clone());
+        sessions.push(active);
+    }
+    Ok(sessions)
+}
 
+/// 创建指定设备的 adb 命令（自动附加 `-s <device>`）
+pub(crate) fn adb_command(device_id: &Option<String>) -> Command {
     let mut cmd = tools::command_for("adb");
     if let Some(device) = device_id {
-        cmd.args(&["-s", device]);
+        cmd.args(["-s", device]);
     }
+    cmd
+}
+
+/// 执行 adb 子命令，成功时返回标准输出
+pub(crate) async fn adb_run(device_id: &Option<String>, args: &[&str]) -> MdtResult<String> {
+    let mut cmd = adb_command(device_id);
+    cmd.args(args);
+
+    let output = tools::run_checked("adb", device_id, cmd).await?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+pub(crate) async fn adb_shell(device_id: &Option<String>, args: &[&str]) -> MdtResult<String> {
+    let mut cmd = adb_command(device_id);
     cmd.arg("shell");
     cmd.args(args);
 
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 adb shell 失败: {}", e))?;
+    let output = tools::run_checked("adb", device_id, cmd).await?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 以 root 身份执行 shell 脚本，兼容 Magisk（`su -c`）与 userdebug（`su 0 sh -c`）两种 su
+pub(crate) async fn adb_su(device_id: &Option<String>, script: &str) -> MdtResult<String> {
+    let quoted = tools::shell_quote(script);
+    match adb_shell(device_id, &["su", "-c", &quoted]).await {
+        Ok(output) => Ok(output),
+        Err(_) => adb_shell(device_id, &["su", "0", "sh", "-c", &quoted]).await,
+    }
+}
+
+/// 读取系统属性，未设置时为 None
+pub(crate) async fn adb_getprop(device_id: &Option<String>, name: &str) -> Option<String> {
+    adb_shell(device_id, &["getprop", name])
+        .await
+        .ok()
+        .filter(|value| !value.is_empty())
+}
+
+/// 读取设置项，未设置（空或 `null`）时为 None
+pub(crate) async fn adb_settings_value(device_id: &Option<String>, namespace: &str, key: &str) -> Option<String> {
+    adb_shell(device_id, &["settings", "get", namespace, key])
+        .await
+        .ok()
+        .filter(|value| !value.is_empty() && value != "null")
+}
+
+/// `cmd` / `am` 等命令出错时多数仍以 0 退出，需要检查输出中的 `Error` / `Exception occurred` 行
+pub(crate) fn expect_no_error(command: &str, output: String) -> MdtResult<String> {
+    let error = output.lines().find(|line| {
+        let line = line.trim_start();
+        line.starts_with("Error") || line.starts_with("Exception occurred")
+    });
+    match error {
+        Some(error) => Err(MdtError::CommandFailed {
+            command: command.to_string(),
+            exit_code: None,
+            stderr: error.trim().to_string(),
+        }),
+        None => Ok(output),
+    }
+}
+
+/// 拉取受保护目录下的文件：先直接拉取，权限不足时通过 su 复制到 /data/local/tmp 再拉取
+async fn adb_pull_protected(
+    device_id: &Option<String>,
+    remote_dir: &str,
+    output_dir: &str,
+) -> MdtResult<Vec<String>> {
+    std::fs::create_dir_all(output_dir).map_err(|e| MdtError::io("创建输出目录", e))?;
+    let (names, use_su) = match adb_shell(device_id, &["ls", "-1", &tools::shell_quote(remote_dir)]).await {
+        Ok(listing) if !listing.contains("Permission denied") => (listing, false),
+        _ => (adb_su(device_id, &format!("ls -1 {}", tools::shell_quote(remote_dir))).await?, true),
+    };
+
+    let mut pulled = Vec::new();
+    for name in names.lines().map(str::trim).filter(|name| !name.is_empty()) {
+        let remote = format!("{}/{}", remote_dir.trim_end_matches('/'), name);
+        let local = Path::new(output_dir).join(name).to_string_lossy().to_string();
+        if !use_su && adb_run(device_id, &["pull", &remote, &local]).await.is_ok() {
+            pulled.push(local);
+            continue;
+        }
+        let staging = format!("/data/local/tmp/mdt_{}", name);
+        adb_su(
+            device_id,
+            &format!(
+                "cp {} {} && chmod 644 {}",
+                tools::shell_quote(&remote),
+                tools::shell_quote(&staging),
+                tools::shell_quote(&staging)
+            ),
+        )
+        .await?;
+        let result = adb_run(device_id, &["pull", &staging, &local]).await;
+        let _ = adb_shell(device_id, &["rm", "-f", &tools::shell_quote(&staging)]).await;
+        result?;
+        pulled.push(local);
+    }
+    Ok(pulled)
+}
+
+/// 从 bugreport zip 中解压 `FS/<目录>/` 下的文件
+fn extract_from_bugreport(zip_path: &Path, remote_dir: &str, output_dir: &str) -> MdtResult<Vec<String>> {
+    let file = std::fs::File::open(zip_path).map_err(|e| MdtError::io("打开 bugreport", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| MdtError::io("读取 bugreport", e))?;
+    let prefix = format!("FS{}/", remote_dir.trim_end_matches('/'));
+    let mut extracted = Vec::new();
+    for index in 0..archive.len() {
+        let mut entry = archive.by_index(index).map_err(|e| MdtError::io("读取 bugreport", e))?;
+        let Some(name) = entry.name().strip_prefix(&prefix).map(str::to_string) else {
+            continue;
+        };
+        if name.is_empty() || name.contains('/') || entry.is_dir() {
+            continue;
+        }
+        let local = Path::new(output_dir).join(&name);
+        let mut out = std::fs::File::create(&local).map_err(|e| MdtError::io("写入文件", e))?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| MdtError::io("写入文件", e))?;
+        extracted.push(local.to_string_lossy().to_string());
+    }
+    Ok(extracted)
+}
+
+/// 收集受保护目录中的文件，直接拉取和 su 均失败时生成 bugreport 并从中提取
+pub(crate) async fn adb_collect_protected(
+    device_id: &Option<String>,
+    remote_dir: &str,
+    output_dir: &str,
+) -> MdtResult<Vec<String>> {
+    if let Ok(pulled) = adb_pull_protected(device_id, remote_dir, output_dir).await {
+        return Ok(pulled);
+    }
+
+    let zip_path = std::env::temp_dir().join(format!("mdt_bugreport_{}.zip", tools::now_millis()));
+    adb_run(device_id, &["bugreport", &zip_path.to_string_lossy()]).await?;
+    let remote_dir = remote_dir.to_string();
+    let output_dir = output_dir.to_string();
+    let archive = zip_path.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        extract_from_bugreport(&archive, &remote_dir, &output_dir)
+    })
+    .await
+    .map_err(|e| MdtError::io("解压 bugreport", e))?;
+    let _ = std::fs::remove_file(&zip_path);
+    result
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+/// 推送目标为设备端目录时补全文件名，便于统计设备端已写入的大小
+async fn adb_push_target(device_id: &Option<String>, local_path: &str, remote_path: &str) -> String {
+    let file_name = Path::new(local_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let is_dir = remote_path.ends_with('/')
+        || adb_shell(device_id, &["stat", "-c", "%F", &tools::shell_quote(remote_path)])
+            .await
+            .map(|kind| kind.contains("directory"))
+            .unwrap_or(false);
+
+    if is_dir && !file_name.is_empty() {
+        format!("{}/{}", remote_path.trim_end_matches('/'), file_name)
     } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+        remote_path.to_string()
+    }
+}
+
+/// 推送进度统计：定时查询设备端目标文件大小
+fn adb_remote_size_probe(device_id: Option<String>, target: String) -> SizeProbe {
+    SizeProbe::Remote(Box::new(move || {
+        let mut cmd = adb_command(&device_id);
+        cmd.args(["shell", "stat", "-c", "%s", &tools::shell_quote(&target)]);
+        cmd
+    }))
+}
+
+/// 递归列出设备端目录下的文件
+async fn adb_remote_listing(
+    device_id: &Option<String>,
+    remote_dir: &str,
+) -> MdtResult<HashMap<String, RemoteFile>> {
+    let args = transfer::remote_listing_args(remote_dir);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = adb_shell(device_id, &args).await?;
+    Ok(transfer::parse_remote_listing(remote_dir, &output))
+}
+
+/// 传输完成后对比本地与设备端文件的校验和，设备不支持 sha256sum 时回退到 md5sum
+async fn adb_verify_checksum(
+    device_id: &Option<String>,
+    local_path: PathBuf,
+    remote_path: &str,
+) -> MdtResult<()> {
+    let quoted = tools::shell_quote(remote_path);
+    let mut last_error = None;
+    for algorithm in ChecksumAlgorithm::ALL {
+        match adb_shell(device_id, &[algorithm.device_command(), &quoted]).await {
+            Ok(output) => {
+                return transfer::compare_checksum(algorithm, local_path, remote_path, &output).await;
+            }
+            Err(err) => last_error = Some(err),
+        }
     }
+    Err(last_error.unwrap_or_else(|| MdtError::ToolNotFound {
+        tool: "sha256sum".to_string(),
+    }))
 }
 
+/// 查询设备端文件大小
+async fn adb_remote_size(device_id: &Option<String>, remote_path: &str) -> Option<u64> {
+    adb_shell(device_id, &["stat", "-c", "%s", &tools::shell_quote(remote_path)])
+        .await
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// 未安装 scrcpy 客户端时假定的 server 版本
+pub(crate) const DEFAULT_SCRCPY_VERSION: &str = "3.3.4";
+/// scrcpy server 推送到设备上的路径
+pub(crate) const SCRCPY_SERVER_REMOTE_PATH: &str = "/data/local/tmp/scrcpy-server.jar";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MirrorStreamInfo {
     pub url: String,
 }
 
-fn resolve_scrcpy_server_path() -> Option<std::path::PathBuf> {
+pub(crate) fn resolve_scrcpy_server_path() -> Option<std::path::PathBuf> {
     if let Ok(path) = std::env::var("MDT_SCRCPY_SERVER_PATH") {
         let candidate = std::path::PathBuf::from(path);
         if candidate.exists() {
@@ -117,7 +719,7 @@ fn resolve_scrcpy_server_path() -> Option<std::path::PathBuf> {
     None
 }
 
-fn resolve_scrcpy_version() -> Option<String> {
+pub(crate) async fn resolve_scrcpy_version() -> Option<String> {
     if let Ok(version) = std::env::var("MDT_SCRCPY_SERVER_VERSION") {
         let trimmed = version.trim().to_string();
         if !trimmed.is_empty() {
@@ -125,7 +727,9 @@ fn resolve_scrcpy_version() -> Option<String> {
         }
     }
 
-    let output = tools::command_for("scrcpy").arg("--version").output().ok()?;
+    let mut cmd = tools::command_for("scrcpy");
+    cmd.arg("--version");
+    let output = tools::output(cmd).await.ok()?;
     if !output.status.success() {
         return None;
     }
@@ -143,12 +747,12 @@ fn resolve_scrcpy_version() -> Option<String> {
     Some(version.to_string())
 }
 
-fn pick_free_port() -> Result<u16, String> {
+pub(crate) fn pick_free_port() -> MdtResult<u16> {
     let listener = TcpListener::bind("127.0.0.1:0")
-        .map_err(|e| format!("分配本地端口失败: {}", e))?;
+        .map_err(|e| MdtError::io("分配本地端口", e))?;
     let port = listener
         .local_addr()
-        .map_err(|e| format!("获取本地端口失败: {}", e))?
+        .map_err(|e| MdtError::io("获取本地端口", e))?
         .port();
     Ok(port)
 }
@@ -167,7 +771,7 @@ fn connect_with_retry(port: u16, stop_flag: &Arc<AtomicBool>) -> Result<TcpStrea
     Err("连接 scrcpy 镜像流失败".to_string())
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct DeviceInfo {
     pub model: Option<String>,
@@ -176,25 +780,71 @@ pub struct DeviceInfo {
     pub version: Option<String>,
     pub battery_level: Option<u8>,
     pub battery_status: Option<String>,
+    #[serde(flatten)]
+    pub details: DeviceDetails,
 }
 
-#[tauri::command]
-pub async fn adb_devices() -> Result<DeviceList, String> {
-    use std::process::Command;
-
-    let output = tools::command_for("adb")
-        .arg("devices")
-        .output()
-        .map_err(|e| format!("执行 adb devices 失败: {}", e))?;
+/// 安装选项，对应 `adb install` 的常用参数（始终附带 `-r` 覆盖安装）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InstallOptions {
+    /// `-g`：授予清单中声明的全部运行时权限
+    pub grant_runtime_permissions: bool,
+    /// `-t`：允许安装测试包
+    pub allow_test: bool,
+    /// `-d`：允许降级安装
+    pub allow_downgrade: bool,
+    /// `--user`：目标用户 ID，或 `all` / `current`
+    pub target_user: Option<String>,
+    /// `--install-location`：`auto` / `internal` / `external`
+    pub install_location: Option<String>,
+}
 
-    if !output.status.success() {
-        return Err(format!(
-            "adb devices 执行失败: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
+impl InstallOptions {
+    pub(crate) fn to_args(&self) -> MdtResult<Vec<String>> {
+        let mut args = vec!["-r".to_string()];
+        if self.grant_runtime_permissions {
+            args.push("-g".to_string());
+        }
+        if self.allow_test {
+            args.push("-t".to_string());
+        }
+        if self.allow_downgrade {
+            args.push("-d".to_string());
+        }
+        if let Some(user) = &self.target_user {
+            let user = user.trim();
+            if user != "all" && user != "current" && user.parse::<u32>().is_err() {
+                return Err(MdtError::invalid_argument(
+                    "targetUser",
+                    format!("无效的用户: {}", user),
+                ));
+            }
+            args.push("--user".to_string());
+            args.push(user.to_string());
+        }
+        if let Some(location) = &self.install_location {
+            let value = match location.as_str() {
+                "auto" => "0",
+                "internal" => "1",
+                "external" => "2",
+                other => {
+                    return Err(MdtError::invalid_argument(
+                        "installLocation",
+                        format!("无效的安装位置: {}", other),
+                    ))
+                }
+            };
+            args.push("--install-location".to_string());
+            args.push(value.to_string());
+        }
+        Ok(args)
     }
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+#[tauri::command]
+pub async fn adb_devices() -> MdtResult<DeviceList> {
+    let stdout = adb_run(&None, &["devices"]).await?;
     let mut devices = Vec::new();
 
     // 解析 adb devices 输出
@@ -211,30 +861,122 @@ pub async fn adb_devices() -> Result<DeviceList, String> {
                 id: parts[0].to_string(),
                 status: parts[1].to_string(),
                 model: None, // 可以通过 adb -s <device> shell getprop ro.product.model 获取
+                alias: aliases::alias_for(parts[0]),
             });
         }
     }
 
+    let online: Vec<String> = devices
+        .iter()
+        .filter(|device| device.status == "device")
+        .map(|device| device.id.clone())
+        .collect();
+    ports::sync_online_devices(&online);
+    prefetch_device_info(&online);
+
     Ok(DeviceList { devices })
 }
 
+fn device_info_cache() -> &'static InfoCache<DeviceInfo> {
+    static CACHE: OnceLock<InfoCache<DeviceInfo>> = OnceLock::new();
+    CACHE.get_or_init(InfoCache::default)
+}
+
+/// 对新上线的设备在后台预取设备信息，并清理已断开设备的缓存
+fn prefetch_device_info(online: &[String]) {
+    for serial in device_info_cache().sync_online(online) {
+        tauri::async_runtime::spawn(async move {
+            let device_id = Some(serial.clone());
+            let info = match collect_device_info(&device_id).await {
+                Ok(info) => Some(info),
+                Err(err) => {
+                    println!("[adb] prefetch device info failed on {}: {}", serial, err);
+                    None
+                }
+            };
+            device_info_cache().finish_prefetch(serial, info);
+        });
+    }
+}
+
+/// 设备信息在缓存有效期内直接返回缓存，`refresh` 为 true 时强制重新采集
 #[tauri::command]
-pub async fn adb_device_info(device_id: Option<String>) -> Result<DeviceInfo, String> {
-    let model = adb_shell(&device_id, &["getprop", "ro.product.model"]).ok();
-    let brand = adb_shell(&device_id, &["getprop", "ro.product.brand"]).ok();
-    let name = adb_shell(&device_id, &["getprop", "ro.product.name"]).ok();
-    let version = adb_shell(&device_id, &["getprop", "ro.build.version.release"]).ok();
+pub async fn adb_device_info(device_id: Option<String>, refresh: Option<bool>) -> MdtResult<DeviceInfo> {
+    let key = tools::device_key(&device_id);
+    if !refresh.unwrap_or(false) {
+        if let Some(info) = device_info_cache().get(&key) {
+            return Ok(info);
+        }
+    }
+    let info = collect_device_info(&device_id).await?;
+    device_info_cache().put(key, info.clone());
+    Ok(info)
+}
+
+async fn collect_device_info(device_id: &Option<String>) -> MdtResult<DeviceInfo> {
+    // 各项查询互不依赖，并发执行以缩短等待时间
+    let query = |args: &'static [&'static str]| {
+        let device_id = device_id.clone();
+        device_info::spawn_query(async move { adb_shell(&device_id, args).await })
+    };
+    let model = query(&["getprop", "ro.product.model"]);
+    let brand = query(&["getprop", "ro.product.brand"]);
+    let name = query(&["getprop", "ro.product.name"]);
+    let version = query(&["getprop", "ro.build.version.release"]);
+    let battery = query(&["dumpsys", "battery"]);
+    let size = query(&["wm", "size"]);
+    let density = query(&["wm", "density"]);
+    let abis = query(&["getprop", "ro.product.cpu.abilist"]);
+    let sdk = query(&["getprop", "ro.build.version.sdk"]);
+    let meminfo = query(&["cat", "/proc/meminfo"]);
+    let cpuinfo = query(&["cat", "/proc/cpuinfo"]);
+    let hardware = query(&["getprop", "ro.hardware"]);
+    let kernel = query(&["uname", "-r"]);
+    let uptime = query(&["cat", "/proc/uptime"]);
+    let wlan = query(&["ip", "addr", "show", "wlan0"]);
+    let serial = query(&["getprop", "ro.serialno"]);
+
+    let mut display = display::DisplayState::default();
+    for output in [device_info::join_query(size).await, device_info::join_query(density).await].into_iter().flatten() {
+        display::parse_wm_output(&mut display, &output);
+    }
+    let (total_ram_kb, available_ram_kb) = device_info::join_query(meminfo)
+        .await
+        .map(|output| device_info::parse_meminfo(&output))
+        .unwrap_or_default();
+    let cpu_hardware = match device_info::join_query(cpuinfo).await.and_then(|output| device_info::parse_cpu_hardware(&output)) {
+        Some(hardware) => Some(hardware),
+        None => device_info::join_query(hardware).await,
+    };
+    let (wifi_mac, wifi_ip) = device_info::join_query(wlan)
+        .await
+        .map(|output| device_info::parse_interface(&output))
+        .unwrap_or_default();
 
     let mut info = DeviceInfo {
-        model,
-        brand,
-        name,
-        version,
+        model: device_info::join_query(model).await,
+        brand: device_info::join_query(brand).await,
+        name: device_info::join_query(name).await,
+        version: device_info::join_query(version).await,
         battery_level: None,
         battery_status: None,
+        details: DeviceDetails {
+            resolution: display.override_size.or(display.physical_size),
+            density: display.override_density.or(display.physical_density),
+            abis: device_info::parse_abis(device_info::join_query(abis).await),
+            sdk_level: device_info::join_query(sdk).await.and_then(|value| value.parse().ok()),
+            total_ram_kb,
+            available_ram_kb,
+            cpu_hardware,
+            kernel_version: device_info::join_query(kernel).await,
+            uptime_secs: device_info::join_query(uptime).await.and_then(|output| device_info::parse_uptime(&output)),
+            wifi_mac,
+            wifi_ip,
+            serial: device_info::join_query(serial).await,
+        },
     };
 
-    if let Ok(battery_dump) = adb_shell(&device_id, &["dumpsys", "battery"]) {
+    if let Some(battery_dump) = device_info::join_query(battery).await {
         for line in battery_dump.lines() {
             let trimmed = line.trim();
             if let Some(value) = trimmed.strip_prefix("level:") {
@@ -246,7 +988,7 @@ pub async fn adb_device_info(device_id: Option<String>) -> Result<DeviceInfo, St
                 let status = match value.trim() {
                     "2" => "charging",
                     "3" => "discharging",
-                    "4" => "discharging",
+                    "4" => "not_charging",
                     "5" => "full",
                     _ => "unknown",
                 };
@@ -258,279 +1000,2116 @@ pub async fn adb_device_info(device_id: Option<String>) -> Result<DeviceInfo, St
     Ok(info)
 }
 
+/// `dumpsys battery` 解析出的电池详情
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryDetails {
+    pub level: Option<u8>,
+    /// charging / discharging / not_charging / full / unknown
+    pub status: Option<String>,
+    /// good / overheat / dead / over_voltage / failure / cold / unknown
+    pub health: Option<String>,
+    /// ac / usb / wireless / dock，未充电时为空
+    pub plug_type: Option<String>,
+    pub present: Option<bool>,
+    /// 电压（mV）
+    pub voltage_mv: Option<u32>,
+    /// 温度（摄氏度）
+    pub temperature_c: Option<f32>,
+    /// 剩余电量计数（µAh）
+    pub charge_counter_uah: Option<u64>,
+    pub technology: Option<String>,
+}
+
+fn parse_battery_details(output: &str) -> BatteryDetails {
+    let mut details = BatteryDetails::default();
+    for line in output.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "level" => details.level = value.parse().ok(),
+            "status" => {
+                details.status = Some(
+                    match value {
+                        "2" => "charging",
+                        "3" => "discharging",
+                        "4" => "not_charging",
+                        "5" => "full",
+                        _ => "unknown",
+                    }
+                    .to_string(),
+                )
+            }
+            "health" => {
+                details.health = Some(
+                    match value {
+                        "2" => "good",
+                        "3" => "overheat",
+                        "4" => "dead",
+                        "5" => "over_voltage",
+                        "6" => "failure",
+                        "7" => "cold",
+                        _ => "unknown",
+                    }
+                    .to_string(),
+                )
+            }
+            "AC powered" if value == "true" => details.plug_type = Some("ac".to_string()),
+            "USB powered" if value == "true" => details.plug_type = Some("usb".to_string()),
+            "Wireless powered" if value == "true" => {
+                details.plug_type = Some("wireless".to_string())
+            }
+            "Dock powered" if value == "true" => details.plug_type = Some("dock".to_string()),
+            "present" => details.present = Some(value == "true"),
+            "voltage" => details.voltage_mv = value.parse().ok(),
+            "temperature" => {
+                details.temperature_c = value.parse::<f32>().ok().map(|t| t / 10.0)
+            }
+            "Charge counter" => details.charge_counter_uah = value.parse().ok(),
+            "technology" => details.technology = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    details
+}
+
+/// 查询电池温度、健康度、电压、电量计数、充电方式等详情
 #[tauri::command]
-pub async fn adb_install(device_id: Option<String>, apk_path: String) -> Result<String, String> {
-    use std::process::Command;
+pub async fn adb_battery_details(device_id: Option<String>) -> MdtResult<BatteryDetails> {
+    let output = adb_shell(&device_id, &["dumpsys", "battery"]).await?;
+    Ok(parse_battery_details(&output))
+}
 
-    let mut cmd = tools::command_for("adb");
-    
-    if let Some(device) = device_id {
-        cmd.args(&["-s", &device]);
+/// 模拟电池状态（`dumpsys battery set/unplug`），用于测试低电量等界面
+#[tauri::command]
+pub async fn adb_battery_set(
+    device_id: Option<String>,
+    level: Option<u8>,
+    unplug: Option<bool>,
+    status: Option<String>,
+) -> MdtResult<()> {
+    let _history = history::track();
+    if level.is_none() && unplug.is_none() && status.is_none() {
+        return Err(MdtError::invalid_argument("level", "至少需要指定一项电池状态"));
+    }
+    // 先校验全部参数，避免只应用了一部分模拟状态
+    if level.is_some_and(|level| level > 100) {
+        return Err(MdtError::invalid_argument("level", "电量需在 0 到 100 之间"));
     }
-    
-    cmd.args(&["install", "-r", &apk_path]);
-    
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 adb install 失败: {}", e))?;
+    let status_code = match status.as_deref() {
+        None => None,
+        Some("charging") => Some("2"),
+        Some("discharging") => Some("3"),
+        Some("not_charging") => Some("4"),
+        Some("full") => Some("5"),
+        Some(other) => {
+            return Err(MdtError::invalid_argument(
+                "status",
+                format!("无效的充电状态: {}", other),
+            ))
+        }
+    };
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    if let Some(level) = level {
+        adb_shell(&device_id, &["dumpsys", "battery", "set", "level", &level.to_string()]).await?;
+    }
+    if let Some(code) = status_code {
+        adb_shell(&device_id, &["dumpsys", "battery", "set", "status", code]).await?;
+    }
+    match unplug {
+        Some(true) => {
+            adb_shell(&device_id, &["dumpsys", "battery", "unplug"]).await?;
+        }
+        Some(false) => {
+            adb_shell(&device_id, &["dumpsys", "battery", "set", "ac", "1"]).await?;
+        }
+        None => {}
     }
+    Ok(())
 }
 
+/// 恢复真实电池状态
 #[tauri::command]
-pub async fn adb_uninstall(device_id: Option<String>, package_name: String) -> Result<String, String> {
-    use std::process::Command;
+pub async fn adb_battery_reset(device_id: Option<String>) -> MdtResult<()> {
+    let _history = history::track();
+    adb_shell(&device_id, &["dumpsys", "battery", "reset"]).await?;
+    Ok(())
+}
 
-    let mut cmd = tools::command_for("adb");
-    
-    if let Some(device) = device_id {
-        cmd.args(&["-s", &device]);
+/// 重启设备，`target` 可选 system（默认）/ recovery / bootloader / fastboot / sideload；
+/// 需由界面确认后传入 `confirm = true`
+#[tauri::command]
+pub async fn adb_reboot(device_id: Option<String>, target: Option<String>, confirm: bool) -> MdtResult<()> {
+    let _history = history::track();
+    if !confirm {
+        return Err(MdtError::invalid_argument("confirm", "重启设备需要确认"));
     }
-    
-    cmd.args(&["uninstall", &package_name]);
-    
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 adb uninstall 失败: {}", e))?;
-
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    let target = target.unwrap_or_else(|| "system".to_string());
+    let mut args = vec!["reboot"];
+    match target.as_str() {
+        "system" => {}
+        "recovery" | "bootloader" | "fastboot" | "sideload" => args.push(&target),
+        _ => {
+            return Err(MdtError::invalid_argument(
+                "target",
+                format!("不支持的重启目标: {}", target),
+            ))
+        }
     }
+    adb_run(&device_id, &args).await?;
+    println!("[adb] rebooting {} into {}", tools::device_key(&device_id), target);
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn adb_list_packages(device_id: Option<String>) -> Result<Vec<String>, String> {
-    use std::process::Command;
+pub async fn adb_install(
+    device_id: Option<String>,
+    apk_path: String,
+    options: Option<InstallOptions>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let option_args = options.unwrap_or_else(|| config::current().install).to_args()?;
+    let mut args = vec!["install"];
+    args.extend(option_args.iter().map(String::as_str));
+    args.push(&apk_path);
+    adb_run(&device_id, &args).await
+}
 
-    let mut cmd = tools::command_for("adb");
-    
-    if let Some(device) = device_id {
-        cmd.args(&["-s", &device]);
+/// 在多台设备上并发安装同一个 APK，通过 `batch-progress` 事件推送每台设备的结果
+#[tauri::command]
+pub async fn adb_install_batch(
+    app: AppHandle,
+    device_ids: Option<Vec<String>>,
+    group: Option<String>,
+    apk_path: String,
+    options: Option<InstallOptions>,
+    parallel: Option<usize>,
+) -> MdtResult<Vec<BatchDeviceResult>> {
+    let _history = history::track();
+    let device_ids = groups::resolve_devices(device_ids, group)?;
+    if !Path::new(&apk_path).is_file() {
+        return Err(MdtError::invalid_argument("apkPath", format!("文件不存在: {}", apk_path)));
     }
-    
-    cmd.args(&["shell", "pm", "list", "packages"]);
-    
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 adb shell pm list packages 失败: {}", e))?;
+    let option_args = options.unwrap_or_else(|| config::current().install).to_args()?;
+
+    Ok(batch::run_batch(&app, "install", device_ids, parallel, move |device_id| {
+        let mut args = vec!["install".to_string()];
+        args.extend(option_args.iter().cloned());
+        args.push(apk_path.clone());
+        async move {
+            let args: Vec<&str> = args.iter().map(String::as_str).collect();
+            adb_run(&Some(device_id), &args).await
+        }
+    })
+    .await)
+}
 
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+/// 安装拆分 APK（base + splits），安装前校验所有文件属于同一个包
+#[tauri::command]
+pub async fn adb_install_multiple(
+    device_id: Option<String>,
+    apk_paths: Vec<String>,
+    options: Option<InstallOptions>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    if apk_paths.is_empty() {
+        return Err(MdtError::invalid_argument("apkPaths", "至少需要一个 APK 文件"));
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let packages: Vec<String> = stdout
-        .lines()
-        .filter_map(|line| {
-            let line = line.trim();
-            if line.starts_with("package:") {
-                Some(line.replace("package:", "").trim().to_string())
-            } else {
-                None
+    for apk_path in &apk_paths {
+        if !Path::new(apk_path).is_file() {
+            return Err(MdtError::invalid_argument(
+                "apkPaths",
+                format!("文件不存在: {}", apk_path),
+            ));
+        }
+    }
+
+    // install-multiple 本身不需要包名，这里只是提前发现混入其他应用的拆分包；
+    // 主机上没有 aapt / aapt2 或无法解析时跳过检查，交给设备端校验
+    let mut package: Option<(String, &str)> = None;
+    for apk_path in &apk_paths {
+        let name = match apk::package_name(apk_path).await {
+            Ok(name) => name,
+            Err(err) => {
+                println!("[adb] skip split package check: {}", err);
+                break;
             }
-        })
-        .collect();
+        };
+        match &package {
+            Some((expected, first)) if *expected != name => {
+                return Err(MdtError::invalid_argument(
+                    "apkPaths",
+                    format!(
+                        "拆分包不属于同一应用: {} ({}) 与 {} ({})",
+                        first, expected, apk_path, name
+                    ),
+                ));
+            }
+            Some(_) => {}
+            None => package = Some((name, apk_path)),
+        }
+    }
 
-    Ok(packages)
+    let option_args = options.unwrap_or_else(|| config::current().install).to_args()?;
+    let mut args = vec!["install-multiple"];
+    args.extend(option_args.iter().map(String::as_str));
+    args.extend(apk_paths.iter().map(String::as_str));
+    adb_run(&device_id, &args).await
+}
+
+pub const AAB_INSTALL_PROGRESS_EVENT: &str = "aab-install-progress";
+
+/// AAB 安装的签名参数，未指定时 bundletool 使用调试签名
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AabInstallOptions {
+    pub keystore_path: Option<String>,
+    pub keystore_password: Option<String>,
+    pub key_alias: Option<String>,
+    pub key_password: Option<String>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AabInstallProgress {
+    pub device_id: Option<String>,
+    pub aab_path: String,
+    /// building / installing / completed / failed
+    pub stage: String,
+    pub message: Option<String>,
+}
+
+fn emit_aab_progress(
+    app: &AppHandle,
+    device_id: &Option<String>,
+    aab_path: &str,
+    stage: &str,
+    message: Option<String>,
+) {
+    let payload = AabInstallProgress {
+        device_id: device_id.clone(),
+        aab_path: aab_path.to_string(),
+        stage: stage.to_string(),
+        message,
+    };
+    let _ = app.emit(AAB_INSTALL_PROGRESS_EVENT, payload);
+}
+
+/// 通过 bundletool 为已连接设备生成 APK 集并安装
 #[tauri::command]
-pub async fn adb_screenshot(
+pub async fn adb_install_aab(
+    app: AppHandle,
+    device_id: Option<String>,
+    aab_path: String,
+    options: Option<AabInstallOptions>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    if !Path::new(&aab_path).is_file() {
+        return Err(MdtError::invalid_argument("aabPath", format!("文件不存在: {}", aab_path)));
+    }
+    let options = options.unwrap_or_default();
+    if options.keystore_path.is_some() && options.key_alias.is_none() {
+        return Err(MdtError::invalid_argument("keyAlias", "指定 keystore 时必须提供 key alias"));
+    }
+
+    let apks_path = std::env::temp_dir().join(format!("mdt_{}.apks", tools::now_millis()));
+    let result = install_aab(&app, &device_id, &aab_path, &options, &apks_path).await;
+    let _ = std::fs::remove_file(&apks_path);
+
+    match &result {
+        Ok(_) => emit_aab_progress(&app, &device_id, &aab_path, "completed", None),
+        Err(e) => emit_aab_progress(&app, &device_id, &aab_path, "failed", Some(e.to_string())),
+    }
+    result
+}
+
+async fn install_aab(
+    app: &AppHandle,
+    device_id: &Option<String>,
+    aab_path: &str,
+    options: &AabInstallOptions,
+    apks_path: &Path,
+) -> MdtResult<String> {
+    let adb_path = tools::resolve_tool_path("adb")
+        .ok_or_else(|| MdtError::ToolNotFound { tool: "adb".to_string() })?;
+
+    emit_aab_progress(app, device_id, aab_path, "building", None);
+    let mut cmd = tools::bundletool_command();
+    cmd.arg("build-apks")
+        .arg("--connected-device")
+        .arg(format!("--bundle={}", aab_path))
+        .arg(format!("--output={}", apks_path.display()))
+        .arg(format!("--adb={}", adb_path.display()));
+    if let Some(id) = device_id {
+        cmd.arg(format!("--device-id={}", id));
+    }
+    if let Some(keystore) = &options.keystore_path {
+        cmd.arg(format!("--ks={}", keystore));
+        if let Some(alias) = &options.key_alias {
+            cmd.arg(format!("--ks-key-alias={}", alias));
+        }
+        if let Some(password) = &options.keystore_password {
+            cmd.arg(format!("--ks-pass=pass:{}", password));
+        }
+        if let Some(password) = &options.key_password {
+            cmd.arg(format!("--key-pass=pass:{}", password));
+        }
+    }
+    tools::run_checked("bundletool", device_id, cmd).await?;
+
+    emit_aab_progress(app, device_id, aab_path, "installing", None);
+    let mut cmd = tools::bundletool_command();
+    cmd.arg("install-apks")
+        .arg(format!("--apks={}", apks_path.display()))
+        .arg(format!("--adb={}", adb_path.display()));
+    if let Some(id) = device_id {
+        cmd.arg(format!("--device-id={}", id));
+    }
+    let output = tools::run_checked("bundletool", device_id, cmd).await?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// XAPK / APKS / APKM 安装过程中单个步骤的结果
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveInstallStep {
+    pub step: String,
+    pub success: bool,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveInstallResult {
+    pub package_name: Option<String>,
+    pub success: bool,
+    pub steps: Vec<ArchiveInstallStep>,
+}
+
+struct ExtractedArchive {
+    package_name: Option<String>,
+    apks: Vec<PathBuf>,
+    obbs: Vec<PathBuf>,
+}
+
+/// 解压安装包归档，收集其中的 APK、OBB 以及清单中声明的包名
+fn extract_app_archive(archive_path: &Path, target_dir: &Path) -> MdtResult<ExtractedArchive> {
+    let file = std::fs::File::open(archive_path).map_err(|e| MdtError::io("打开安装包", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| MdtError::io("读取安装包", e))?;
+    let mut extracted = ExtractedArchive {
+        package_name: None,
+        apks: Vec::new(),
+        obbs: Vec::new(),
+    };
+
+    for index in 0..archive.len() {
+        let mut entry = archive
+            .by_index(index)
+            .map_err(|e| MdtError::io("读取安装包", e))?;
+        // 忽略包含 `..` 等越界路径的条目
+        let Some(relative) = entry.enclosed_name() else {
+            continue;
+        };
+        if entry.is_dir() {
+            continue;
+        }
+        let name = relative.to_string_lossy().replace('\\', "/");
+        let lower = name.to_lowercase();
+
+        // XAPK: manifest.json / APKM: info.json / APKS (SAI): meta.sai_v2.json
+        if matches!(lower.as_str(), "manifest.json" | "info.json" | "meta.sai_v2.json") {
+            let mut content = String::new();
+            let _ = entry.read_to_string(&mut content);
+            if let Ok(value) = serde_json::from_str::<serde_json::Value>(&content) {
+                extracted.package_name = ["package_name", "pname", "package"]
+                    .iter()
+                    .find_map(|key| value.get(*key).and_then(|v| v.as_str()))
+                    .map(str::to_string)
+                    .or(extracted.package_name.take());
+            }
+            continue;
+        }
+
+        let is_apk = lower.ends_with(".apk");
+        let is_obb = lower.ends_with(".obb");
+        if !is_apk && !is_obb {
+            continue;
+        }
+        let out_path = target_dir.join(&relative);
+        if let Some(parent) = out_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| MdtError::io("创建临时目录", e))?;
+        }
+        let mut out = std::fs::File::create(&out_path).map_err(|e| MdtError::io("解压安装包", e))?;
+        std::io::copy(&mut entry, &mut out).map_err(|e| MdtError::io("解压安装包", e))?;
+        if is_apk {
+            extracted.apks.push(out_path);
+        } else {
+            extracted.obbs.push(out_path);
+        }
+    }
+
+    Ok(extracted)
+}
+
+/// 安装 .xapk / .apks / .apkm 归档：解压、推送 OBB 到 `/sdcard/Android/obb/<包名>/`，再以 install-multiple 安装
+#[tauri::command]
+pub async fn adb_install_archive(
+    device_id: Option<String>,
+    archive_path: String,
+    options: Option<InstallOptions>,
+) -> MdtResult<ArchiveInstallResult> {
+    let _history = history::track();
+    let extension = Path::new(&archive_path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    if !matches!(extension.as_str(), "xapk" | "apks" | "apkm") {
+        return Err(MdtError::invalid_argument(
+            "archivePath",
+            "仅支持 .xapk / .apks / .apkm 文件",
+        ));
+    }
+    if !Path::new(&archive_path).is_file() {
+        return Err(MdtError::invalid_argument(
+            "archivePath",
+            format!("文件不存在: {}", archive_path),
+        ));
+    }
+
+    let temp_dir = std::env::temp_dir().join(format!("mdt_archive_{}", tools::now_millis()));
+    let result = install_archive(&device_id, &archive_path, &temp_dir, options).await;
+    let _ = std::fs::remove_dir_all(&temp_dir);
+    result
+}
+
+async fn install_archive(
+    device_id: &Option<String>,
+    archive_path: &str,
+    temp_dir: &Path,
+    options: Option<InstallOptions>,
+) -> MdtResult<ArchiveInstallResult> {
+    let mut steps = Vec::new();
+    let archive = PathBuf::from(archive_path);
+    let target = temp_dir.to_path_buf();
+    let extracted = tauri::async_runtime::spawn_blocking(move || extract_app_archive(&archive, &target))
+        .await
+        .map_err(|e| MdtError::io("解压安装包", e))?;
+    let mut extracted = match extracted {
+        Ok(extracted) => extracted,
+        Err(e) => {
+            steps.push(ArchiveInstallStep {
+                step: "extract".to_string(),
+                success: false,
+                message: e.to_string(),
+            });
+            return Ok(ArchiveInstallResult {
+                package_name: None,
+                success: false,
+                steps,
+            });
+        }
+    };
+    extracted.apks.sort();
+    steps.push(ArchiveInstallStep {
+        step: "extract".to_string(),
+        success: !extracted.apks.is_empty(),
+        message: format!("{} 个 APK，{} 个 OBB", extracted.apks.len(), extracted.obbs.len()),
+    });
+    if extracted.apks.is_empty() {
+        return Ok(ArchiveInstallResult {
+            package_name: extracted.package_name,
+            success: false,
+            steps,
+        });
+    }
+
+    // 包名优先取自归档清单（XAPK 的 manifest.json 等），缺失时才尝试用 aapt 读取；
+    // 包名只用于确定 OBB 目录，没有 OBB 时不需要
+    let mut package_name = extracted.package_name.take();
+    if package_name.is_none() && !extracted.obbs.is_empty() {
+        package_name = apk::package_name(&extracted.apks[0].to_string_lossy()).await.ok();
+    }
+
+    if !extracted.obbs.is_empty() {
+        let Some(package_name) = package_name.clone() else {
+            steps.push(ArchiveInstallStep {
+                step: "obb".to_string(),
+                success: false,
+                message: "清单中没有包名且无法通过 aapt 读取，无法确定 OBB 目录".to_string(),
+            });
+            return Ok(ArchiveInstallResult {
+                package_name: None,
+                success: false,
+                steps,
+            });
+        };
+        let obb_dir = format!("/sdcard/Android/obb/{}", package_name);
+        adb_shell(device_id, &["mkdir", "-p", &tools::shell_quote(&obb_dir)]).await?;
+        for obb in &extracted.obbs {
+            let file_name = obb
+                .file_name()
+                .map(|name| name.to_string_lossy().to_string())
+                .unwrap_or_default();
+            let remote = format!("{}/{}", obb_dir, file_name);
+            let pushed = adb_run(device_id, &["push", &obb.to_string_lossy(), &remote]).await;
+            let success = pushed.is_ok();
+            steps.push(ArchiveInstallStep {
+                step: format!("push {}", file_name),
+                success,
+                message: match pushed {
+                    Ok(_) => remote,
+                    Err(e) => e.to_string(),
+                },
+            });
+            if !success {
+                return Ok(ArchiveInstallResult {
+                    package_name: Some(package_name),
+                    success: false,
+                    steps,
+                });
+            }
+        }
+    }
+
+    let apk_paths = extracted
+        .apks
+        .iter()
+        .map(|path| path.to_string_lossy().to_string())
+        .collect();
+    let installed = adb_install_multiple(device_id.clone(), apk_paths, options).await;
+    let success = installed.is_ok();
+    steps.push(ArchiveInstallStep {
+        step: "install".to_string(),
+        success,
+        message: match installed {
+            Ok(output) => output.trim().to_string(),
+            Err(e) => e.to_string(),
+        },
+    });
+
+    Ok(ArchiveInstallResult {
+        package_name,
+        success,
+        steps,
+    })
+}
+
+/// 导出已安装应用的 base APK 及全部拆分 APK，返回本地文件路径
+///
+/// 文件命名为 `<包名>.apk`（base）和 `<包名>.<拆分名>.apk`（如 `com.demo.config.arm64_v8a.apk`）。
+#[tauri::command]
+pub async fn adb_export_apk(
+    device_id: Option<String>,
+    package_name: String,
+    output_dir: String,
+) -> MdtResult<Vec<String>> {
+    let _history = history::track();
+    let stdout = adb_shell(&device_id, &["pm", "path", &tools::shell_quote(&package_name)]).await?;
+    let remote_paths: Vec<&str> = stdout
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("package:"))
+        .collect();
+    if remote_paths.is_empty() {
+        return Err(MdtError::invalid_argument(
+            "packageName",
+            format!("设备上未安装 {}", package_name),
+        ));
+    }
+
+    std::fs::create_dir_all(&output_dir).map_err(|e| MdtError::io("创建导出目录", e))?;
+    let mut exported = Vec::new();
+    for remote_path in remote_paths {
+        let stem = remote_path
+            .rsplit('/')
+            .next()
+            .unwrap_or(remote_path)
+            .trim_end_matches(".apk");
+        let file_name = match stem {
+            "base" => format!("{}.apk", package_name),
+            split => format!(
+                "{}.{}.apk",
+                package_name,
+                split.strip_prefix("split_").unwrap_or(split)
+            ),
+        };
+        let local_path = Path::new(&output_dir).join(file_name);
+        let local = local_path.to_string_lossy().to_string();
+        adb_run(&device_id, &["pull", remote_path, &local]).await?;
+        exported.push(local);
+    }
+    Ok(exported)
+}
+
+/// 卸载应用；指定 `user_id` 时仅从该用户（如工作资料）中卸载
+#[tauri::command]
+pub async fn adb_uninstall(
+    device_id: Option<String>,
+    package_name: String,
+    user_id: Option<u32>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let Some(user_id) = user_id else {
+        return adb_run(&device_id, &["uninstall", &package_name]).await;
+    };
+    let user = user_id.to_string();
+    let output = adb_shell(
+        &device_id,
+        &["pm", "uninstall", "--user", &user, &tools::shell_quote(&package_name)],
+    )
+    .await?;
+    expect_pm_success(&format!("pm uninstall --user {} {}", user, package_name), output)
+}
+
+/// 为指定用户安装设备上已存在的应用（`pm install-existing`），常用于向工作资料添加应用
+#[tauri::command]
+pub async fn adb_install_existing(
+    device_id: Option<String>,
+    package_name: String,
+    user_id: u32,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let user = user_id.to_string();
+    let output = adb_shell(
+        &device_id,
+        &["pm", "install-existing", "--user", &user, &tools::shell_quote(&package_name)],
+    )
+    .await?;
+    // 成功输出 `Package com.demo installed for user: 10`
+    if output.contains("installed for user") {
+        Ok(output)
+    } else {
+        Err(MdtError::CommandFailed {
+            command: format!("pm install-existing --user {} {}", user, package_name),
+            exit_code: None,
+            stderr: output,
+        })
+    }
+}
+
+/// 从多台设备上卸载同一个包，返回以设备 ID 为键的结果
+#[tauri::command]
+pub async fn adb_uninstall_batch(
+    app: AppHandle,
+    device_ids: Option<Vec<String>>,
+    group: Option<String>,
+    package_name: String,
+    parallel: Option<usize>,
+) -> MdtResult<HashMap<String, BatchDeviceResult>> {
+    let _history = history::track();
+    let device_ids = groups::resolve_devices(device_ids, group)?;
+    let results = batch::run_batch(&app, "uninstall", device_ids, parallel, move |device_id| {
+        let package_name = package_name.clone();
+        async move { adb_run(&Some(device_id), &["uninstall", &package_name]).await }
+    })
+    .await;
+    Ok(results
+        .into_iter()
+        .map(|result| (result.device_id.clone(), result))
+        .collect())
+}
+
+/// `dumpsys package <pkg>` 解析出的应用详情
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageDetails {
+    pub package_name: String,
+    pub uid: Option<u32>,
+    pub version_code: Option<i64>,
+    pub version_name: Option<String>,
+    pub min_sdk: Option<u32>,
+    pub target_sdk: Option<u32>,
+    pub first_install_time: Option<String>,
+    pub last_update_time: Option<String>,
+    pub installer: Option<String>,
+    pub code_path: Option<String>,
+    pub data_dir: Option<String>,
+    pub requested_permissions: Vec<String>,
+    pub granted_permissions: Vec<String>,
+    pub activities: Vec<String>,
+    /// 当前用户下是否启用，`enabled=2/3/4` 视为禁用
+    pub enabled: Option<bool>,
+}
+
+fn parse_package_details(package: &str, output: &str) -> Option<PackageDetails> {
+    let mut details = PackageDetails {
+        package_name: package.to_string(),
+        ..Default::default()
+    };
+    let header = format!("Package [{}]", package);
+    let component_prefix = format!("{}/", package);
+    let mut in_package = false;
+    let mut found = false;
+    // 当前所在的权限小节（requested / granted）及其标题的缩进
+    let mut section = "";
+    let mut section_indent = 0;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("Package [") {
+            in_package = trimmed.starts_with(&header);
+            found |= in_package;
+            section = "";
+            continue;
+        }
+
+        if !in_package {
+            // Activity Resolver Table 中的组件，如 `a1b2c3 com.demo/.MainActivity filter ...`
+            for token in trimmed.split_whitespace() {
+                if let Some(class) = token.strip_prefix(&component_prefix) {
+                    let class = if class.starts_with('.') {
+                        format!("{}{}", package, class)
+                    } else {
+                        class.to_string()
+                    };
+                    if !details.activities.contains(&class) {
+                        details.activities.push(class);
+                    }
+                }
+            }
+            continue;
+        }
+
+        let indent = line.len() - line.trim_start().len();
+        if !section.is_empty() && indent <= section_indent {
+            section = "";
+        }
+        if trimmed == "requested permissions:" {
+            section = "requested";
+            section_indent = indent;
+            continue;
+        }
+        if trimmed == "install permissions:" || trimmed == "runtime permissions:" {
+            section = "granted";
+            section_indent = indent;
+            continue;
+        }
+        match section {
+            "requested" => {
+                let name = trimmed.split(':').next().unwrap_or(trimmed);
+                details.requested_permissions.push(name.to_string());
+                continue;
+            }
+            "granted" => {
+                if let Some((name, rest)) = trimmed.split_once(": granted=") {
+                    if rest.starts_with("true") && !details.granted_permissions.iter().any(|p| p == name) {
+                        details.granted_permissions.push(name.to_string());
+                    }
+                }
+                continue;
+            }
+            _ => {}
+        }
+
+        for field in trimmed.split_whitespace() {
+            let Some((key, value)) = field.split_once('=') else {
+                continue;
+            };
+            match key {
+                "userId" | "appId" => details.uid = details.uid.or(value.parse().ok()),
+                "versionCode" => details.version_code = value.parse().ok(),
+                "minSdk" => details.min_sdk = value.parse().ok(),
+                "targetSdk" => details.target_sdk = value.parse().ok(),
+                "codePath" => details.code_path = Some(value.to_string()),
+                "dataDir" => details.data_dir = Some(value.to_string()),
+                "installerPackageName" => details.installer = Some(value.to_string()),
+                "enabled" if details.enabled.is_none() => {
+                    details.enabled = Some(matches!(value, "0" | "1"));
+                }
+                _ => {}
+            }
+        }
+        // 以下字段的值可能包含空格，按整行解析
+        if let Some(value) = trimmed.strip_prefix("versionName=") {
+            details.version_name = Some(value.to_string());
+        } else if let Some(value) = trimmed.strip_prefix("firstInstallTime=") {
+            details.first_install_time = Some(value.to_string());
+        } else if let Some(value) = trimmed.strip_prefix("lastUpdateTime=") {
+            details.last_update_time = Some(value.to_string());
+        }
+    }
+
+    found.then_some(details)
+}
+
+/// 查询应用详情：版本、安装时间、安装来源、数据目录、权限、Activity 与启用状态
+#[tauri::command]
+pub async fn adb_package_info(device_id: Option<String>, package: String) -> MdtResult<PackageDetails> {
+    let output = adb_shell(&device_id, &["dumpsys", "package", &tools::shell_quote(&package)]).await?;
+    parse_package_details(&package, &output).ok_or_else(|| {
+        MdtError::invalid_argument("package", format!("设备上未安装 {}", package))
+    })
+}
+
+/// 确认设备上已安装指定应用
+async fn adb_ensure_package(device_id: &Option<String>, package: &str) -> MdtResult<()> {
+    let output = adb_shell(device_id, &["pm", "path", &tools::shell_quote(package)])
+        .await
+        .unwrap_or_default();
+    if output.lines().any(|line| line.starts_with("package:")) {
+        Ok(())
+    } else {
+        Err(MdtError::invalid_argument("package", format!("设备上未安装 {}", package)))
+    }
+}
+
+/// `pm` 系列命令失败时多数仍以 0 退出，需要检查输出中的 `Success`
+pub(crate) fn expect_pm_success(command: &str, output: String) -> MdtResult<String> {
+    if output.contains("Success") {
+        Ok(output)
+    } else {
+        Err(MdtError::CommandFailed {
+            command: command.to_string(),
+            exit_code: None,
+            stderr: output,
+        })
+    }
+}
+
+/// 清除应用全部数据（`pm clear`），相当于重新安装后的初始状态
+#[tauri::command]
+pub async fn adb_clear_app_data(device_id: Option<String>, package: String) -> MdtResult<String> {
+    let _history = history::track();
+    let quoted = tools::shell_quote(&package);
+    let output = adb_shell(&device_id, &["pm", "clear", &quoted]).await?;
+    expect_pm_success(&format!("pm clear {}", package), output)
+}
+
+/// 仅清除应用缓存：优先使用 `pm clear --cache-only`（Android 14+），
+/// 不支持时对可调试应用通过 `run-as` 删除 cache / code_cache 目录
+#[tauri::command]
+pub async fn adb_clear_app_cache(device_id: Option<String>, package: String) -> MdtResult<String> {
+    let _history = history::track();
+    let quoted = tools::shell_quote(&package);
+    if let Ok(output) = adb_shell(&device_id, &["pm", "clear", "--cache-only", &quoted]).await {
+        if output.contains("Success") {
+            return Ok(output);
+        }
+    }
+    // 非可调试应用或未安装时 run-as 输出 `run-as: package not debuggable` 等，退出码不一定非 0
+    let detail = match adb_shell(
+        &device_id,
+        &["run-as", &quoted, "sh", "-c", "'rm -rf cache/* code_cache/*'"],
+    )
+    .await
+    {
+        Ok(output) => match output.lines().find(|line| line.trim_start().starts_with("run-as:")) {
+            Some(error) => error.trim().to_string(),
+            None => return Ok(output),
+        },
+        Err(err) if err.to_string().contains("run-as:") => err.to_string(),
+        Err(err) => return Err(err),
+    };
+    Err(MdtError::unsupported(
+        "仅清除应用缓存",
+        format!("`pm clear --cache-only` 不可用且无法通过 run-as 访问 {}: {}", package, detail),
+    ))
+}
+
+/// 强制停止应用（`am force-stop`）
+#[tauri::command]
+pub async fn adb_force_stop(device_id: Option<String>, package: String) -> MdtResult<()> {
+    let _history = history::track();
+    adb_ensure_package(&device_id, &package).await?;
+    adb_shell(&device_id, &["am", "force-stop", &tools::shell_quote(&package)]).await?;
+    Ok(())
+}
+
+/// 列出设备上运行中的进程
+#[tauri::command]
+pub async fn adb_list_processes(device_id: Option<String>) -> MdtResult<Vec<ProcessInfo>> {
+    let output = adb_shell(&device_id, &process::PS_ARGS).await?;
+    Ok(process::parse_ps(&output))
+}
+
+/// 按 PID 结束进程，非调试设备上通常只能结束 shell 用户的进程
+#[tauri::command]
+pub async fn adb_kill_process(device_id: Option<String>, pid: u32) -> MdtResult<()> {
+    let _history = history::track();
+    adb_shell(&device_id, &["kill", &pid.to_string()]).await?;
+    Ok(())
+}
+
+/// 解析应用的启动 Activity（`cmd package resolve-activity`），返回 `包名/类名`
+async fn adb_resolve_launcher(device_id: &Option<String>, package: &str) -> Option<String> {
+    let output = adb_shell(
+        device_id,
+        &[
+            "cmd",
+            "package",
+            "resolve-activity",
+            "--brief",
+            "-c",
+            "android.intent.category.LAUNCHER",
+            &tools::shell_quote(package),
+        ],
+    )
+    .await
+    .ok()?;
+    // 输出最后一行为组件名，找不到时为 "No activity found"
+    output
+        .lines()
+        .last()
+        .map(str::trim)
+        .filter(|line| line.contains('/'))
+        .map(str::to_string)
+}
+
+/// 启动应用的主 Activity，无法解析时退回 `monkey -p <pkg> 1`
+#[tauri::command]
+pub async fn adb_launch_app(device_id: Option<String>, package: String) -> MdtResult<String> {
+    let _history = history::track();
+    adb_ensure_package(&device_id, &package).await?;
+    if let Some(component) = adb_resolve_launcher(&device_id, &package).await {
+        return adb_shell(&device_id, &["am", "start", "-n", &tools::shell_quote(&component)]).await;
+    }
+    adb_shell(
+        &device_id,
+        &[
+            "monkey",
+            "-p",
+            &tools::shell_quote(&package),
+            "-c",
+            "android.intent.category.LAUNCHER",
+            "1",
+        ],
+    )
+    .await
+}
+
+const MAX_LAUNCH_ITERATIONS: u32 = 50;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchSample {
+    pub total_time: Option<u64>,
+    pub wait_time: Option<u64>,
+    /// COLD / WARM / HOT，旧系统不输出时为空
+    pub launch_state: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimingStats {
+    pub min: u64,
+    pub avg: f64,
+    pub max: u64,
+}
+
+impl TimingStats {
+    fn from_values(values: &[u64]) -> Option<Self> {
+        Some(TimingStats {
+            min: *values.iter().min()?,
+            avg: values.iter().sum::<u64>() as f64 / values.len() as f64,
+            max: *values.iter().max()?,
+        })
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LaunchTimeStats {
+    pub package: String,
+    pub component: String,
+    pub cold: bool,
+    pub samples: Vec<LaunchSample>,
+    pub total_time: Option<TimingStats>,
+    pub wait_time: Option<TimingStats>,
+}
+
+fn parse_launch_sample(output: &str) -> LaunchSample {
+    let mut sample = LaunchSample {
+        total_time: None,
+        wait_time: None,
+        launch_state: None,
+    };
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "TotalTime" => sample.total_time = value.parse().ok(),
+            "WaitTime" => sample.wait_time = value.parse().ok(),
+            "LaunchState" => sample.launch_state = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    sample
+}
+
+/// 多次执行 `am start -W` 统计启动耗时；冷启动每轮前强制停止应用，热启动每轮前回到桌面
+#[tauri::command]
+pub async fn adb_measure_launch_time(
+    device_id: Option<String>,
+    package: String,
+    iterations: u32,
+    cold: Option<bool>,
+) -> MdtResult<LaunchTimeStats> {
+    let _history = history::track();
+    if iterations == 0 || iterations > MAX_LAUNCH_ITERATIONS {
+        return Err(MdtError::invalid_argument(
+            "iterations",
+            format!("次数需在 1 到 {} 之间", MAX_LAUNCH_ITERATIONS),
+        ));
+    }
+    adb_ensure_package(&device_id, &package).await?;
+    let component = adb_resolve_launcher(&device_id, &package)
+        .await
+        .ok_or_else(|| MdtError::invalid_argument("package", format!("{} 没有可启动的 Activity", package)))?;
+    let cold = cold.unwrap_or(true);
+    let quoted_package = tools::shell_quote(&package);
+    let quoted_component = tools::shell_quote(&component);
+
+    let mut samples = Vec::new();
+    for _ in 0..iterations {
+        if cold {
+            adb_shell(&device_id, &["am", "force-stop", &quoted_package]).await?;
+        } else {
+            adb_shell(&device_id, &["input", "keyevent", "KEYCODE_HOME"]).await?;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let output = adb_shell(&device_id, &["am", "start", "-W", "-n", &quoted_component]).await?;
+        samples.push(parse_launch_sample(&output));
+    }
+
+    let total: Vec<u64> = samples.iter().filter_map(|s| s.total_time).collect();
+    let wait: Vec<u64> = samples.iter().filter_map(|s| s.wait_time).collect();
+    Ok(LaunchTimeStats {
+        package,
+        component,
+        cold,
+        total_time: TimingStats::from_values(&total),
+        wait_time: TimingStats::from_values(&wait),
+        samples,
+    })
+}
+
+#[tauri::command]
+pub async fn adb_list_packages(device_id: Option<String>, user_id: Option<u32>) -> MdtResult<Vec<String>> {
+    let user = user_id.map(|id| id.to_string());
+    let mut args = vec!["shell", "pm", "list", "packages"];
+    if let Some(user) = &user {
+        args.extend(["--user", user]);
+    }
+    let stdout = adb_run(&device_id, &args)
+    .await?;
+
+    let packages: Vec<String> = stdout
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.starts_with("package:") {
+                Some(line.replace("package:", "").trim().to_string())
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    Ok(packages)
+}
+
+/// bugreport 生成进度事件名
+pub const BUGREPORT_PROGRESS_EVENT: &str = "bugreport-progress";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BugreportProgress {
+    pub device_id: Option<String>,
+    pub percent: u8,
+    pub message: String,
+}
+
+/// 解析 `[ 42%] generating bugreport-xxx.zip` 形式的进度行
+fn parse_bugreport_progress(line: &str) -> Option<u8> {
+    let rest = line.trim().strip_prefix('[')?;
+    let (percent, _) = rest.split_once("%]")?;
+    percent.trim().parse().ok()
+}
+
+/// 生成 bugreport 并保存到本地（文件或目录），通过事件推送进度，返回最终的 zip 路径
+#[tauri::command]
+pub async fn adb_bugreport(
+    app: AppHandle,
+    device_id: Option<String>,
+    output_path: String,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let mut cmd = adb_command(&device_id);
+    cmd.args(["bugreport", &output_path]);
+    let command = tools::describe("adb", &cmd);
+    let invocation = history::capture(&cmd);
+    let started = Instant::now();
+    let mut child = tokio::process::Command::from(cmd)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| MdtError::spawn("adb", e))?;
+
+    let stderr_task = tools::drain_pipe(child.stderr.take());
+
+    // 进度行以 \r 刷新，需要按 \r / \n 切分
+    let mut stdout = String::new();
+    let mut pending = String::new();
+    if let Some(mut out) = child.stdout.take() {
+        let mut buf = [0u8; 4096];
+        loop {
+            let read = tokio::io::AsyncReadExt::read(&mut out, &mut buf)
+                .await
+                .map_err(|e| MdtError::io("读取 bugreport 输出", e))?;
+            if read == 0 {
+                break;
+            }
+            pending.push_str(&String::from_utf8_lossy(&buf[..read]));
+            while let Some(index) = pending.find(['\r', '\n']) {
+                let line: String = pending.drain(..=index).collect();
+                let line = line.trim();
+                if let Some(percent) = parse_bugreport_progress(line) {
+                    let payload = BugreportProgress {
+                        device_id: device_id.clone(),
+                        percent,
+                        message: line.to_string(),
+                    };
+                    let _ = app.emit(BUGREPORT_PROGRESS_EVENT, payload);
+                } else if !line.is_empty() {
+                    stdout.push_str(line);
+                    stdout.push('\n');
+                }
+            }
+        }
+    }
+    stdout.push_str(&pending);
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| MdtError::io("等待 bugreport", e))?;
+    let output = std::process::Output {
+        status,
+        stdout: stdout.into_bytes(),
+        stderr: stderr_task.await.unwrap_or_default(),
+    };
+    history::record(invocation, started.elapsed(), Ok(&output));
+    if !output.status.success() {
+        return Err(MdtError::from_output(command, &device_id, &output));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    // 成功时输出 `Bug report copied to /path/bugreport-xxx.zip`
+    Ok(stdout
+        .lines()
+        .find_map(|line| line.split_once("copied to ").map(|(_, path)| path.trim().to_string()))
+        .unwrap_or(output_path))
+}
+
+/// sideload 进度事件名
+pub const SIDELOAD_PROGRESS_EVENT: &str = "sideload-progress";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SideloadProgress {
+    pub device_id: Option<String>,
+    pub percent: u8,
+}
+
+/// 解析 `serving: 'ota.zip'  (~47%)` 形式的进度行
+fn parse_sideload_progress(line: &str) -> Option<u8> {
+    let (_, rest) = line.split_once("(~")?;
+    let (percent, _) = rest.split_once('%')?;
+    percent.trim().parse().ok()
+}
+
+/// 向处于 sideload 模式（recovery 中的 Apply update from ADB）的设备推送 OTA 包，通过事件推送进度
+#[tauri::command]
+pub async fn adb_sideload(app: AppHandle, device_id: Option<String>, ota_zip: String) -> MdtResult<String> {
+    let _history = history::track();
+    if !Path::new(&ota_zip).is_file() {
+        return Err(MdtError::invalid_argument("otaZip", format!("OTA 包不存在: {}", ota_zip)));
+    }
+    let state = adb_run(&device_id, &["get-state"]).await.unwrap_or_default();
+    if state.trim() != "sideload" {
+        return Err(MdtError::invalid_argument(
+            "deviceId",
+            format!("设备未处于 sideload 模式（当前: {}），请先重启到 sideload", state.trim()),
+        ));
+    }
+
+    let mut cmd = adb_command(&device_id);
+    cmd.args(["sideload", &ota_zip]);
+    let command = tools::describe("adb", &cmd);
+    let invocation = history::capture(&cmd);
+    let started = Instant::now();
+    let mut child = tokio::process::Command::from(cmd)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| MdtError::spawn("adb", e))?;
+
+    let stderr_task = tools::drain_pipe(child.stderr.take());
+
+    // 进度行以 \r 刷新，需要按 \r / \n 切分
+    let mut stdout = String::new();
+    let mut pending = String::new();
+    let mut last_percent = None;
+    if let Some(mut out) = child.stdout.take() {
+        let mut buf = [0u8; 4096];
+        loop {
+            let read = tokio::io::AsyncReadExt::read(&mut out, &mut buf)
+                .await
+                .map_err(|e| MdtError::io("读取 sideload 输出", e))?;
+            if read == 0 {
+                break;
+            }
+            pending.push_str(&String::from_utf8_lossy(&buf[..read]));
+            while let Some(index) = pending.find(['\r', '\n']) {
+                let line: String = pending.drain(..=index).collect();
+                let line = line.trim();
+                if let Some(percent) = parse_sideload_progress(line) {
+                    if last_percent != Some(percent) {
+                        last_percent = Some(percent);
+                        let payload = SideloadProgress {
+                            device_id: device_id.clone(),
+                            percent,
+                        };
+                        let _ = app.emit(SIDELOAD_PROGRESS_EVENT, payload);
+                    }
+                } else if !line.is_empty() {
+                    stdout.push_str(line);
+                    stdout.push('\n');
+                }
+            }
+        }
+    }
+    stdout.push_str(&pending);
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| MdtError::io("等待 sideload", e))?;
+    let output = std::process::Output {
+        status,
+        stdout: stdout.into_bytes(),
+        stderr: stderr_task.await.unwrap_or_default(),
+    };
+    history::record(invocation, started.elapsed(), Ok(&output));
+    if !output.status.success() {
+        return Err(MdtError::from_output(command, &device_id, &output));
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    println!("[adb] sideload finished: {}", stdout.trim());
+    Ok(stdout.trim().to_string())
+}
+
+/// 收集 `/data/anr/` 下的 ANR trace，返回本地文件列表
+#[tauri::command]
+pub async fn adb_pull_anr_traces(device_id: Option<String>, output_dir: String) -> MdtResult<Vec<String>> {
+    let _history = history::track();
+    adb_collect_protected(&device_id, "/data/anr", &output_dir).await
+}
+
+/// 按设置的目录与文件名模板生成截图 / 录屏的默认本地路径
+async fn default_capture_path(
+    device_id: &Option<String>,
+    kind: config::OutputKind,
+    timestamp: u64,
+    extension: &str,
+) -> MdtResult<String> {
+    let model = if config::template_uses_model() {
+        match device_info_cache().get(&tools::device_key(device_id)).and_then(|info| info.model) {
+            Some(model) => Some(model),
+            None => adb_shell(device_id, &["getprop", "ro.product.model"]).await.ok(),
+        }
+    } else {
+        None
+    };
+    config::capture_output_path(kind, device_id, model.as_deref(), timestamp, extension)
+}
+
+/// 长截图最多滚动的次数
+const MAX_LONG_SCREENSHOT_SCROLLS: u32 = 30;
+
+/// 通过 `exec-out screencap -p` 截取 PNG 数据，`display_id` 为逻辑显示器 ID
+async fn capture_screenshot(device_id: &Option<String>, display_id: Option<u32>) -> MdtResult<Vec<u8>> {
+    let display = display::capture_display_arg(device_id, display_id).await?;
+    let mut cmd = adb_command(device_id);
+    cmd.args(["exec-out", "screencap", "-p"]);
+    if let Some(display) = display {
+        cmd.args(["-d", &display]);
+    }
+    Ok(tools::run_checked("adb", device_id, cmd).await?.stdout)
+}
+
+/// 截图并按选项转换格式 / 缩放后保存，返回本地路径
+#[tauri::command]
+pub async fn adb_screenshot(
+    device_id: Option<String>,
+    output_path: Option<String>,
+    options: Option<ScreenshotOptions>,
+    display_id: Option<u32>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let options = options.unwrap_or_default();
+    let extension = options.format.extension();
+    let png = capture_screenshot(&device_id, display_id).await?;
+    let encoded = screenshot::encode(png, options).await?;
+
+    // 确定输出路径
+    let final_path = if let Some(path) = output_path {
+        path
+    } else {
+        // 未指定路径时按设置的截图目录与文件名模板生成
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        default_capture_path(&device_id, config::OutputKind::Screenshot, timestamp, extension).await?
+    };
+
+    // 将截图数据写入文件
+    std::fs::write(&final_path, &encoded.bytes)
+        .map_err(|e| MdtError::io("写入截图文件", e))?;
+    history::attach_output("adb", &device_id, &final_path);
+
+    Ok(final_path)
+}
+
+/// 截图但不写入磁盘，返回 base64 编码的图片供界面即时预览
+#[tauri::command]
+pub async fn adb_screenshot_preview(
+    device_id: Option<String>,
+    options: Option<ScreenshotOptions>,
+    display_id: Option<u32>,
+) -> MdtResult<ScreenshotPreview> {
+    let png = capture_screenshot(&device_id, display_id).await?;
+    screenshot::build_preview(png, options.unwrap_or_default()).await
+}
+
+/// 保存预览得到的截图数据，未指定路径时按截图目录与文件名模板生成（扩展名取自 `format`）
+#[tauri::command]
+pub async fn adb_save_screenshot(
+    device_id: Option<String>,
+    data: String,
+    output_path: Option<String>,
+    format: Option<ScreenshotFormat>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let final_path = match output_path {
+        Some(path) => path,
+        None => {
+            let timestamp = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let extension = format.unwrap_or_default().extension();
+            default_capture_path(&device_id, config::OutputKind::Screenshot, timestamp, extension).await?
+        }
+    };
+    screenshot::write_preview(&data, &final_path)?;
+    Ok(final_path)
+}
+
+/// 截图并直接放入本机剪贴板，便于粘贴到 IM / 缺陷单
+#[tauri::command]
+pub async fn adb_copy_screenshot_to_clipboard(device_id: Option<String>, display_id: Option<u32>) -> MdtResult<()> {
+    let _history = history::track();
+    let png = capture_screenshot(&device_id, display_id).await?;
+    screenshot::copy_to_host_clipboard(png).await
+}
+
+/// 长截图：截图后向上滑动，重复 `scroll_count` 次，再按重叠区域拼接为一张 PNG
+#[tauri::command]
+pub async fn adb_long_screenshot(
+    device_id: Option<String>,
+    scroll_count: u32,
+    output_path: Option<String>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    if scroll_count == 0 || scroll_count > MAX_LONG_SCREENSHOT_SCROLLS {
+        return Err(MdtError::invalid_argument(
+            "scrollCount",
+            format!("滚动次数应在 1-{} 之间", MAX_LONG_SCREENSHOT_SCROLLS),
+        ));
+    }
+
+    let first = capture_screenshot(&device_id, None).await?;
+    // 解码整张截图较耗时，放到阻塞线程中执行
+    let (first, decoded) = tauri::async_runtime::spawn_blocking(move || {
+        let decoded = image::load_from_memory(&first).map(|image| (image.width(), image.height()));
+        (first, decoded)
+    })
+    .await
+    .map_err(|e| MdtError::io("解析截图", e))?;
+    let (width, height) = decoded.map_err(|e| MdtError::io("解析截图", e))?;
+    // 在屏幕中部从 70% 滑到 30%，保证相邻两帧之间有足够的重叠
+    let x = (width / 2).to_string();
+    let from_y = (height * 7 / 10).to_string();
+    let to_y = (height * 3 / 10).to_string();
+
+    let mut frames = vec![first];
+    for _ in 0..scroll_count {
+        adb_shell(&device_id, &["input", "swipe", &x, &from_y, &x, &to_y, "600"]).await?;
+        // 等待惯性滚动停止
+        tokio::time::sleep(Duration::from_millis(800)).await;
+        let frame = capture_screenshot(&device_id, None).await?;
+        // 与上一帧完全相同说明已经到底
+        if frames.last() == Some(&frame) {
+            break;
+        }
+        frames.push(frame);
+    }
+    let captured = frames.len();
+    let (png, _, stitched_height) = screenshot::stitch_vertical(frames).await?;
+
+    let final_path = match output_path {
+        Some(path) => path,
+        None => {
+            let timestamp = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            default_capture_path(&device_id, config::OutputKind::Screenshot, timestamp, "png").await?
+        }
+    };
+    std::fs::write(&final_path, &png).map_err(|e| MdtError::io("写入截图文件", e))?;
+    println!("[adb] long screenshot: {} frames, {}x{}", captured, width, stitched_height);
+    Ok(final_path)
+}
+
+/// 定时截图的逐帧事件名
+pub const SCREENSHOT_SERIES_EVENT: &str = "screenshot-series-frame";
+
+/// 定时截图的最小间隔
+const MIN_SERIES_INTERVAL_MS: u64 = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotSeriesFrame {
+    pub device_id: Option<String>,
+    /// 从 1 开始的帧序号
+    pub index: u32,
+    pub path: Option<String>,
+    pub error: Option<String>,
+    pub timestamp: u64,
+    /// 最后一个事件为 true（达到次数、被停止或设备断开）
+    pub finished: bool,
+}
+
+/// 按固定间隔截图到 `output_dir`，每帧推送 `screenshot-series-frame` 事件；
+/// 未指定 `count` 时持续截图直到调用 `adb_stop_screenshot_series`
+#[tauri::command]
+pub async fn adb_start_screenshot_series(
+    app: AppHandle,
+    device_id: Option<String>,
+    interval_ms: u64,
+    count: Option<u32>,
+    output_dir: String,
+    options: Option<ScreenshotOptions>,
+) -> MdtResult<()> {
+    let _history = history::track();
+    if count == Some(0) {
+        return Err(MdtError::invalid_argument("count", "截图次数必须大于 0"));
+    }
+    std::fs::create_dir_all(&output_dir).map_err(|e| MdtError::io("创建输出目录", e))?;
+    let interval = Duration::from_millis(interval_ms.max(MIN_SERIES_INTERVAL_MS));
+    let options = options.unwrap_or_default();
+    let device_key = tools::device_key(&device_id);
+    let stop_flag = perf::register_monitor("screenshot series", &device_key)?;
+
+    tauri::async_runtime::spawn(async move {
+        let mut index = 0u32;
+        let mut consecutive_errors = 0;
+        loop {
+            index += 1;
+            let started = Instant::now();
+            let timestamp = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+            let path = Path::new(&output_dir)
+                .join(format!("frame_{:04}_{}.{}", index, timestamp, options.format.extension()))
+                .to_string_lossy()
+                .to_string();
+            let result = adb_screenshot(device_id.clone(), Some(path), Some(options.clone()), None).await;
+            consecutive_errors = if result.is_ok() { 0 } else { consecutive_errors + 1 };
+            // 连续失败多次视为设备已断开
+            let finished = count.is_some_and(|count| index >= count)
+                || stop_flag.load(Ordering::SeqCst)
+                || consecutive_errors >= 3;
+            let (path, error) = match result {
+                Ok(path) => (Some(path), None),
+                Err(err) => (None, Some(err.to_string())),
+            };
+            let _ = app.emit(
+                SCREENSHOT_SERIES_EVENT,
+                ScreenshotSeriesFrame {
+                    device_id: device_id.clone(),
+                    index,
+                    path,
+                    error,
+                    timestamp,
+                    finished,
+                },
+            );
+            if finished {
+                break;
+            }
+
+            // 分段等待，以便及时响应停止
+            let deadline = started + interval;
+            while !stop_flag.load(Ordering::SeqCst) && Instant::now() < deadline {
+                tokio::time::sleep(deadline.saturating_duration_since(Instant::now()).min(Duration::from_millis(200))).await;
+            }
+            if stop_flag.load(Ordering::SeqCst) {
+                let _ = app.emit(
+                    SCREENSHOT_SERIES_EVENT,
+                    ScreenshotSeriesFrame {
+                        device_id: device_id.clone(),
+                        index,
+                        path: None,
+                        error: None,
+                        timestamp,
+                        finished: true,
+                    },
+                );
+                break;
+            }
+        }
+        perf::finish_monitor("screenshot series", &device_key, &stop_flag);
+        println!("[adb] screenshot series stopped for {} after {} frames", device_key, index);
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn adb_stop_screenshot_series(device_id: Option<String>) -> MdtResult<()> {
+    perf::stop_monitor("screenshot series", &tools::device_key(&device_id))
+}
+
+/// 在多台设备（或一个分组）上同时截图，文件保存为 `<output_dir>/<设备>_<时间戳>.<扩展名>`
+#[tauri::command]
+pub async fn adb_screenshot_batch(
+    app: AppHandle,
+    device_ids: Option<Vec<String>>,
+    group: Option<String>,
+    output_dir: String,
+    options: Option<ScreenshotOptions>,
+    parallel: Option<usize>,
+) -> MdtResult<Vec<BatchDeviceResult>> {
+    let _history = history::track();
+    let device_ids = groups::resolve_devices(device_ids, group)?;
+    std::fs::create_dir_all(&output_dir).map_err(|e| MdtError::io("创建输出目录", e))?;
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let options = options.unwrap_or_default();
+
+    Ok(batch::run_batch(&app, "screenshot", device_ids, parallel, move |device_id| {
+        let path = Path::new(&output_dir)
+            .join(format!(
+                "{}_{}.{}",
+                batch::file_safe_name(&device_id),
+                timestamp,
+                options.format.extension()
+            ))
+            .to_string_lossy()
+            .to_string();
+        let options = options.clone();
+        async move { adb_screenshot(Some(device_id), Some(path), Some(options), None).await }
+    })
+    .await)
+}
+
+/// screenrecord 单段录制的时长上限（秒）
+const SCREENRECORD_SEGMENT_SECS: u32 = 180;
+
+/// 以 SIGINT 结束设备上指定 pid 的 screenrecord，screenrecord 会写完 MP4 索引后退出
+async fn interrupt_screenrecord(device_id: &Option<String>, pid: u32) {
+    let _ = adb_shell(device_id, &["kill", "-2", &pid.to_string()]).await;
+}
+
+/// 分段录制：循环启动 screenrecord，每段自然结束后立即开始下一段，直到收到停止信号。
+/// 每段先由 shell 输出自身 pid 再 exec 为 screenrecord，停止时只结束本会话的进程
+async fn run_record_segments(
+    device_id: Option<String>,
+    base_args: Vec<String>,
+    timestamp: u64,
+    stop_flag: Arc<AtomicBool>,
+    remote_paths: Arc<Mutex<Vec<String>>>,
+    current_pid: Arc<AtomicU32>,
+) {
+    use std::process::Stdio;
+    use tokio::io::AsyncBufReadExt;
+
+    let args: Vec<String> = base_args.iter().map(|arg| tools::shell_quote(arg)).collect();
+    let mut index = 0;
+    while !stop_flag.load(Ordering::SeqCst) {
+        index += 1;
+        let remote_path = format!("/sdcard/screenrecord_{}_{}.mp4", timestamp, index);
+        let script = format!(
+            "echo $$; exec screenrecord {} --time-limit {} {}",
+            args.join(" "),
+            SCREENRECORD_SEGMENT_SECS,
+            tools::shell_quote(&remote_path)
+        );
+        let mut cmd = adb_command(&device_id);
+        cmd.args(["shell", &script]);
+        let spawned = tokio::process::Command::from(cmd)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn();
+        let mut child = match spawned {
+            Ok(child) => child,
+            Err(err) => {
+                println!("[adb] start screenrecord segment failed: {}", err);
+                break;
+            }
+        };
+        if let Ok(mut paths) = remote_paths.lock() {
+            paths.push(remote_path);
+        }
+        let started = Instant::now();
+        // 保留 stdout 直到分段结束，第一行为设备端 pid
+        let mut stdout = child.stdout.take().map(|out| tokio::io::BufReader::new(out).lines());
+        let pid = match stdout.as_mut() {
+            Some(lines) => lines.next_line().await.ok().flatten().and_then(|line| line.trim().parse().ok()),
+            None => None,
+        };
+        if let Some(pid) = pid {
+            current_pid.store(pid, Ordering::SeqCst);
+            // 停止信号在读取 pid 期间到达时，由这里结束刚启动的分段
+            if stop_flag.load(Ordering::SeqCst) {
+                interrupt_screenrecord(&device_id, pid).await;
+            }
+        }
+        let _ = child.wait().await;
+        current_pid.store(0, Ordering::SeqCst);
+        // screenrecord 启动即退出（参数不被支持、设备断开等），避免空转
+        if started.elapsed() < Duration::from_secs(1) && !stop_flag.load(Ordering::SeqCst) {
+            println!("[adb] screenrecord segment {} exited immediately, stop chaining", index);
+            break;
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn adb_start_screenrecord(
     device_id: Option<String>,
-    output_path: Option<String>,
-) -> Result<String, String> {
-    use std::process::Command;
+    display_id: Option<u32>,
+    options: Option<RecordOptions>,
+) -> MdtResult<String> {
+    use std::process::Stdio;
+    let _history = history::track();
 
-    let mut cmd = tools::command_for("adb");
-    
-    if let Some(device) = device_id {
-        cmd.args(&["-s", &device]);
+    let options = options.unwrap_or_default();
+    if options.prefers_scrcpy() {
+        return adb_start_scrcpy_record(device_id, display_id, Some(options)).await;
     }
-    
-    cmd.args(&["exec-out", "screencap", "-p"]);
-    
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 adb screencap 失败: {}", e))?;
+    let mut record_args = Vec::new();
+    if let Some(display) = display::capture_display_arg(&device_id, display_id).await? {
+        record_args.push("--display-id".to_string());
+        record_args.push(display);
+    }
+    record_args.extend(options.to_args()?);
+    let device_key = tools::device_key(&device_id);
+    let mut store = screen_recordings()
+        .lock()
+        .map_err(|_| MdtError::lock("screenrecord"))?;
 
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    if store.contains_key(&device_key) {
+        return Err(MdtError::session_exists("screenrecord", &device_key));
     }
 
-    // 确定输出路径
-    let final_path = if let Some(path) = output_path {
-        path
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+
+    let (process, remote_path) = if options.segmented {
+        let stop_flag = Arc::new(AtomicBool::new(false));
+        let remote_paths = Arc::new(Mutex::new(Vec::new()));
+        let current_pid = Arc::new(AtomicU32::new(0));
+        let task = tauri::async_runtime::spawn(run_record_segments(
+            device_id.clone(),
+            record_args,
+            timestamp,
+            stop_flag.clone(),
+            remote_paths.clone(),
+            current_pid.clone(),
+        ));
+        let process = RecordProcess::Segmented {
+            stop_flag,
+            remote_paths,
+            current_pid,
+            task,
+        };
+        (process, format!("/sdcard/screenrecord_{}_1.mp4", timestamp))
     } else {
-        // 如果没有指定路径，使用默认路径：当前目录/screenshot_<timestamp>.png
-        let timestamp = SystemTime::now()
-            .duration_since(SystemTime::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        format!("screenshot_{}.png", timestamp)
+        let remote_path = format!("/sdcard/screenrecord_{}.mp4", timestamp);
+        let mut cmd = adb_command(&device_id);
+        cmd.args(["shell", "screenrecord"]);
+        cmd.args(&record_args);
+        cmd.arg(&remote_path)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        history::record_launch(&cmd);
+        let child = cmd
+            .spawn()
+            .map_err(|e| MdtError::spawn("adb", e))?;
+        (RecordProcess::Single(child), remote_path)
     };
 
-    // 将截图数据写入文件
-    std::fs::write(&final_path, &output.stdout)
-        .map_err(|e| format!("写入截图文件失败: {}", e))?;
+    store.insert(
+        device_key,
+        ScreenRecordSession {
+            process,
+            remote_path: remote_path.clone(),
+            start_time: timestamp,
+            display_id,
+            options,
+        },
+    );
 
-    Ok(final_path)
+    Ok(remote_path)
 }
 
+/// 通过 scrcpy 客户端录制到本地文件：带声音（Android 11+）且没有 180 秒限制，返回本地文件路径
 #[tauri::command]
-pub async fn adb_start_screenrecord(device_id: Option<String>) -> Result<String, String> {
-    use std::process::{Command, Stdio};
-
-    let device_key = device_key(&device_id);
-    let mut store = screen_recordings()
+pub async fn adb_start_scrcpy_record(
+    device_id: Option<String>,
+    display_id: Option<u32>,
+    options: Option<RecordOptions>,
+) -> MdtResult<String> {
+    use std::process::Stdio;
+    let _history = history::track();
+
+    let mut options = options.unwrap_or_default();
+    options.backend = RecordBackend::Scrcpy;
+    let scrcpy_args = options.to_scrcpy_args()?;
+    if tools::resolve_tool_path("scrcpy").is_none() {
+        return Err(MdtError::ToolNotFound {
+            tool: "scrcpy".to_string(),
+        });
+    }
+    let device_key = tools::device_key(&device_id);
+    if screen_recordings()
         .lock()
-        .map_err(|_| "录屏状态锁定失败".to_string())?;
-
-    if store.contains_key(&device_key) {
-        return Err("当前设备正在录屏中".to_string());
+        .map_err(|_| MdtError::lock("screenrecord"))?
+        .contains_key(&device_key)
+    {
+        return Err(MdtError::session_exists("screenrecord", &device_key));
     }
 
     let timestamp = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
         .unwrap()
         .as_secs();
-    let remote_path = format!("/sdcard/screenrecord_{}.mp4", timestamp);
-
-    let mut cmd = tools::command_for("adb");
-    if let Some(device) = device_id.clone() {
-        cmd.args(&["-s", &device]);
+    // Windows 上无法向 scrcpy 发送中断信号，使用被强制结束后仍可播放的 mkv 容器
+    let extension = if cfg!(target_os = "windows") { "mkv" } else { "mp4" };
+    let local_path =
+        default_capture_path(&device_id, config::OutputKind::Recording, timestamp, extension).await?;
+
+    let mut cmd = tools::command_for("scrcpy");
+    if let Some(serial) = &device_id {
+        cmd.arg(format!("--serial={}", serial));
+    }
+    if let Some(display_id) = display_id {
+        cmd.arg(format!("--display-id={}", display_id));
     }
-    cmd.args(&["shell", "screenrecord", &remote_path])
+    cmd.args(&scrcpy_args)
+        .arg(format!("--record={}", local_path))
+        .args(["--no-playback", "--no-window"])
         .stdout(Stdio::null())
-        .stderr(Stdio::null());
+        .stderr(Stdio::piped());
+    // 让 scrcpy 使用与本工具相同的 adb 与 server
+    if let Some(adb_path) = tools::resolve_tool_path("adb") {
+        cmd.env("ADB", adb_path);
+    }
+    if let Some(server_path) = resolve_scrcpy_server_path() {
+        cmd.env("SCRCPY_SERVER_PATH", server_path);
+    }
+    history::record_launch(&cmd);
+    let mut child = cmd.spawn().map_err(|e| MdtError::spawn("scrcpy", e))?;
+    let stderr = Arc::new(Mutex::new(String::new()));
+    if let Some(mut reader) = child.stderr.take() {
+        let stderr = stderr.clone();
+        thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            while let Ok(n) = reader.read(&mut buf) {
+                if n == 0 {
+                    break;
+                }
+                if let Ok(mut output) = stderr.lock() {
+                    output.push_str(&String::from_utf8_lossy(&buf[..n]));
+                    // 只保留最近的输出，避免长时间录制时无限增长；失败原因通常在最后几行
+                    if output.len() > SCRCPY_STDERR_KEEP_BYTES {
+                        let mut cut = output.len() - SCRCPY_STDERR_KEEP_BYTES;
+                        while !output.is_char_boundary(cut) {
+                            cut += 1;
+                        }
+                        output.drain(..cut);
+                    }
+                }
+            }
+        });
+    }
 
-    let child = cmd
-        .spawn()
-        .map_err(|e| format!("启动录屏失败: {}", e))?;
+    // server 版本不匹配、设备不支持音频等情况下 scrcpy 会在启动后很快退出
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    if let Ok(Some(status)) = child.try_wait() {
+        return Err(MdtError::CommandFailed {
+            command: "scrcpy --record".to_string(),
+            exit_code: status.code(),
+            stderr: stderr.lock().map(|output| output.trim().to_string()).unwrap_or_default(),
+        });
+    }
 
+    let mut store = screen_recordings()
+        .lock()
+        .map_err(|_| MdtError::lock("screenrecord"))?;
+    // 等待启动期间未持有锁，可能已有其他录制开始
+    if store.contains_key(&device_key) {
+        let _ = child.kill();
+        let _ = child.wait();
+        return Err(MdtError::session_exists("screenrecord", &device_key));
+    }
     store.insert(
         device_key,
         ScreenRecordSession {
-            child,
-            remote_path: remote_path.clone(),
+            process: RecordProcess::Scrcpy {
+                child,
+                local_path: local_path.clone(),
+                stderr,
+            },
+            remote_path: local_path.clone(),
             start_time: timestamp,
+            display_id,
+            options,
         },
     );
+    Ok(local_path)
+}
 
-    Ok(remote_path)
+/// 结束 scrcpy 录制：先发送 SIGINT 让其写完文件索引，超时后强制结束
+async fn stop_scrcpy_record(mut child: std::process::Child) {
+    if !cfg!(target_os = "windows") {
+        let mut cmd = Command::new("kill");
+        cmd.args(["-INT", &child.id().to_string()]);
+        let _ = tools::output(cmd).await;
+        for _ in 0..50 {
+            if !matches!(child.try_wait(), Ok(None)) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        println!("[adb] scrcpy record did not exit after SIGINT, killing");
+    }
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+/// 用 ffmpeg 的 concat 分离器无损合并多个分段
+async fn concat_segments(segments: &[String], output_path: &str) -> MdtResult<()> {
+    let list_path = std::env::temp_dir().join(format!(
+        "mdt_concat_{}.txt",
+        SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_millis())
+            .unwrap_or(0)
+    ));
+    let list: String = segments
+        .iter()
+        .map(|path| format!("file '{}'\n", path.replace('\'', "'\\''")))
+        .collect();
+    std::fs::write(&list_path, list).map_err(|e| MdtError::io("写入分段列表", e))?;
+
+    let mut cmd = tools::command_for("ffmpeg");
+    cmd.args(["-y", "-f", "concat", "-safe", "0", "-i"])
+        .arg(&list_path)
+        .args(["-c", "copy", output_path]);
+    let result = tools::run_checked("ffmpeg", &None, cmd).await;
+    let _ = std::fs::remove_file(&list_path);
+    result.map(|_| ())
 }
 
+/// 停止录屏并拉取到本地，返回文件路径及录制参数；分段录制时合并各段
 #[tauri::command]
 pub async fn adb_stop_screenrecord(
     device_id: Option<String>,
     output_path: Option<String>,
-) -> Result<String, String> {
-    use std::process::Command;
-
-    let device_key = device_key(&device_id);
-    let mut store = screen_recordings()
+) -> MdtResult<RecordingResult> {
+    let _history = history::track();
+    let device_key = tools::device_key(&device_id);
+    let session = screen_recordings()
         .lock()
-        .map_err(|_| "录屏状态锁定失败".to_string())?;
-
-    let session = store
+        .map_err(|_| MdtError::lock("screenrecord"))?
         .remove(&device_key)
-        .ok_or_else(|| "当前设备没有正在进行的录屏".to_string())?;
+        .ok_or_else(|| MdtError::session_not_found("screenrecord", &device_key))?;
 
-    let mut child = session.child;
-    let _ = child.kill();
-    let _ = child.wait();
+    // 设置了时长上限时，设备端可能早已自行结束录制
+    let elapsed = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(session.start_time)
+        .saturating_sub(session.start_time);
+    let duration_secs = match session.options.time_limit_secs {
+        Some(limit) => elapsed.min(limit as u64),
+        None => elapsed,
+    };
+
+    let remote_paths = match session.process {
+        RecordProcess::Scrcpy {
+            child,
+            local_path,
+            stderr,
+        } => {
+            stop_scrcpy_record(child).await;
+            if !Path::new(&local_path).is_file() {
+                return Err(MdtError::CommandFailed {
+                    command: "scrcpy --record".to_string(),
+                    exit_code: None,
+                    stderr: stderr.lock().map(|output| output.trim().to_string()).unwrap_or_default(),
+                });
+            }
+            let path = match output_path {
+                Some(path) if path != local_path => {
+                    if std::fs::rename(&local_path, &path).is_err() {
+                        std::fs::copy(&local_path, &path).map_err(|e| MdtError::io("保存录屏文件", e))?;
+                        let _ = std::fs::remove_file(&local_path);
+                    }
+                    path
+                }
+                _ => local_path,
+            };
+            return Ok(RecordingResult {
+                path,
+                start_time: session.start_time,
+                duration_secs,
+                display_id: session.display_id,
+                options: session.options,
+                backend: RecordBackend::Scrcpy,
+                segments: Vec::new(),
+            });
+        }
+        RecordProcess::Single(mut child) => {
+            let _ = child.kill();
+            let _ = child.wait();
+            vec![session.remote_path.clone()]
+        }
+        RecordProcess::Segmented {
+            stop_flag,
+            remote_paths,
+            current_pid,
+            task,
+        } => {
+            stop_flag.store(true, Ordering::SeqCst);
+            // 只结束本会话当前分段的 screenrecord，不影响其他录屏
+            let pid = current_pid.load(Ordering::SeqCst);
+            if pid != 0 {
+                interrupt_screenrecord(&device_id, pid).await;
+            }
+            let _ = tokio::time::timeout(Duration::from_secs(10), task).await;
+            remote_paths.lock().map(|paths| paths.clone()).unwrap_or_default()
+        }
+    };
 
     let final_path = if let Some(path) = output_path {
         path
     } else {
-        format!("screenrecord_{}.mp4", session.start_time)
+        default_capture_path(&device_id, config::OutputKind::Recording, session.start_time, "mp4").await?
     };
 
-    let mut pull_cmd = tools::command_for("adb");
-    if let Some(device) = device_id.clone() {
-        pull_cmd.args(&["-s", &device]);
+    if remote_paths.is_empty() {
+        return Err(MdtError::invalid_argument("screenrecord", "录屏未能启动，没有可拉取的分段"));
     }
-    pull_cmd.args(&["pull", &session.remote_path, &final_path]);
-
-    let output = pull_cmd
-        .output()
-        .map_err(|e| format!("拉取录屏文件失败: {}", e))?;
-
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+    let mut segments = Vec::new();
+    if let [remote_path] = remote_paths.as_slice() {
+        adb_run(&device_id, &["pull", remote_path, &final_path]).await?;
+    } else {
+        let stem = final_path.strip_suffix(".mp4").unwrap_or(&final_path).to_string();
+        for (index, remote_path) in remote_paths.iter().enumerate() {
+            let local_path = format!("{}_part{}.mp4", stem, index + 1);
+            adb_run(&device_id, &["pull", remote_path, &local_path]).await?;
+            segments.push(local_path);
+        }
     }
-
-    let mut rm_cmd = tools::command_for("adb");
-    if let Some(device) = device_id {
-        rm_cmd.args(&["-s", &device]);
+    for remote_path in &remote_paths {
+        let _ = adb_shell(&device_id, &["rm", "-f", remote_path]).await;
     }
-    rm_cmd.args(&["shell", "rm", "-f", &session.remote_path]);
-    let _ = rm_cmd.output();
 
-    Ok(final_path)
+    let path = if segments.is_empty() {
+        final_path
+    } else {
+        match concat_segments(&segments, &final_path).await {
+            Ok(()) => {
+                for segment in segments.drain(..) {
+                    let _ = std::fs::remove_file(segment);
+                }
+                final_path
+            }
+            Err(err) => {
+                println!("[adb] merge screenrecord segments failed, keep parts: {}", err);
+                segments.first().cloned().unwrap_or(final_path)
+            }
+        }
+    };
+
+    Ok(RecordingResult {
+        path,
+        start_time: session.start_time,
+        duration_secs,
+        display_id: session.display_id,
+        options: session.options,
+        backend: RecordBackend::Screenrecord,
+        segments,
+    })
 }
 
+/// 启动投屏，`display_id` 为逻辑显示器 ID（见 `adb_list_displays`），默认主屏
 #[tauri::command]
-pub async fn adb_start_mirror(device_id: Option<String>) -> Result<MirrorStreamInfo, String> {
+pub async fn adb_start_mirror(device_id: Option<String>, display_id: Option<u32>) -> MdtResult<MirrorStreamInfo> {
     use std::process::Stdio;
-
-    let device_key = device_key(&device_id);
-    let mut store = mirror_streams()
-        .lock()
-        .map_err(|_| "镜像状态锁定失败".to_string())?;
-
-    if store.contains_key(&device_key) {
-        let existing = store.get(&device_key).map(|s| s.url.clone());
-        if let Some(url) = existing {
-            return Ok(MirrorStreamInfo { url });
+    let _history = history::track();
+
+    let device_key = tools::device_key(&device_id);
+    {
+        let store = mirror_streams()
+            .lock()
+            .map_err(|_| MdtError::lock("mirror"))?;
+        if let Some(session) = store.get(&device_key) {
+            return Ok(MirrorStreamInfo {
+                url: session.url.clone(),
+            });
         }
-        return Err("当前设备镜像已启动".to_string());
     }
 
     let server_path = resolve_scrcpy_server_path()
-        .ok_or_else(|| "未找到 scrcpy-server，请安装 scrcpy 或设置 MDT_SCRCPY_SERVER_PATH".to_string())?;
-    let server_version = resolve_scrcpy_version().unwrap_or_else(|| "3.3.4".to_string());
-
-    let mut push_cmd = tools::command_for("adb");
-    if let Some(device) = device_id.clone() {
-        push_cmd.args(&["-s", &device]);
-    }
-    push_cmd
-        .args(&["push", server_path.to_str().unwrap(), "/data/local/tmp/scrcpy-server.jar"]);
-    let output = push_cmd
-        .output()
-        .map_err(|e| format!("推送 scrcpy-server 失败: {}", e))?;
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
-    }
+        .ok_or_else(|| MdtError::ToolNotFound {
+            tool: "scrcpy-server".to_string(),
+        })?;
+    let server_version = resolve_scrcpy_version()
+        .await
+        .unwrap_or_else(|| DEFAULT_SCRCPY_VERSION.to_string());
 
-    let forward_port = pick_free_port()?;
-    let mut forward_cmd = tools::command_for("adb");
-    if let Some(device) = device_id.clone() {
-        forward_cmd.args(&["-s", &device]);
-    }
-    forward_cmd.args(&[
-        "forward",
-        &format!("tcp:{}", forward_port),
-        "localabstract:scrcpy",
-    ]);
-    let output = forward_cmd
-        .output()
-        .map_err(|e| format!("建立 adb forward 失败: {}", e))?;
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
-    }
+    adb_run(&device_id, &["push", server_path.to_str().unwrap(), SCRCPY_SERVER_REMOTE_PATH])
+    .await?;
 
-    let mut cmd = tools::command_for("adb");
-    if let Some(device) = device_id.clone() {
-        cmd.args(&["-s", &device]);
-    }
-    cmd.args(&[
+    let forward_port = pick_free_port()?;
+    adb_run(&device_id, &["forward", &format!("tcp:{}", forward_port), "localabstract:scrcpy"])
+    .await?;
+
+    let mirror = config::current().mirror;
+    let max_size = format!("max_size={}", mirror.max_size);
+    let max_fps = format!("max_fps={}", mirror.max_fps);
+    let mut cmd = adb_command(&device_id);
+    cmd.args([
         "shell",
         "CLASSPATH=/data/local/tmp/scrcpy-server.jar",
         "app_process",
@@ -540,8 +3119,8 @@ pub async fn adb_start_mirror(device_id: Option<String>) -> Result<MirrorStreamI
         "tunnel_forward=true",
         "audio=false",
         "control=false",
-        "max_size=1920",
-        "max_fps=60",
+        &max_size,
+        &max_fps,
         "video_codec=h264",
         "send_device_meta=false",
         "send_frame_meta=false",
@@ -552,27 +3131,33 @@ pub async fn adb_start_mirror(device_id: Option<String>) -> Result<MirrorStreamI
     ])
     .stdout(Stdio::null())
     .stderr(Stdio::piped());
+    if let Some(bit_rate) = mirror.video_bit_rate {
+        cmd.arg(format!("video_bit_rate={}", bit_rate));
+    }
+    if let Some(display_id) = display_id {
+        cmd.arg(format!("display_id={}", display_id));
+    }
 
-    let mut child = cmd.spawn().map_err(|e| format!("启动 scrcpy server 失败: {}", e))?;
+    history::record_launch(&cmd);
+    let mut child = cmd.spawn().map_err(|e| MdtError::spawn("adb", e))?;
     let stderr = child
         .stderr
         .take()
-        .ok_or_else(|| "无法获取 scrcpy server 错误输出".to_string())?;
+        .ok_or_else(|| MdtError::io("获取 scrcpy server 错误输出", "stderr 不可用"))?;
 
     let listener = TcpListener::bind("127.0.0.1:0")
-        .map_err(|e| format!("启动镜像服务失败: {}", e))?;
+        .map_err(|e| MdtError::io("启动镜像服务", e))?;
     listener
         .set_nonblocking(true)
-        .map_err(|e| format!("设置镜像服务失败: {}", e))?;
+        .map_err(|e| MdtError::io("设置镜像服务", e))?;
     let addr = listener
         .local_addr()
-        .map_err(|e| format!("获取镜像服务地址失败: {}", e))?;
+        .map_err(|e| MdtError::io("获取镜像服务地址", e))?;
     let url = format!("ws://127.0.0.1:{}/mirror", addr.port());
 
     let stop_flag = Arc::new(AtomicBool::new(false));
-    let clients: Arc<Mutex<Vec<Sender<Vec<u8>>>>> = Arc::new(Mutex::new(Vec::new()));
-    let prebuffer: Arc<Mutex<Vec<u8>>> = Arc::new(Mutex::new(Vec::new()));
-    let prebuffer_limit = 2 * 1024 * 1024;
+    let clients: Arc<Mutex<Vec<MirrorClient>>> = Arc::new(Mutex::new(Vec::new()));
+    let prebuffer = Arc::new(Mutex::new(H264Prebuffer::new(2 * 1024 * 1024)));
 
     let stop_flag_reader = stop_flag.clone();
     let clients_reader = clients.clone();
@@ -596,18 +3181,17 @@ pub async fn adb_start_mirror(device_id: Option<String>) -> Result<MirrorStreamI
                         logged = true;
                     }
                     let chunk = buf[..n].to_vec();
-                    if let Ok(mut cache) = prebuffer_reader.lock() {
-                        cache.extend_from_slice(&chunk);
-                        if cache.len() > prebuffer_limit {
-                            let excess = cache.len() - prebuffer_limit;
-                            cache.drain(0..excess);
-                        }
-                    }
+                    // 持有预缓冲锁完成分发，避免与新客户端注册交错导致数据重复或缺失
+                    let mut cache = match prebuffer_reader.lock() {
+                        Ok(cache) => cache,
+                        Err(_) => break,
+                    };
+                    cache.push(&chunk);
                     let mut list = match clients_reader.lock() {
                         Ok(list) => list,
                         Err(_) => break,
                     };
-                    list.retain(|tx| tx.send(chunk.clone()).is_ok());
+                    list.retain(|client| client.deliver(&chunk, &cache));
                 }
                 Err(_) => break,
             }
@@ -636,24 +3220,46 @@ pub async fn adb_start_mirror(device_id: Option<String>) -> Result<MirrorStreamI
     let clients_server = clients.clone();
     let prebuffer_server = prebuffer.clone();
     thread::spawn(move || {
+        let mut next_client_id = 0u64;
         while !stop_flag_server.load(Ordering::SeqCst) {
             match listener.accept() {
-                Ok((stream, _)) => {
+                Ok((stream, peer)) => {
                     let _ = stream.set_nonblocking(false);
+                    // 半开连接上写入会一直阻塞，超时后写线程退出
+                    let _ = stream.set_write_timeout(Some(Duration::from_millis(MIRROR_CLIENT_STALL_TIMEOUT_MS)));
                     let websocket = tungstenite::accept(stream);
                     if websocket.is_err() {
                         continue;
                     }
                     let mut websocket = websocket.unwrap();
-                    let (tx, rx) = crossbeam_channel::unbounded::<Vec<u8>>();
-                    if let Ok(mut list) = clients_server.lock() {
-                        list.push(tx);
-                    }
+                    let (tx, rx) = crossbeam_channel::bounded::<Vec<u8>>(MIRROR_CLIENT_QUEUE_CAPACITY);
+                    next_client_id += 1;
+                    let connected_at = tools::now_millis();
+                    let stats = Arc::new(MirrorClientStats {
+                        sent_chunks: AtomicU64::new(0),
+                        sent_bytes: AtomicU64::new(0),
+                        dropped_chunks: AtomicU64::new(0),
+                        dropped_bytes: AtomicU64::new(0),
+                        last_write_at: AtomicU64::new(connected_at),
+                    });
+                    let client = MirrorClient {
+                        id: next_client_id,
+                        peer: peer.to_string(),
+                        connected_at,
+                        tx,
+                        rx: rx.clone(),
+                        stats: stats.clone(),
+                    };
+                    let initial = match prebuffer_server.lock() {
+                        Ok(cache) => {
+                            if let Ok(mut list) = clients_server.lock() {
+                                list.push(client);
+                            }
+                            cache.snapshot()
+                        }
+                        Err(_) => continue,
+                    };
                     let stop_flag_client = stop_flag_server.clone();
-                    let initial = prebuffer_server
-                        .lock()
-                        .map(|cache| cache.clone())
-                        .unwrap_or_default();
                     thread::spawn(move || {
                         if initial.is_empty() {
                             println!("[mirror] client connected, prebuffer empty");
@@ -669,14 +3275,24 @@ pub async fn adb_start_mirror(device_id: Option<String>) -> Result<MirrorStreamI
                         while !stop_flag_client.load(Ordering::SeqCst) {
                             match rx.recv_timeout(Duration::from_millis(200)) {
                                 Ok(chunk) => {
+                                    let len = chunk.len() as u64;
                                     if websocket
                                         .write_message(Message::Binary(chunk))
                                         .is_err()
                                     {
                                         break;
                                     }
+                                    stats.sent_chunks.fetch_add(1, Ordering::Relaxed);
+                                    stats.sent_bytes.fetch_add(len, Ordering::Relaxed);
+                                    stats.last_write_at.store(tools::now_millis(), Ordering::Relaxed);
+                                }
+                                Err(crossbeam_channel::RecvTimeoutError::Timeout) => {
+                                    // 空闲时同样视为正常，避免误判为滞后
+                                    if rx.is_empty() {
+                                        stats.last_write_at.store(tools::now_millis(), Ordering::Relaxed);
+                                    }
+                                    continue;
                                 }
-                                Err(crossbeam_channel::RecvTimeoutError::Timeout) => continue,
                                 Err(_) => break,
                             }
                         }
@@ -691,118 +3307,505 @@ pub async fn adb_start_mirror(device_id: Option<String>) -> Result<MirrorStreamI
         }
     });
 
-    store.insert(
-        device_key,
-        MirrorStreamSession {
-            child,
-            device_id: device_id.clone(),
-            forward_port,
-            stop_flag,
-            clients,
-            url: url.clone(),
-        },
-    );
-
-    Ok(MirrorStreamInfo { url })
-}
+    let session = MirrorStreamSession {
+        child,
+        device_id: device_id.clone(),
+        forward_port,
+        stop_flag,
+        clients,
+        url: url.clone(),
+        start_time: SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+    };
 
-#[tauri::command]
-pub async fn adb_stop_mirror(device_id: Option<String>) -> Result<(), String> {
-    let device_key = device_key(&device_id);
-    let mut store = mirror_streams()
-        .lock()
-        .map_err(|_| "镜像状态锁定失败".to_string())?;
+    // 启动过程中未持有锁，若期间已有其他请求完成启动则复用已有会话
+    let existing_url = {
+        let mut store = mirror_streams()
+            .lock()
+            .map_err(|_| MdtError::lock("mirror"))?;
+        match store.get(&device_key) {
+            Some(existing) => Some(existing.url.clone()),
+            None => {
+                store.insert(device_key, session);
+                return Ok(MirrorStreamInfo { url });
+            }
+        }
+    };
 
-    let session = store
-        .remove(&device_key)
-        .ok_or_else(|| "当前设备没有正在进行的镜像".to_string())?;
+    teardown_mirror(session).await;
+    Ok(MirrorStreamInfo {
+        url: existing_url.unwrap_or(url),
+    })
+}
 
+async fn teardown_mirror(session: MirrorStreamSession) {
     session.stop_flag.store(true, Ordering::SeqCst);
     if let Ok(mut list) = session.clients.lock() {
         list.clear();
     }
 
-    let mut forward_remove = tools::command_for("adb");
-    if let Some(device) = &session.device_id {
-        forward_remove.args(&["-s", device]);
-    }
-    forward_remove.args(&["forward", "--remove", &format!("tcp:{}", session.forward_port)]);
-    let _ = forward_remove.output();
+    let _ = adb_run(&session.device_id, &["forward", "--remove", &format!("tcp:{}", session.forward_port)])
+    .await;
 
     let mut child = session.child;
     let _ = child.kill();
     let _ = child.wait();
+}
+
+#[tauri::command]
+pub async fn adb_stop_mirror(device_id: Option<String>) -> MdtResult<()> {
+    let _history = history::track();
+    let device_key = tools::device_key(&device_id);
+    let session = mirror_streams()
+        .lock()
+        .map_err(|_| MdtError::lock("mirror"))?
+        .remove(&device_key)
+        .ok_or_else(|| MdtError::session_not_found("mirror", &device_key))?;
 
+    teardown_mirror(session).await;
     Ok(())
 }
 
+#[tauri::command]
+pub async fn adb_mirror_clients(device_id: Option<String>) -> MdtResult<Vec<MirrorClientInfo>> {
+    let device_key = tools::device_key(&device_id);
+    let store = mirror_streams()
+        .lock()
+        .map_err(|_| MdtError::lock("mirror"))?;
+
+    let session = store
+        .get(&device_key)
+        .ok_or_else(|| MdtError::session_not_found("mirror", &device_key))?;
+
+    let list = session
+        .clients
+        .lock()
+        .map_err(|_| MdtError::lock("mirror client"))?;
+    Ok(list.iter().map(|client| client.snapshot()).collect())
+}
+
 #[tauri::command]
 pub async fn adb_push_file(
+    app: AppHandle,
     device_id: Option<String>,
     local_path: String,
     remote_path: String,
-) -> Result<String, String> {
-    use std::process::Command;
+    transfer_id: Option<String>,
+    verify: Option<bool>,
+    media_scan: Option<bool>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let total_bytes = std::fs::metadata(&local_path).map(|m| m.len()).ok();
+    let target = adb_push_target(&device_id, &local_path, &remote_path).await;
+    let probe = adb_remote_size_probe(device_id.clone(), target.clone());
+
+    let mut cmd = adb_command(&device_id);
+    cmd.args(["push", &local_path, &remote_path]);
+
+    let request = TransferRequest {
+        transfer_id: transfer_id.unwrap_or_else(transfer::new_transfer_id),
+        device_id: device_id.clone(),
+        tool: "adb",
+        direction: "push",
+        local_path: local_path.clone(),
+        remote_path,
+        total_bytes,
+    };
+    let output = transfer::run_transfer(&app, request, cmd, probe).await?;
 
-    let mut cmd = tools::command_for("adb");
-    if let Some(device) = device_id {
-        cmd.args(&["-s", &device]);
+    if verify.unwrap_or(false) {
+        adb_verify_checksum(&device_id, PathBuf::from(&local_path), &target).await?;
+    }
+    // 推送已成功，扫描失败只记录日志
+    if media_scan.unwrap_or(false) {
+        if let Err(err) = media_scan_path(&device_id, &target).await {
+            println!("[adb] media scan after push failed: {}", err);
+        }
+    }
+    Ok(output)
+}
+
+/// Android 10 起 `MEDIA_SCANNER_SCAN_FILE` 广播已废弃，目录改为请求 MediaProvider 扫描整个卷
+const MEDIA_SCAN_VOLUME_MIN_SDK: u32 = 29;
+
+/// 扫描文件或目录，返回扫描的文件数：Android 10+ 的目录通过 `content call` 扫描外部存储卷，
+/// 其余情况对每个文件发送 `MEDIA_SCANNER_SCAN_FILE` 广播，只统计广播成功的文件
+async fn media_scan_path(device_id: &Option<String>, remote_path: &str) -> MdtResult<usize> {
+    let quoted = tools::shell_quote(remote_path.trim_end_matches('/'));
+    let sdk = adb_getprop(device_id, "ro.build.version.sdk")
+        .await
+        .and_then(|sdk| sdk.parse::<u32>().ok())
+        .unwrap_or(0);
+    let is_dir = adb_shell(device_id, &[&format!("[ -d {} ] && echo dir", quoted)])
+        .await
+        .is_ok_and(|output| output == "dir");
+
+    if is_dir && sdk >= MEDIA_SCAN_VOLUME_MIN_SDK {
+        let command = "content call --uri content://media --method scan_volume --arg external_primary";
+        let output = adb_shell(device_id, &[command]).await?;
+        expect_no_error(command, output)?;
+        let count = adb_shell(device_id, &[&format!("find {} -type f 2>/dev/null | wc -l", quoted)]).await?;
+        return Ok(count.parse().unwrap_or(0));
     }
-    cmd.args(&["push", &local_path, &remote_path]);
 
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 adb push 失败: {}", e))?;
+    let script = format!(
+        "find {} -type f 2>/dev/null | while IFS= read -r f; do \
+         am broadcast -a android.intent.action.MEDIA_SCANNER_SCAN_FILE -d \"file://$f\" >/dev/null 2>&1 \
+         && echo \"$f\"; done",
+        quoted
+    );
+    let output = adb_shell(device_id, &[&script]).await?;
+    Ok(output.lines().filter(|line| !line.trim().is_empty()).count())
+}
+
+/// 让媒体库立即收录设备上的文件或目录（如推送到 /sdcard 的图片、视频），返回扫描的文件数
+#[tauri::command]
+pub async fn adb_media_scan(device_id: Option<String>, remote_path: String) -> MdtResult<usize> {
+    let _history = history::track();
+    let count = media_scan_path(&device_id, &remote_path).await?;
+    if count == 0 {
+        return Err(MdtError::invalid_argument("remotePath", format!("路径下没有文件: {}", remote_path)));
+    }
+    Ok(count)
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+/// 校验 OBB 文件名是否符合 `main|patch.<versionCode>.<包名>.obb` 约定
+fn validate_obb_name(file_name: &str, package_name: &str) -> MdtResult<()> {
+    let valid = file_name
+        .strip_suffix(".obb")
+        .and_then(|stem| stem.split_once('.'))
+        .filter(|(kind, _)| *kind == "main" || *kind == "patch")
+        .and_then(|(_, rest)| rest.split_once('.'))
+        .is_some_and(|(version, package)| {
+            !version.is_empty() && version.chars().all(|c| c.is_ascii_digit()) && package == package_name
+        });
+    if valid {
+        Ok(())
     } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+        Err(MdtError::invalid_argument(
+            "obbPath",
+            format!(
+                "OBB 文件名应为 main.<versionCode>.{0}.obb 或 patch.<versionCode>.{0}.obb",
+                package_name
+            ),
+        ))
+    }
+}
+
+/// 推送 OBB 扩展文件到 `/sdcard/Android/obb/<包名>/`，完成后校验文件大小
+#[tauri::command]
+pub async fn adb_push_obb(
+    app: AppHandle,
+    device_id: Option<String>,
+    package_name: String,
+    obb_path: String,
+    transfer_id: Option<String>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let file_name = Path::new(&obb_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    validate_obb_name(&file_name, &package_name)?;
+    let local_size = std::fs::metadata(&obb_path)
+        .map_err(|e| MdtError::io("读取 OBB 文件", e))?
+        .len();
+
+    let obb_dir = format!("/sdcard/Android/obb/{}", package_name);
+    adb_shell(&device_id, &["mkdir", "-p", &tools::shell_quote(&obb_dir)]).await?;
+
+    let remote_path = format!("{}/{}", obb_dir, file_name);
+    let output = adb_push_file(
+        app,
+        device_id.clone(),
+        obb_path,
+        remote_path.clone(),
+        transfer_id,
+        None,
+        None,
+    )
+    .await?;
+
+    let remote_size = adb_remote_size(&device_id, &remote_path).await;
+    if remote_size != Some(local_size) {
+        return Err(MdtError::TransferIncomplete {
+            path: remote_path,
+            expected: local_size,
+            actual: remote_size,
+        });
     }
+    Ok(output)
 }
 
 #[tauri::command]
 pub async fn adb_pull_file(
+    app: AppHandle,
     device_id: Option<String>,
     remote_path: String,
     local_path: String,
-) -> Result<String, String> {
-    use std::process::Command;
+    transfer_id: Option<String>,
+    verify: Option<bool>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let total_bytes = adb_remote_size(&device_id, &remote_path).await;
+
+    // 本地路径为目录时，实际写入的是同名文件
+    let target = if Path::new(&local_path).is_dir() {
+        let file_name = remote_path.rsplit('/').next().unwrap_or_default();
+        Path::new(&local_path).join(file_name)
+    } else {
+        PathBuf::from(&local_path)
+    };
 
-    let mut cmd = tools::command_for("adb");
-    if let Some(device) = device_id {
-        cmd.args(&["-s", &device]);
-    }
-    cmd.args(&["pull", &remote_path, &local_path]);
+    let mut cmd = adb_command(&device_id);
+    cmd.args(["pull", &remote_path, &local_path]);
+
+    let request = TransferRequest {
+        transfer_id: transfer_id.unwrap_or_else(transfer::new_transfer_id),
+        device_id: device_id.clone(),
+        tool: "adb",
+        direction: "pull",
+        local_path,
+        remote_path: remote_path.clone(),
+        total_bytes,
+    };
+    let output = transfer::run_transfer(&app, request, cmd, SizeProbe::Local(target.clone())).await?;
 
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 adb pull 失败: {}", e))?;
+    if verify.unwrap_or(false) {
+        adb_verify_checksum(&device_id, target, &remote_path).await?;
+    }
+    Ok(output)
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+#[tauri::command]
+pub async fn adb_push_dir(
+    app: AppHandle,
+    device_id: Option<String>,
+    local_dir: String,
+    remote_dir: String,
+    parallel: Option<usize>,
+    skip_unchanged: Option<bool>,
+    transfer_id: Option<String>,
+) -> MdtResult<DirectoryTransferResult> {
+    let _history = history::track();
+    let local_files = transfer::walk_local_dir(Path::new(&local_dir))?;
+    let remote_root = remote_dir.trim_end_matches('/').to_string();
+    // 目标目录可能尚不存在，列举失败时视为空目录
+    let existing = if skip_unchanged.unwrap_or(true) {
+        adb_remote_listing(&device_id, &remote_root)
+            .await
+            .unwrap_or_default()
     } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+        HashMap::new()
+    };
+
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+    for file in local_files {
+        if transfer::is_unchanged(file.size, file.modified, existing.get(&file.relative_path)) {
+            skipped.push(file.relative_path);
+            continue;
+        }
+        entries.push(DirectoryEntry {
+            remote_path: format!("{}/{}", remote_root, file.relative_path),
+            local_path: file.path,
+            relative_path: file.relative_path,
+            size: file.size,
+        });
+    }
+
+    // 预先创建设备端目录结构
+    let mut dirs: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| entry.remote_path.rsplit_once('/').map(|(dir, _)| dir.to_string()))
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+    for batch in dirs.chunks(50) {
+        let mut args = vec!["mkdir".to_string(), "-p".to_string()];
+        args.extend(batch.iter().map(|dir| tools::shell_quote(dir)));
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        adb_shell(&device_id, &args).await?;
+    }
+
+    let build_device = device_id.clone();
+    let build: Arc<EntryCommandBuilder> = Arc::new(move |entry: &DirectoryEntry| {
+        let mut cmd = adb_command(&build_device);
+        cmd.arg("push");
+        cmd.arg(&entry.local_path).arg(&entry.remote_path);
+        let probe = adb_remote_size_probe(build_device.clone(), entry.remote_path.clone());
+        (cmd, probe)
+    });
+
+    let request = DirectoryTransferRequest {
+        transfer_id: transfer_id.unwrap_or_else(transfer::new_transfer_id),
+        device_id,
+        direction: "push",
+        local_dir,
+        remote_dir,
+        skipped,
+        parallel: parallel.unwrap_or(1).clamp(1, 8),
+    };
+    transfer::run_directory_transfer(&app, request, "adb", entries, build).await
+}
+
+#[tauri::command]
+pub async fn adb_pull_dir(
+    app: AppHandle,
+    device_id: Option<String>,
+    remote_dir: String,
+    local_dir: String,
+    parallel: Option<usize>,
+    skip_unchanged: Option<bool>,
+    transfer_id: Option<String>,
+) -> MdtResult<DirectoryTransferResult> {
+    let _history = history::track();
+    let remote_root = remote_dir.trim_end_matches('/').to_string();
+    let remote_files = adb_remote_listing(&device_id, &remote_root).await?;
+    let skip_unchanged = skip_unchanged.unwrap_or(true);
+
+    let mut names: Vec<&String> = remote_files.keys().collect();
+    names.sort();
+
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+    for relative_path in names {
+        let remote = &remote_files[relative_path];
+        let local_path = Path::new(&local_dir).join(relative_path);
+        if skip_unchanged {
+            let local = std::fs::metadata(&local_path).ok().map(|metadata| RemoteFile {
+                size: metadata.len(),
+                modified: transfer::modified_secs(&metadata),
+            });
+            if transfer::is_unchanged(remote.size, remote.modified, local.as_ref()) {
+                skipped.push(relative_path.clone());
+                continue;
+            }
+        }
+        if let Some(parent) = local_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| MdtError::io("创建本地目录", e))?;
+        }
+        entries.push(DirectoryEntry {
+            relative_path: relative_path.clone(),
+            remote_path: format!("{}/{}", remote_root, relative_path),
+            local_path,
+            size: remote.size,
+        });
+    }
+
+    let build_device = device_id.clone();
+    let build: Arc<EntryCommandBuilder> = Arc::new(move |entry: &DirectoryEntry| {
+        let mut cmd = adb_command(&build_device);
+        cmd.args(["pull", "-a"]);
+        cmd.arg(&entry.remote_path).arg(&entry.local_path);
+        (cmd, SizeProbe::Local(entry.local_path.clone()))
+    });
+
+    let request = DirectoryTransferRequest {
+        transfer_id: transfer_id.unwrap_or_else(transfer::new_transfer_id),
+        device_id,
+        direction: "pull",
+        local_dir,
+        remote_dir,
+        skipped,
+        parallel: parallel.unwrap_or(1).clamp(1, 8),
+    };
+    transfer::run_directory_transfer(&app, request, "adb", entries, build).await
+}
+
+#[tauri::command]
+pub async fn adb_fs_list(device_id: Option<String>, path: String) -> MdtResult<Vec<RemoteFileEntry>> {
+    let args = remote_fs::list_args(&path);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = adb_shell(&device_id, &args).await?;
+    Ok(remote_fs::parse_entries(&output))
+}
+
+#[tauri::command]
+pub async fn adb_fs_stat(device_id: Option<String>, path: String) -> MdtResult<RemoteFileEntry> {
+    let args = remote_fs::stat_args(&path);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = adb_shell(&device_id, &args).await?;
+    remote_fs::parse_entries(&output)
+        .into_iter()
+        .next()
+        .ok_or_else(|| MdtError::invalid_argument("path", format!("无法读取 {} 的文件信息", path)))
+}
+
+#[tauri::command]
+pub async fn adb_fs_mkdir(device_id: Option<String>, path: String) -> MdtResult<()> {
+    let _history = history::track();
+    adb_shell(&device_id, &["mkdir", "-p", &tools::shell_quote(&path)]).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn adb_fs_remove(
+    device_id: Option<String>,
+    path: String,
+    recursive: Option<bool>,
+) -> MdtResult<()> {
+    let _history = history::track();
+    remote_fs::validate_removable(&path)?;
+    let flags = if recursive.unwrap_or(false) { "-rf" } else { "-f" };
+    adb_shell(&device_id, &["rm", flags, &tools::shell_quote(&path)]).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn adb_fs_move(
+    device_id: Option<String>,
+    source: String,
+    destination: String,
+) -> MdtResult<()> {
+    let _history = history::track();
+    adb_shell(
+        &device_id,
+        &["mv", &tools::shell_quote(&source), &tools::shell_quote(&destination)],
+    )
+    .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn adb_fs_chmod(
+    device_id: Option<String>,
+    path: String,
+    mode: String,
+    recursive: Option<bool>,
+) -> MdtResult<()> {
+    let _history = history::track();
+    remote_fs::validate_mode(&mode)?;
+    let mut args = vec!["chmod"];
+    if recursive.unwrap_or(false) {
+        args.push("-R");
     }
+    let quoted = tools::shell_quote(&path);
+    args.push(&mode);
+    args.push(&quoted);
+    adb_shell(&device_id, &args).await?;
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn adb_push_certificate(
+    app: AppHandle,
     device_id: Option<String>,
     cert_path: String,
     remote_dir: Option<String>,
-) -> Result<String, String> {
-    use std::path::Path;
-
+) -> MdtResult<String> {
+    let _history = history::track();
     let file_name = Path::new(&cert_path)
         .file_name()
-        .ok_or_else(|| "证书文件名无效".to_string())?
+        .ok_or_else(|| MdtError::invalid_argument("certPath", "证书文件名无效"))?
         .to_string_lossy()
         .to_string();
     let base_dir = remote_dir.unwrap_or_else(|| "/sdcard/Download".to_string());
     let remote_path = format!("{}/{}", base_dir.trim_end_matches('/'), file_name);
 
-    adb_push_file(device_id, cert_path, remote_path.clone()).await?;
+    adb_push_file(app, device_id, cert_path, remote_path.clone(), None, None, None).await?;
     Ok(remote_path)
 }
 
@@ -810,33 +3813,19 @@ pub async fn adb_push_certificate(
 pub async fn adb_open_cert_installer(
     device_id: Option<String>,
     remote_path: String,
-) -> Result<String, String> {
-    use std::process::Command;
-
+) -> MdtResult<String> {
+    let _history = history::track();
     let uri = format!("file://{}", remote_path);
-    let mut cmd = tools::command_for("adb");
-    if let Some(device) = device_id {
-        cmd.args(&["-s", &device]);
-    }
-    cmd.args(&[
-        "shell",
-        "am",
-        "start",
-        "-a",
-        "android.intent.action.VIEW",
-        "-t",
-        "application/x-x509-ca-cert",
-        "-d",
-        &uri,
-    ]);
-
-    let output = cmd
-        .output()
-        .map_err(|e| format!("打开证书安装向导失败: {}", e))?;
-
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
-    }
+    adb_run(&device_id, &[
+            "shell",
+            "am",
+            "start",
+            "-a",
+            "android.intent.action.VIEW",
+            "-t",
+            "application/x-x509-ca-cert",
+            "-d",
+            &uri,
+        ])
+    .await
 }
@@ -0,0 +1,41 @@
+use crate::error::{MdtError, MdtResult};
+use crate::store;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, OnceLock};
+
+const ALIASES_FILE: &str = "device-aliases";
+
+/// 设备序列号到别名的映射，首次访问时从数据目录加载
+fn aliases() -> &'static Mutex<BTreeMap<String, String>> {
+    static STORE: OnceLock<Mutex<BTreeMap<String, String>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(store::load(ALIASES_FILE)))
+}
+
+/// 查询设备别名，供设备列表填充 `alias` 字段
+pub fn alias_for(serial: &str) -> Option<String> {
+    aliases().lock().ok()?.get(serial).cloned()
+}
+
+/// 设置设备别名，`alias` 为空时删除
+#[tauri::command]
+pub async fn set_device_alias(serial: String, alias: Option<String>) -> MdtResult<()> {
+    let serial = serial.trim().to_string();
+    if serial.is_empty() {
+        return Err(MdtError::invalid_argument("serial", "设备序列号不能为空"));
+    }
+    let alias = alias.map(|alias| alias.trim().to_string()).filter(|alias| !alias.is_empty());
+
+    // 持锁写入文件，避免并发修改时较旧的快照覆盖较新的
+    let mut entries = aliases().lock().map_err(|_| MdtError::lock("device aliases"))?;
+    match alias {
+        Some(alias) => entries.insert(serial, alias),
+        None => entries.remove(&serial),
+    };
+    store::save(ALIASES_FILE, &*entries)
+}
+
+#[tauri::command]
+pub async fn get_device_aliases() -> MdtResult<BTreeMap<String, String>> {
+    let store = aliases().lock().map_err(|_| MdtError::lock("device aliases"))?;
+    Ok(store.clone())
+}
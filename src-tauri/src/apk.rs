@@ -0,0 +1,361 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+use std::path::Path;
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::tools;
+
+/// 通过 aapt2 / aapt `dump badging` 解析出的 APK 信息
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ApkInfo {
+    pub path: String,
+    pub package_name: String,
+    pub version_code: Option<String>,
+    pub version_name: Option<String>,
+    pub min_sdk: Option<String>,
+    pub target_sdk: Option<String>,
+    pub label: Option<String>,
+    pub permissions: Vec<String>,
+    pub abis: Vec<String>,
+    pub launchable_activity: Option<String>,
+    /// 应用图标（PNG/WebP）的 base64 编码，自适应图标等无法直接展示的格式为空
+    pub icon_base64: Option<String>,
+}
+
+/// 执行 `aapt2 dump <subcommand>`，找不到 aapt2 时退回 aapt（仅支持 badging）
+async fn aapt_dump(subcommand: &str, apk_path: &str) -> MdtResult<String> {
+    let mut cmd = tools::command_for("aapt2");
+    cmd.args(["dump", subcommand, apk_path]);
+    match tools::run_checked("aapt2", &None, cmd).await {
+        Ok(output) => return Ok(String::from_utf8_lossy(&output.stdout).to_string()),
+        Err(MdtError::ToolNotFound { .. }) if subcommand == "badging" => {}
+        Err(e) => return Err(e),
+    }
+
+    let mut cmd = tools::command_for("aapt");
+    cmd.args(["dump", subcommand, apk_path]);
+    let output = tools::run_checked("aapt", &None, cmd).await?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// 读取 APK 的包名：优先使用 `aapt2 dump packagename`，找不到 aapt2 时退回 `aapt dump badging`
+pub async fn package_name(apk_path: &str) -> MdtResult<String> {
+    match aapt_dump("packagename", apk_path).await {
+        Ok(output) => return Ok(output.trim().to_string()),
+        Err(MdtError::ToolNotFound { .. }) => {}
+        Err(e) => return Err(e),
+    }
+
+    let output = aapt_dump("badging", apk_path).await?;
+    output
+        .lines()
+        .find_map(|line| line.strip_prefix("package:").and_then(|rest| quoted_value(rest, "name")))
+        .ok_or_else(|| MdtError::invalid_argument("apkPath", format!("无法读取包名: {}", apk_path)))
+}
+
+/// 取出 `key='value'` 形式的属性值
+fn quoted_value(line: &str, key: &str) -> Option<String> {
+    let padded = format!(" {}", line);
+    let start = padded.find(&format!(" {}='", key))? + key.len() + 3;
+    let rest = &padded[start..];
+    let end = rest.find('\'')?;
+    Some(rest[..end].to_string())
+}
+
+/// 取出 `key:'value'` 形式的整行值
+fn line_value(line: &str, key: &str) -> Option<String> {
+    line.strip_prefix(key)?
+        .strip_prefix(":'")?
+        .strip_suffix('\'')
+        .map(str::to_string)
+}
+
+/// 解析 badging 输出，返回 APK 信息及图标在包内的路径
+fn parse_badging(path: &str, output: &str) -> (ApkInfo, Option<String>) {
+    let mut info = ApkInfo {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    // (密度, 路径)，取密度最高的图标
+    let mut icon: Option<(u32, String)> = None;
+
+    for line in output.lines() {
+        let line = line.trim_end_matches('\r');
+        if let Some(rest) = line.strip_prefix("package:") {
+            info.package_name = quoted_value(rest, "name").unwrap_or_default();
+            info.version_code = quoted_value(rest, "versionCode");
+            info.version_name = quoted_value(rest, "versionName");
+        } else if let Some(value) = line_value(line, "sdkVersion") {
+            info.min_sdk = Some(value);
+        } else if let Some(value) = line_value(line, "targetSdkVersion") {
+            info.target_sdk = Some(value);
+        } else if let Some(value) = line_value(line, "application-label") {
+            info.label = Some(value);
+        } else if let Some(rest) = line.strip_prefix("uses-permission:") {
+            if let Some(name) = quoted_value(rest, "name") {
+                info.permissions.push(name);
+            }
+        } else if let Some(rest) = line.strip_prefix("launchable-activity:") {
+            info.launchable_activity = quoted_value(rest, "name");
+        } else if let Some(rest) = line.strip_prefix("native-code:") {
+            info.abis = rest
+                .split('\'')
+                .map(str::trim)
+                .filter(|abi| !abi.is_empty())
+                .map(str::to_string)
+                .collect();
+        } else if let Some(rest) = line.strip_prefix("application-icon-") {
+            let Some((density, value)) = rest.split_once(':') else {
+                continue;
+            };
+            let density = density.parse().unwrap_or(0);
+            let value = value.trim_matches('\'').to_string();
+            if icon.as_ref().is_none_or(|(best, _)| density > *best) {
+                icon = Some((density, value));
+            }
+        }
+    }
+
+    (info, icon.map(|(_, path)| path))
+}
+
+/// 从 APK 中读取图标并编码为 base64，XML 资源（自适应图标）无法直接展示，返回空
+fn read_icon(apk_path: &str, icon_path: &str) -> Option<String> {
+    if icon_path.ends_with(".xml") {
+        return None;
+    }
+    let file = std::fs::File::open(apk_path).ok()?;
+    let mut archive = zip::ZipArchive::new(file).ok()?;
+    let mut entry = archive.by_name(icon_path).ok()?;
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes).ok()?;
+    Some(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+/// 安装前查看 APK 内容：包名、版本、SDK、权限、ABI、启动 Activity 与图标
+#[tauri::command]
+pub async fn analyze_apk(path: String) -> MdtResult<ApkInfo> {
+    if !Path::new(&path).is_file() {
+        return Err(MdtError::invalid_argument("path", format!("文件不存在: {}", path)));
+    }
+    let output = aapt_dump("badging", &path).await?;
+    let (mut info, icon_path) = parse_badging(&path, &output);
+    if let Some(icon_path) = icon_path {
+        let apk_path = path.clone();
+        info.icon_base64 = tauri::async_runtime::spawn_blocking(move || read_icon(&apk_path, &icon_path))
+            .await
+            .ok()
+            .flatten();
+    }
+    Ok(info)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApkSigner {
+    pub subject: String,
+    pub sha256: String,
+}
+
+/// apksigner 校验结果
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ApkSignatureInfo {
+    pub verified: bool,
+    /// 校验通过的签名方案，如 v1 / v2 / v3
+    pub schemes: Vec<String>,
+    pub signers: Vec<ApkSigner>,
+    pub errors: Vec<String>,
+    /// 与设备上已安装版本的证书是否一致，未指定设备或未安装时为空
+    pub matches_installed: Option<bool>,
+}
+
+async fn verify_signature(apk_path: &str) -> MdtResult<ApkSignatureInfo> {
+    let mut cmd = tools::command_for("apksigner");
+    cmd.args(["verify", "--print-certs", "-v", apk_path]);
+    let output = tools::output(cmd)
+        .await
+        .map_err(|e| MdtError::spawn("apksigner", e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+
+    let mut info = ApkSignatureInfo {
+        verified: output.status.success(),
+        ..Default::default()
+    };
+    for line in stdout.lines().chain(stderr.lines()) {
+        let line = line.trim();
+        if let Some(rest) = line.strip_prefix("Verified using ") {
+            // Verified using v2 scheme (APK Signature Scheme v2): true
+            if rest.ends_with(": true") {
+                if let Some(scheme) = rest.split_whitespace().next() {
+                    info.schemes.push(scheme.to_string());
+                }
+            }
+        } else if let Some(rest) = line.strip_prefix("Signer #") {
+            let Some((_, field)) = rest.split_once(' ') else {
+                continue;
+            };
+            if let Some(subject) = field.strip_prefix("certificate DN: ") {
+                info.signers.push(ApkSigner {
+                    subject: subject.to_string(),
+                    sha256: String::new(),
+                });
+            } else if let Some(digest) = field.strip_prefix("certificate SHA-256 digest: ") {
+                if let Some(signer) = info.signers.last_mut() {
+                    signer.sha256 = digest.to_string();
+                }
+            }
+        } else if line.starts_with("ERROR") || line.starts_with("DOES NOT VERIFY") {
+            info.errors.push(line.to_string());
+        }
+    }
+    Ok(info)
+}
+
+/// 拉取设备上已安装的 base APK 并读取其签名证书
+async fn installed_signers(device_id: &Option<String>, package: &str) -> MdtResult<Option<Vec<String>>> {
+    let stdout = adb::adb_shell(device_id, &["pm", "path", &tools::shell_quote(package)])
+        .await
+        .unwrap_or_default();
+    let Some(remote_path) = stdout
+        .lines()
+        .filter_map(|line| line.trim().strip_prefix("package:"))
+        .find(|path| path.ends_with("/base.apk"))
+        .or_else(|| stdout.lines().find_map(|line| line.trim().strip_prefix("package:")))
+    else {
+        return Ok(None);
+    };
+
+    let local_path = std::env::temp_dir().join(format!("mdt_installed_{}.apk", package));
+    let local = local_path.to_string_lossy().to_string();
+    let pulled = adb::adb_run(device_id, &["pull", remote_path, &local]).await;
+    let info = match pulled {
+        Ok(_) => verify_signature(&local).await,
+        Err(e) => Err(e),
+    };
+    let _ = std::fs::remove_file(&local_path);
+    Ok(Some(info?.signers.into_iter().map(|signer| signer.sha256).collect()))
+}
+
+/// 比较两组证书 SHA-256 摘要（忽略顺序）
+fn same_signers(mut local: Vec<String>, mut installed: Vec<String>) -> bool {
+    local.sort_unstable();
+    installed.sort_unstable();
+    !local.is_empty() && local == installed
+}
+
+/// 查看 APK 的签名方案与证书；指定设备时同时对比已安装版本的签名
+#[tauri::command]
+pub async fn apk_signature_info(path: String, device_id: Option<String>) -> MdtResult<ApkSignatureInfo> {
+    if !Path::new(&path).is_file() {
+        return Err(MdtError::invalid_argument("path", format!("文件不存在: {}", path)));
+    }
+    let mut info = verify_signature(&path).await?;
+
+    if device_id.is_some() {
+        let package = package_name(&path).await?;
+        if let Some(installed) = installed_signers(&device_id, &package).await? {
+            let local = info.signers.iter().map(|s| s.sha256.clone()).collect();
+            info.matches_installed = Some(same_signers(local, installed));
+        }
+    }
+    Ok(info)
+}
+
+/// APK 与设备上已安装版本的对比结果
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApkInstallComparison {
+    pub package_name: String,
+    pub apk_version_code: Option<i64>,
+    pub apk_version_name: Option<String>,
+    pub installed_version_code: Option<i64>,
+    pub installed_version_name: Option<String>,
+    /// not_installed / upgrade / same / downgrade / signature_conflict
+    pub status: String,
+    /// 签名是否与已安装版本一致，无法校验（如缺少 apksigner）时为空
+    pub signature_match: Option<bool>,
+    /// 建议附加的安装参数，如降级时的 `-d`
+    pub suggested_flags: Vec<String>,
+}
+
+/// 从 `dumpsys package <pkg>` 中读取已安装的 versionCode / versionName
+fn parse_installed_version(output: &str) -> (Option<i64>, Option<String>) {
+    let mut code = None;
+    let mut name = None;
+    for line in output.lines() {
+        let line = line.trim();
+        if code.is_none() {
+            if let Some(rest) = line.strip_prefix("versionCode=") {
+                code = rest.split_whitespace().next().and_then(|v| v.parse().ok());
+            }
+        }
+        if name.is_none() {
+            if let Some(rest) = line.strip_prefix("versionName=") {
+                name = Some(rest.to_string());
+            }
+        }
+    }
+    (code, name)
+}
+
+/// 安装前对比 APK 与已安装版本，判断是升级、降级（需 `-d`）还是签名冲突
+#[tauri::command]
+pub async fn compare_apk_with_installed(
+    device_id: Option<String>,
+    apk_path: String,
+) -> MdtResult<ApkInstallComparison> {
+    if !Path::new(&apk_path).is_file() {
+        return Err(MdtError::invalid_argument("apkPath", format!("文件不存在: {}", apk_path)));
+    }
+    let badging = aapt_dump("badging", &apk_path).await?;
+    let (info, _) = parse_badging(&apk_path, &badging);
+    let apk_version_code = info.version_code.as_deref().and_then(|v| v.parse().ok());
+
+    let dumpsys = adb::adb_shell(
+        &device_id,
+        &["dumpsys", "package", &tools::shell_quote(&info.package_name)],
+    )
+    .await?;
+    let (installed_version_code, installed_version_name) = parse_installed_version(&dumpsys);
+
+    let mut comparison = ApkInstallComparison {
+        package_name: info.package_name.clone(),
+        apk_version_code,
+        apk_version_name: info.version_name,
+        installed_version_code,
+        installed_version_name,
+        status: "not_installed".to_string(),
+        signature_match: None,
+        suggested_flags: Vec::new(),
+    };
+    let Some(installed) = installed_version_code else {
+        return Ok(comparison);
+    };
+
+    // 签名校验失败（如未安装 apksigner）不影响版本对比
+    if let Ok(local) = verify_signature(&apk_path).await {
+        if let Ok(Some(installed_signers)) = installed_signers(&device_id, &info.package_name).await {
+            let local = local.signers.into_iter().map(|s| s.sha256).collect();
+            comparison.signature_match = Some(same_signers(local, installed_signers));
+        }
+    }
+
+    comparison.status = if comparison.signature_match == Some(false) {
+        "signature_conflict"
+    } else {
+        match apk_version_code.map(|code| code.cmp(&installed)) {
+            Some(std::cmp::Ordering::Greater) | None => "upgrade",
+            Some(std::cmp::Ordering::Equal) => "same",
+            Some(std::cmp::Ordering::Less) => {
+                comparison.suggested_flags.push("-d".to_string());
+                "downgrade"
+            }
+        }
+    }
+    .to_string();
+    Ok(comparison)
+}
@@ -0,0 +1,135 @@
+use serde::{Deserialize, Serialize};
+use crate::error::MdtResult;
+use crate::history;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tauri::{AppHandle, Emitter};
+
+/// 多设备批量操作的进度事件名
+pub const BATCH_PROGRESS_EVENT: &str = "batch-progress";
+
+const DEFAULT_BATCH_PARALLEL: usize = 4;
+
+/// 单台设备的执行结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchDeviceResult {
+    pub device_id: String,
+    pub success: bool,
+    pub output: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchProgress {
+    pub batch_id: String,
+    pub operation: String,
+    pub device_id: String,
+    pub status: String, // "running" | "completed" | "failed"
+    pub output: Option<String>,
+    pub error: Option<String>,
+    pub finished: usize,
+    pub total: usize,
+}
+
+fn new_batch_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    format!("batch-{}-{}", timestamp, COUNTER.fetch_add(1, Ordering::Relaxed))
+}
+
+/// 将设备 ID（可能含 `:` 等字符，如 `192.168.1.2:5555`）转换为可用作文件名的形式
+pub fn file_safe_name(device_id: &str) -> String {
+    device_id
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.') { c } else { '_' })
+        .collect()
+}
+
+/// 在多台设备上并发执行同一操作（并发数受 `parallel` 限制），按设备推送进度事件，
+/// 返回结果与 `device_ids` 顺序一致
+pub async fn run_batch<F, Fut>(
+    app: &AppHandle,
+    operation: &str,
+    device_ids: Vec<String>,
+    parallel: Option<usize>,
+    task: F,
+) -> Vec<BatchDeviceResult>
+where
+    F: Fn(String) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = MdtResult<String>> + Send + 'static,
+{
+    let batch_id = new_batch_id();
+    let total = device_ids.len();
+    let finished = Arc::new(AtomicUsize::new(0));
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(
+        parallel.unwrap_or(DEFAULT_BATCH_PARALLEL).max(1),
+    ));
+    let task = Arc::new(task);
+
+    let mut handles = Vec::new();
+    for device_id in device_ids {
+        let app = app.clone();
+        let batch_id = batch_id.clone();
+        let operation = operation.to_string();
+        let finished = finished.clone();
+        let semaphore = semaphore.clone();
+        let task = task.clone();
+        handles.push(tauri::async_runtime::spawn(async move {
+            // 每台设备在独立任务中执行，需要单独登记才会写入操作历史
+            let _history = history::track();
+            let _permit = semaphore.acquire_owned().await.ok();
+            let emit = |status: &str, output: Option<String>, error: Option<String>, done: usize| {
+                let payload = BatchProgress {
+                    batch_id: batch_id.clone(),
+                    operation: operation.clone(),
+                    device_id: device_id.clone(),
+                    status: status.to_string(),
+                    output,
+                    error,
+                    finished: done,
+                    total,
+                };
+                let _ = app.emit(BATCH_PROGRESS_EVENT, payload);
+            };
+
+            emit("running", None, None, finished.load(Ordering::SeqCst));
+            let result = task(device_id.clone()).await;
+            let done = finished.fetch_add(1, Ordering::SeqCst) + 1;
+            match result {
+                Ok(output) => {
+                    emit("completed", Some(output.clone()), None, done);
+                    BatchDeviceResult {
+                        device_id: device_id.clone(),
+                        success: true,
+                        output: Some(output),
+                        error: None,
+                    }
+                }
+                Err(err) => {
+                    emit("failed", None, Some(err.to_string()), done);
+                    BatchDeviceResult {
+                        device_id: device_id.clone(),
+                        success: false,
+                        output: None,
+                        error: Some(err.to_string()),
+                    }
+                }
+            }
+        }));
+    }
+
+    let mut results = Vec::with_capacity(total);
+    for handle in handles {
+        if let Ok(result) = handle.await {
+            results.push(result);
+        }
+    }
+    results
+}
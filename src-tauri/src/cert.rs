@@ -0,0 +1,184 @@
+use base64::Engine;
+use md5::{Digest, Md5};
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+use std::path::Path;
+
+const SYSTEM_CACERTS_DIR: &str = "/system/etc/security/cacerts";
+/// tmpfs 覆盖前暂存原有系统证书的目录
+const CACERTS_BACKUP_DIR: &str = "/data/local/tmp/mdt-cacerts";
+/// Android 14 起系统证书由 conscrypt APEX 提供（`/apex/com.android.conscrypt/cacerts`）
+const APEX_CACERTS_MIN_SDK: u32 = 34;
+
+/// 读取一个 DER TLV，返回 (tag, 内容起始位置, 结束位置)
+fn read_tlv(data: &[u8], pos: usize) -> Option<(u8, usize, usize)> {
+    let tag = *data.get(pos)?;
+    let first = *data.get(pos + 1)? as usize;
+    let (len, header) = if first < 0x80 {
+        (first, 2)
+    } else {
+        let count = first & 0x7f;
+        if count == 0 || count > 4 {
+            return None;
+        }
+        let mut len = 0usize;
+        for i in 0..count {
+            len = (len << 8) | *data.get(pos + 2 + i)? as usize;
+        }
+        (len, 2 + count)
+    };
+    let start = pos + header;
+    let end = start.checked_add(len)?;
+    (end <= data.len()).then_some((tag, start, end))
+}
+
+/// 从证书 DER 中取出 subject（含 TLV 头）
+fn subject_der(cert: &[u8]) -> Option<&[u8]> {
+    let (_, cert_start, _) = read_tlv(cert, 0)?;
+    let (_, mut pos, _) = read_tlv(cert, cert_start)?; // tbsCertificate
+    // version 为可选的 [0] 显式标签
+    if cert.get(pos) == Some(&0xa0) {
+        pos = read_tlv(cert, pos)?.2;
+    }
+    // serialNumber、signature、issuer、validity
+    for _ in 0..4 {
+        pos = read_tlv(cert, pos)?.2;
+    }
+    let (_, _, end) = read_tlv(cert, pos)?;
+    cert.get(pos..end)
+}
+
+/// 读取 PEM 或 DER 格式的证书，返回 DER 字节
+fn load_certificate(content: &[u8]) -> MdtResult<Vec<u8>> {
+    let text = String::from_utf8_lossy(content);
+    if !text.contains("-----BEGIN CERTIFICATE-----") {
+        return Ok(content.to_vec());
+    }
+    let body: String = text
+        .lines()
+        .skip_while(|line| !line.starts_with("-----BEGIN CERTIFICATE-----"))
+        .skip(1)
+        .take_while(|line| !line.starts_with("-----END CERTIFICATE-----"))
+        .map(str::trim)
+        .collect();
+    base64::engine::general_purpose::STANDARD
+        .decode(body)
+        .map_err(|e| MdtError::invalid_argument("certPath", format!("PEM 解码失败: {}", e)))
+}
+
+/// 计算 Android 系统证书文件名：`openssl x509 -subject_hash_old` 的结果加 `.0`
+fn subject_hash_old(der: &[u8]) -> MdtResult<String> {
+    let subject = subject_der(der).ok_or_else(|| MdtError::invalid_argument("certPath", "无法解析证书 subject"))?;
+    let digest = Md5::digest(subject);
+    let hash = u32::from_le_bytes([digest[0], digest[1], digest[2], digest[3]]);
+    Ok(format!("{:08x}.0", hash))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SystemCertResult {
+    /// 如 `9a5ba575.0`
+    pub file_name: String,
+    pub remote_path: String,
+    /// remount / tmpfs
+    pub method: String,
+    /// tmpfs 挂载在重启后失效
+    pub persistent: bool,
+}
+
+/// 将 CA 证书安装为系统证书（API 24+ 的应用默认不信任用户证书）。
+/// 优先 remount /system 写入；失败时用 tmpfs 覆盖 cacerts 目录（Magisk 常用做法，重启后失效）。
+/// Android 14 起证书目录位于 APEX 中，需逐个进程替换挂载，暂不支持
+#[tauri::command]
+pub async fn adb_install_system_cert(device_id: Option<String>, cert_path: String) -> MdtResult<SystemCertResult> {
+    let _history = history::track();
+    let content = std::fs::read(Path::new(&cert_path)).map_err(|e| MdtError::io("读取证书", e))?;
+    let der = load_certificate(&content)?;
+    let file_name = subject_hash_old(&der)?;
+
+    let sdk = adb::adb_getprop(&device_id, "ro.build.version.sdk")
+        .await
+        .and_then(|sdk| sdk.parse::<u32>().ok());
+    if sdk.is_some_and(|sdk| sdk >= APEX_CACERTS_MIN_SDK) {
+        return Err(MdtError::unsupported(
+            "安装系统证书",
+            "Android 14 起系统证书位于 /apex/com.android.conscrypt/cacerts，无法通过 remount 或 tmpfs 覆盖",
+        ));
+    }
+
+    let whoami = adb::adb_su(&device_id, "id -u").await.unwrap_or_default();
+    if whoami.trim() != "0" {
+        return Err(MdtError::PermissionDenied {
+            detail: "安装系统证书需要 root（su 不可用或未授权）".to_string(),
+        });
+    }
+
+    // 统一以 PEM 推送，系统证书目录中的文件均为 PEM 格式
+    let pem = format!(
+        "-----BEGIN CERTIFICATE-----\n{}\n-----END CERTIFICATE-----\n",
+        base64::engine::general_purpose::STANDARD
+            .encode(&der)
+            .as_bytes()
+            .chunks(64)
+            .map(|chunk| String::from_utf8_lossy(chunk).to_string())
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+    let local_tmp = std::env::temp_dir().join(&file_name);
+    std::fs::write(&local_tmp, pem).map_err(|e| MdtError::io("写入临时证书", e))?;
+    let staged = format!("/data/local/tmp/{}", file_name);
+    let pushed = adb::adb_run(&device_id, &["push", &local_tmp.to_string_lossy(), &staged]).await;
+    let _ = std::fs::remove_file(&local_tmp);
+    pushed?;
+
+    let remote_path = format!("{}/{}", SYSTEM_CACERTS_DIR, file_name);
+    let fix_perms = format!(
+        "chown root:root {path} && chmod 644 {path} && chcon u:object_r:system_file:s0 {path}",
+        path = tools::shell_quote(&remote_path)
+    );
+    let remount = format!(
+        "(mount -o rw,remount /system || mount -o rw,remount /) && cp {} {} && {}",
+        staged, remote_path, fix_perms
+    );
+    let method = if adb::adb_su(&device_id, &remount).await.is_ok() {
+        "remount"
+    } else {
+        // 挂载 tmpfs 后任一步骤失败都要卸载，否则系统证书目录会变成空目录
+        let tmpfs = format!(
+            "set -e; rm -rf {backup}; mkdir -p {backup}; cp {dir}/* {backup}/; mount -t tmpfs tmpfs {dir}; \
+             if ! (cp {backup}/* {dir}/ && cp {staged} {dir}/ && chown root:root {dir}/* && chmod 644 {dir}/* \
+             && chcon u:object_r:system_file:s0 {dir}/*); then umount {dir}; rm -rf {backup}; exit 1; fi; \
+             rm -rf {backup}",
+            dir = SYSTEM_CACERTS_DIR,
+            backup = CACERTS_BACKUP_DIR,
+            staged = staged
+        );
+        adb::adb_su(&device_id, &tmpfs).await.map_err(|e| MdtError::PermissionDenied {
+            detail: format!("无法写入系统证书目录: {}", e),
+        })?;
+        "tmpfs"
+    };
+    let _ = adb::adb_shell(&device_id, &["rm", "-f", &staged]).await;
+
+    let check = adb::adb_shell(&device_id, &["ls", &remote_path]).await.unwrap_or_default();
+    if !check.contains(&file_name) {
+        if method == "tmpfs" {
+            let _ = adb::adb_su(&device_id, &format!("umount {}", SYSTEM_CACERTS_DIR)).await;
+        }
+        return Err(MdtError::CommandFailed {
+            command: format!("install system cert {}", file_name),
+            exit_code: None,
+            stderr: "证书写入后未在系统目录中找到".to_string(),
+        });
+    }
+    println!("[cert] installed {} via {}", file_name, method);
+    Ok(SystemCertResult {
+        file_name,
+        remote_path,
+        method: method.to_string(),
+        persistent: method == "remount",
+    })
+}
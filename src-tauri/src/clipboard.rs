@@ -0,0 +1,217 @@
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::process::Stdio;
+use std::time::Duration;
+
+/// scrcpy 控制协议消息类型
+const SCRCPY_MSG_GET_CLIPBOARD: u8 = 8;
+const SCRCPY_MSG_SET_CLIPBOARD: u8 = 9;
+/// 设备端回传消息类型
+const SCRCPY_DEVICE_MSG_CLIPBOARD: u8 = 0;
+const SCRCPY_DEVICE_MSG_ACK_CLIPBOARD: u8 = 1;
+
+const SCRCPY_CONTROL_TIMEOUT: Duration = Duration::from_secs(5);
+
+enum ClipboardRequest {
+    Get,
+    Set(String),
+}
+
+/// `cmd clipboard` 不可用时的输出特征（旧系统没有该 shell 命令）
+fn is_unsupported(output: &str) -> bool {
+    let lower = output.to_lowercase();
+    lower.contains("unknown command") || lower.contains("no shell command") || lower.contains("can't find service")
+}
+
+/// `cmd clipboard` 被拒绝（如 `SecurityException`）时仍以 0 退出，需要检查输出中的异常行
+fn command_error(command: &str, output: &str) -> MdtResult<()> {
+    match output.lines().find(|line| line.contains("Exception")) {
+        Some(line) => Err(MdtError::CommandFailed {
+            command: command.to_string(),
+            exit_code: None,
+            stderr: line.trim().to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// 连接 scrcpy server 的控制通道，等待 dummy byte 确认 server 已就绪
+fn connect_control(port: u16) -> MdtResult<TcpStream> {
+    let addr = format!("127.0.0.1:{}", port);
+    for _ in 0..30 {
+        if let Ok(mut stream) = TcpStream::connect(&addr) {
+            let _ = stream.set_read_timeout(Some(SCRCPY_CONTROL_TIMEOUT));
+            // server 未监听时 adb forward 会接受连接后立即关闭
+            let mut dummy = [0u8; 1];
+            if stream.read_exact(&mut dummy).is_ok() {
+                return Ok(stream);
+            }
+        }
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    Err(MdtError::io("连接 scrcpy 控制通道", "server 未就绪"))
+}
+
+fn read_u32(stream: &mut TcpStream) -> std::io::Result<u32> {
+    let mut buf = [0u8; 4];
+    stream.read_exact(&mut buf)?;
+    Ok(u32::from_be_bytes(buf))
+}
+
+/// 通过 scrcpy 控制协议读写剪贴板，跳过与本次请求无关的设备消息
+fn scrcpy_clipboard_exchange(port: u16, request: &ClipboardRequest) -> MdtResult<Option<String>> {
+    let mut stream = connect_control(port)?;
+    let mut message = Vec::new();
+    match request {
+        ClipboardRequest::Get => {
+            // copy_key = 0：不模拟复制按键，直接读取当前剪贴板
+            message.extend([SCRCPY_MSG_GET_CLIPBOARD, 0]);
+        }
+        ClipboardRequest::Set(text) => {
+            message.push(SCRCPY_MSG_SET_CLIPBOARD);
+            // 非 0 的 sequence 会让设备回传 ACK
+            message.extend(1u64.to_be_bytes());
+            message.push(0); // paste = false
+            message.extend((text.len() as u32).to_be_bytes());
+            message.extend(text.as_bytes());
+        }
+    }
+    stream
+        .write_all(&message)
+        .map_err(|e| MdtError::io("发送 scrcpy 控制消息", e))?;
+
+    loop {
+        let mut kind = [0u8; 1];
+        stream
+            .read_exact(&mut kind)
+            .map_err(|e| MdtError::io("读取 scrcpy 设备消息", e))?;
+        match kind[0] {
+            SCRCPY_DEVICE_MSG_CLIPBOARD => {
+                let len = read_u32(&mut stream).map_err(|e| MdtError::io("读取剪贴板内容", e))?;
+                let mut text = vec![0u8; len as usize];
+                stream
+                    .read_exact(&mut text)
+                    .map_err(|e| MdtError::io("读取剪贴板内容", e))?;
+                if matches!(request, ClipboardRequest::Get) {
+                    return Ok(Some(String::from_utf8_lossy(&text).to_string()));
+                }
+            }
+            SCRCPY_DEVICE_MSG_ACK_CLIPBOARD => {
+                let mut sequence = [0u8; 8];
+                stream
+                    .read_exact(&mut sequence)
+                    .map_err(|e| MdtError::io("读取剪贴板确认", e))?;
+                if matches!(request, ClipboardRequest::Set(_)) {
+                    return Ok(None);
+                }
+            }
+            other => {
+                return Err(MdtError::io("读取 scrcpy 设备消息", format!("未知消息类型 {}", other)));
+            }
+        }
+    }
+}
+
+/// 临时启动仅含控制通道的 scrcpy server 完成一次剪贴板读写，结束后清理进程与端口转发
+async fn scrcpy_clipboard(device_id: &Option<String>, request: ClipboardRequest) -> MdtResult<Option<String>> {
+    let server_path = adb::resolve_scrcpy_server_path().ok_or_else(|| MdtError::ToolNotFound {
+        tool: "scrcpy-server".to_string(),
+    })?;
+    let server_version = adb::resolve_scrcpy_version()
+        .await
+        .unwrap_or_else(|| adb::DEFAULT_SCRCPY_VERSION.to_string());
+    adb::adb_run(
+        device_id,
+        &["push", &server_path.to_string_lossy(), adb::SCRCPY_SERVER_REMOTE_PATH],
+    )
+    .await?;
+
+    // 使用独立的 scid，避免与正在运行的镜像会话冲突
+    let scid = format!("{:08x}", tools::now_millis() & 0x7fff_ffff);
+    let port = adb::pick_free_port()?;
+    let forward = format!("tcp:{}", port);
+    adb::adb_run(device_id, &["forward", &forward, &format!("localabstract:scrcpy_{}", scid)]).await?;
+
+    let classpath = format!("CLASSPATH={}", adb::SCRCPY_SERVER_REMOTE_PATH);
+    let scid_arg = format!("scid={}", scid);
+    let mut cmd = adb::adb_command(device_id);
+    cmd.args([
+        "shell",
+        &classpath,
+        "app_process",
+        "/",
+        "com.genymobile.scrcpy.Server",
+        &server_version,
+        &scid_arg,
+        "tunnel_forward=true",
+        "video=false",
+        "audio=false",
+        "control=true",
+        "send_device_meta=false",
+        "cleanup=false",
+    ]);
+    let spawned = tokio::process::Command::from(cmd)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            let _ = adb::adb_run(device_id, &["forward", "--remove", &forward]).await;
+            return Err(MdtError::spawn("adb", e));
+        }
+    };
+
+    let result = tauri::async_runtime::spawn_blocking(move || scrcpy_clipboard_exchange(port, &request))
+        .await
+        .unwrap_or_else(|e| Err(MdtError::io("scrcpy 剪贴板任务", e)));
+    let _ = child.kill().await;
+    let _ = adb::adb_run(device_id, &["forward", "--remove", &forward]).await;
+    result
+}
+
+/// 读取设备剪贴板：优先 `cmd clipboard`，不支持时通过 scrcpy 控制通道读取
+#[tauri::command]
+pub async fn adb_get_clipboard(device_id: Option<String>) -> MdtResult<String> {
+    if let Ok(output) = adb::adb_shell(&device_id, &["cmd", "clipboard", "get-primary-clip"]).await {
+        if !is_unsupported(&output) {
+            command_error("cmd clipboard get-primary-clip", &output)?;
+            return Ok(output.trim_end_matches('\n').to_string());
+        }
+    }
+    println!("[clipboard] cmd clipboard unavailable, falling back to scrcpy control");
+    Ok(scrcpy_clipboard(&device_id, ClipboardRequest::Get).await?.unwrap_or_default())
+}
+
+/// 写入设备剪贴板：优先 `cmd clipboard`，不支持时通过 scrcpy 控制通道写入
+#[tauri::command]
+pub async fn adb_set_clipboard(device_id: Option<String>, text: String) -> MdtResult<()> {
+    let _history = history::track();
+    let quoted = tools::shell_quote(&text);
+    if let Ok(output) = adb::adb_shell(&device_id, &["cmd", "clipboard", "set-primary-clip", &quoted]).await {
+        if !is_unsupported(&output) {
+            return command_error("cmd clipboard set-primary-clip", &output);
+        }
+    }
+    println!("[clipboard] cmd clipboard unavailable, falling back to scrcpy control");
+    scrcpy_clipboard(&device_id, ClipboardRequest::Set(text)).await?;
+    Ok(())
+}
+
+/// HarmonyOS 没有可供 hdc shell 读写剪贴板的命令，返回不支持以便界面隐藏该功能
+#[tauri::command]
+pub async fn hdc_get_clipboard(device_id: Option<String>) -> MdtResult<String> {
+    let _ = device_id;
+    Err(MdtError::unsupported("读取剪贴板", "HarmonyOS 未提供 hdc shell 剪贴板命令"))
+}
+
+#[tauri::command]
+pub async fn hdc_set_clipboard(device_id: Option<String>, text: String) -> MdtResult<()> {
+    let _ = (device_id, text);
+    Err(MdtError::unsupported("写入剪贴板", "HarmonyOS 未提供 hdc shell 剪贴板命令"))
+}
@@ -0,0 +1,185 @@
+use crate::adb::InstallOptions;
+use crate::batch;
+use crate::error::{MdtError, MdtResult};
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+const SETTINGS_FILE: &str = "settings";
+/// 默认文件名模板，可用占位符：{kind} {device} {model} {timestamp}
+const DEFAULT_FILENAME_TEMPLATE: &str = "{kind}_{timestamp}";
+
+/// 截图 / 录屏等设备采集产物
+#[derive(Debug, Clone, Copy)]
+pub enum OutputKind {
+    Screenshot,
+    Recording,
+}
+
+impl OutputKind {
+    fn label(self) -> &'static str {
+        match self {
+            OutputKind::Screenshot => "screenshot",
+            OutputKind::Recording => "screenrecord",
+        }
+    }
+}
+
+/// 投屏默认参数，对应 scrcpy-server 的同名选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct MirrorSettings {
+    pub max_size: u32,
+    pub max_fps: u32,
+    /// 视频码率（bps），为空时使用 scrcpy 默认值
+    pub video_bit_rate: Option<u32>,
+}
+
+impl Default for MirrorSettings {
+    fn default() -> Self {
+        Self {
+            max_size: 1920,
+            max_fps: 60,
+            video_bit_rate: None,
+        }
+    }
+}
+
+/// 应用偏好设置，保存在应用数据目录的 settings.json 中
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AppSettings {
+    /// 导出文件的默认目录，为空时使用当前工作目录
+    pub output_dir: Option<String>,
+    /// 截图目录，为空时使用 `output_dir`
+    pub screenshot_dir: Option<String>,
+    /// 录屏目录，为空时使用 `output_dir`
+    pub recording_dir: Option<String>,
+    /// 截图 / 录屏文件名模板（不含扩展名），如 `{model}_{device}_{timestamp}`
+    pub filename_template: Option<String>,
+    /// 未显式传入安装选项时使用的默认安装参数
+    pub install: InstallOptions,
+    /// 工具名到可执行文件路径的映射（如 `adb` -> `/opt/platform-tools/adb`），优先级低于 `MDT_<TOOL>_PATH`
+    pub tool_paths: BTreeMap<String, String>,
+    pub mirror: MirrorSettings,
+}
+
+fn settings() -> &'static Mutex<AppSettings> {
+    static STORE: OnceLock<Mutex<AppSettings>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(store::load(SETTINGS_FILE)))
+}
+
+/// 当前设置的快照
+pub fn current() -> AppSettings {
+    settings().lock().map(|settings| settings.clone()).unwrap_or_default()
+}
+
+/// 设置中为该工具配置的路径（仅当文件存在时生效）
+pub fn tool_path(tool: &str) -> Option<PathBuf> {
+    let settings = settings().lock().ok()?;
+    let path = PathBuf::from(settings.tool_paths.get(tool)?);
+    path.exists().then_some(path)
+}
+
+fn filename_template(settings: &AppSettings) -> String {
+    settings
+        .filename_template
+        .clone()
+        .filter(|template| !template.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_FILENAME_TEMPLATE.to_string())
+}
+
+/// 文件名模板是否用到设备型号，用到时调用方才需要查询型号
+pub fn template_uses_model() -> bool {
+    filename_template(&current()).contains("{model}")
+}
+
+/// 未指定输出路径时，按设置的目录与文件名模板生成截图 / 录屏的本地路径
+pub fn capture_output_path(
+    kind: OutputKind,
+    device_id: &Option<String>,
+    model: Option<&str>,
+    timestamp: u64,
+    extension: &str,
+) -> MdtResult<String> {
+    let settings = current();
+    let dir = match kind {
+        OutputKind::Screenshot => settings.screenshot_dir.clone(),
+        OutputKind::Recording => settings.recording_dir.clone(),
+    }
+    .or_else(|| settings.output_dir.clone())
+    .filter(|dir| !dir.is_empty());
+
+    let file_name = format!(
+        "{}.{}",
+        filename_template(&settings)
+            .replace("{kind}", kind.label())
+            .replace("{device}", &batch::file_safe_name(device_id.as_deref().unwrap_or("default")))
+            .replace("{model}", &batch::file_safe_name(model.unwrap_or("unknown")))
+            .replace("{timestamp}", &timestamp.to_string()),
+        extension
+    );
+    match dir {
+        Some(dir) => {
+            std::fs::create_dir_all(&dir).map_err(|e| MdtError::io("创建输出目录", e))?;
+            Ok(Path::new(&dir).join(file_name).to_string_lossy().to_string())
+        }
+        None => Ok(file_name),
+    }
+}
+
+fn validate(settings: &AppSettings) -> MdtResult<()> {
+    for dir in [&settings.output_dir, &settings.screenshot_dir, &settings.recording_dir]
+        .into_iter()
+        .flatten()
+        .filter(|dir| !dir.is_empty())
+    {
+        std::fs::create_dir_all(dir).map_err(|e| MdtError::io("创建输出目录", e))?;
+    }
+    if let Some(template) = &settings.filename_template {
+        if template.contains(['/', '\\']) {
+            return Err(MdtError::invalid_argument("filenameTemplate", "文件名模板不能包含路径分隔符"));
+        }
+        if !template.trim().is_empty() && !template.contains("{timestamp}") {
+            return Err(MdtError::invalid_argument(
+                "filenameTemplate",
+                "文件名模板需包含 {timestamp}，避免覆盖已有文件",
+            ));
+        }
+    }
+    for (tool, path) in &settings.tool_paths {
+        if !Path::new(path).is_file() {
+            return Err(MdtError::invalid_argument(
+                "toolPaths",
+                format!("{} 的路径不存在: {}", tool, path),
+            ));
+        }
+    }
+    if settings.mirror.max_size == 0 || settings.mirror.max_fps == 0 {
+        return Err(MdtError::invalid_argument("mirror", "投屏分辨率与帧率必须大于 0"));
+    }
+    // 提前校验安装参数，避免保存后每次安装都失败
+    settings.install.to_args()?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_settings() -> MdtResult<AppSettings> {
+    Ok(current())
+}
+
+/// 整体替换设置并写盘，返回保存后的设置
+#[tauri::command]
+pub async fn update_settings(settings: AppSettings) -> MdtResult<AppSettings> {
+    validate(&settings)?;
+    {
+        let mut current = self::settings().lock().map_err(|_| MdtError::lock("settings"))?;
+        // 写盘失败时保持内存中的旧设置，避免两者不一致
+        store::save(SETTINGS_FILE, &settings)?;
+        *current = settings.clone();
+    }
+    println!("[config] settings updated");
+    Ok(settings)
+}
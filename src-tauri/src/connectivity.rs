@@ -0,0 +1,238 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityStatus {
+    pub wifi: Option<bool>,
+    pub mobile_data: Option<bool>,
+    pub bluetooth: Option<bool>,
+    pub airplane_mode: Option<bool>,
+}
+
+/// 读取 global 设置的开关状态（wifi_on 在扫描常开时可能为 2，非 0 即视为开启）
+async fn global_flag(device_id: &Option<String>, key: &str) -> Option<bool> {
+    adb::adb_settings_value(device_id, "global", key)
+        .await
+        .and_then(|value| value.parse::<u8>().ok())
+        .map(|value| value != 0)
+}
+
+#[tauri::command]
+pub async fn adb_connectivity_status(device_id: Option<String>) -> MdtResult<ConnectivityStatus> {
+    Ok(ConnectivityStatus {
+        wifi: global_flag(&device_id, "wifi_on").await,
+        mobile_data: global_flag(&device_id, "mobile_data").await,
+        bluetooth: global_flag(&device_id, "bluetooth_on").await,
+        airplane_mode: global_flag(&device_id, "airplane_mode_on").await,
+    })
+}
+
+/// 旧系统切换飞行模式：写入设置后发送 AIRPLANE_MODE 广播。该广播为受保护广播，
+/// 多数系统不允许 shell 发送，失败时恢复原设置，避免设置与实际状态不一致
+async fn set_airplane_mode_legacy(device_id: &Option<String>, enable: bool) -> MdtResult<()> {
+    let (value, state) = if enable { ("1", "true") } else { ("0", "false") };
+    let previous = adb::adb_settings_value(device_id, "global", "airplane_mode_on")
+        .await
+        .unwrap_or_else(|| "0".to_string());
+    adb::adb_shell(device_id, &["settings", "put", "global", "airplane_mode_on", value]).await?;
+    let broadcast = adb::adb_shell(
+        device_id,
+        &["am", "broadcast", "-a", "android.intent.action.AIRPLANE_MODE", "--ez", "state", state],
+    )
+    .await;
+    let detail = match broadcast {
+        Ok(output) if output.contains("Broadcast completed") && !output.contains("Exception") => return Ok(()),
+        Ok(output) => output,
+        Err(err) => err.to_string(),
+    };
+    if let Err(err) = adb::adb_shell(device_id, &["settings", "put", "global", "airplane_mode_on", &previous]).await {
+        println!("[connectivity] restore airplane_mode_on failed: {}", err);
+    }
+    Err(MdtError::unsupported(
+        "切换飞行模式",
+        format!("Android 11 以下系统不允许 shell 发送飞行模式广播: {}", detail.trim()),
+    ))
+}
+
+/// 开关无线功能：wifi / data / bluetooth / airplane，返回切换后的状态
+#[tauri::command]
+pub async fn adb_connectivity_set(
+    device_id: Option<String>,
+    radio: String,
+    enable: bool,
+) -> MdtResult<ConnectivityStatus> {
+    let _history = history::track();
+    let action = if enable { "enable" } else { "disable" };
+    match radio.as_str() {
+        "wifi" | "data" => {
+            adb::adb_shell(&device_id, &["svc", &radio, action]).await?;
+        }
+        "bluetooth" => {
+            adb::adb_shell(&device_id, &["cmd", "bluetooth_manager", action]).await?;
+        }
+        "airplane" => {
+            // Android 11+ 提供 cmd connectivity，旧系统写设置后发送广播通知各模块
+            let via_cmd = adb::adb_shell(&device_id, &["cmd", "connectivity", "airplane-mode", action]).await;
+            if via_cmd.map(|output| !output.is_empty()).unwrap_or(true) {
+                set_airplane_mode_legacy(&device_id, enable).await?;
+            }
+        }
+        _ => {
+            return Err(MdtError::invalid_argument(
+                "radio",
+                format!("不支持的类型: {}（可选 wifi / data / bluetooth / airplane）", radio),
+            ))
+        }
+    }
+    println!("[connectivity] {} -> {}", radio, enable);
+    // 开关状态异步生效，稍等后再读取
+    tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    adb_connectivity_status(device_id).await
+}
+
+/// 读取全局代理，未设置（`null`）或已清除（`:0`）时为 None
+async fn read_http_proxy(device_id: &Option<String>) -> MdtResult<Option<String>> {
+    let value = adb::adb_shell(device_id, &["settings", "get", "global", "http_proxy"]).await?;
+    let value = value.trim();
+    Ok((!value.is_empty() && value != "null" && value != ":0").then(|| value.to_string()))
+}
+
+#[tauri::command]
+pub async fn adb_get_http_proxy(device_id: Option<String>) -> MdtResult<Option<String>> {
+    read_http_proxy(&device_id).await
+}
+
+/// 校验代理地址与端口，返回 `host:port`
+fn validate_proxy(host: &str, port: u16) -> MdtResult<String> {
+    let host = host.trim();
+    if host.is_empty() || !host.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_')) {
+        return Err(MdtError::invalid_argument("host", format!("无效的代理地址: {}", host)));
+    }
+    if port == 0 {
+        return Err(MdtError::invalid_argument("port", "端口必须大于 0"));
+    }
+    Ok(format!("{}:{}", host, port))
+}
+
+/// 设置全局 HTTP 代理（`host:port`），返回设置后的值
+#[tauri::command]
+pub async fn adb_set_http_proxy(device_id: Option<String>, host: String, port: u16) -> MdtResult<Option<String>> {
+    let _history = history::track();
+    let proxy = validate_proxy(&host, port)?;
+    adb::adb_shell(&device_id, &["settings", "put", "global", "http_proxy", &proxy]).await?;
+    println!("[connectivity] http proxy -> {}", proxy);
+    read_http_proxy(&device_id).await
+}
+
+/// 清除全局代理；直接删除设置需要重启才生效，因此写入 `:0`
+#[tauri::command]
+pub async fn adb_clear_http_proxy(device_id: Option<String>) -> MdtResult<Option<String>> {
+    let _history = history::track();
+    adb::adb_shell(&device_id, &["settings", "put", "global", "http_proxy", ":0"]).await?;
+    println!("[connectivity] http proxy cleared");
+    read_http_proxy(&device_id).await
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrafficCaptureState {
+    /// 推送到设备上的证书路径，拆除时用于清理
+    pub remote_cert_path: Option<String>,
+    pub proxy: Option<String>,
+    /// 配置抓包前的全局代理，拆除时传回以恢复
+    pub previous_proxy: Option<String>,
+    /// 设备经代理能否正常发起请求，不可用时已恢复原来的代理
+    pub proxy_reachable: bool,
+}
+
+/// 探测代理时请求的地址，正常时返回 204
+const PROXY_PROBE_HOST: &str = "connectivitycheck.gstatic.com";
+
+/// 在设备上经代理实际发起一次请求：有 curl 时用 `curl -x` 请求探测地址，
+/// 否则用 toybox nc 向代理发送 HTTP CONNECT，代理返回 2xx 状态行才视为可用
+async fn probe_proxy(device_id: &Option<String>, host: &str, port: u16) -> bool {
+    let script = format!(
+        "if command -v curl >/dev/null 2>&1; then \
+         curl -s -o /dev/null -m 5 -w 'HTTP/1.1 %{{http_code}}\\n' -x http://{host}:{port} http://{target}/generate_204; \
+         else printf 'CONNECT {target}:443 HTTP/1.1\\r\\nHost: {target}:443\\r\\n\\r\\n' | nc -w 5 {host} {port}; fi",
+        host = host,
+        port = port,
+        target = PROXY_PROBE_HOST,
+    );
+    let Ok(output) = adb::adb_shell(device_id, &["sh", "-c", &tools::shell_quote(&script)]).await else {
+        return false;
+    };
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("HTTP/"))
+        .and_then(|status| status.split_whitespace().nth(1))
+        .is_some_and(|code| code.starts_with('2'))
+}
+
+/// 恢复配置抓包前的全局代理，原来未设置代理时写入 `:0` 清除
+async fn restore_http_proxy(device_id: &Option<String>, previous: &Option<String>) -> MdtResult<()> {
+    let value = previous.as_deref().unwrap_or(":0");
+    adb::adb_shell(device_id, &["settings", "put", "global", "http_proxy", &tools::shell_quote(value)]).await?;
+    println!("[connectivity] http proxy restored -> {}", value);
+    Ok(())
+}
+
+/// 一键配置抓包：推送 CA 证书并打开安装界面、设置全局代理，再检查设备到代理的连通性
+#[tauri::command]
+pub async fn setup_traffic_capture(
+    app: AppHandle,
+    device_id: Option<String>,
+    proxy_host: String,
+    proxy_port: u16,
+    cert_path: String,
+) -> MdtResult<TrafficCaptureState> {
+    let _history = history::track();
+    validate_proxy(&proxy_host, proxy_port)?;
+    let previous_proxy = read_http_proxy(&device_id).await?;
+    let remote_cert_path = adb::adb_push_certificate(app, device_id.clone(), cert_path, None).await?;
+    // 用户证书必须在设备上手动确认安装
+    adb::adb_open_cert_installer(device_id.clone(), remote_cert_path.clone()).await?;
+    let mut proxy = adb_set_http_proxy(device_id.clone(), proxy_host.clone(), proxy_port).await?;
+    let proxy_reachable = probe_proxy(&device_id, proxy_host.trim(), proxy_port).await;
+    if !proxy_reachable {
+        // 不可用的全局代理会让设备断网，恢复为原来的代理
+        println!("[connectivity] proxy {}:{} unreachable from device", proxy_host, proxy_port);
+        if let Err(err) = restore_http_proxy(&device_id, &previous_proxy).await {
+            println!("[connectivity] restore http proxy failed: {}", err);
+        }
+        proxy = read_http_proxy(&device_id).await?;
+    }
+    Ok(TrafficCaptureState {
+        remote_cert_path: Some(remote_cert_path),
+        proxy,
+        previous_proxy,
+        proxy_reachable,
+    })
+}
+
+/// 拆除抓包配置：恢复配置前的全局代理（`previous_proxy` 为空时清除）并删除推送的证书文件
+/// （已安装的用户证书需在系统设置中移除）
+#[tauri::command]
+pub async fn teardown_traffic_capture(
+    device_id: Option<String>,
+    remote_cert_path: Option<String>,
+    previous_proxy: Option<String>,
+) -> MdtResult<TrafficCaptureState> {
+    let _history = history::track();
+    restore_http_proxy(&device_id, &previous_proxy).await?;
+    let proxy = read_http_proxy(&device_id).await?;
+    if let Some(path) = &remote_cert_path {
+        adb::adb_shell(&device_id, &["rm", "-f", &tools::shell_quote(path)]).await?;
+    }
+    Ok(TrafficCaptureState {
+        remote_cert_path: None,
+        proxy,
+        previous_proxy: None,
+        proxy_reachable: false,
+    })
+}
@@ -0,0 +1,214 @@
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter};
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+/// 录屏转换进度事件名
+pub const CONVERSION_PROGRESS_EVENT: &str = "conversion-progress";
+
+/// GIF 未指定帧率 / 宽度时的默认值，控制文件体积
+const DEFAULT_GIF_FPS: u32 = 10;
+const DEFAULT_GIF_WIDTH: u32 = 480;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversionFormat {
+    Gif,
+    Webm,
+}
+
+impl ConversionFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ConversionFormat::Gif => "gif",
+            ConversionFormat::Webm => "webm",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversionProgress {
+    pub input_path: String,
+    pub output_path: String,
+    /// 已转换的时长（秒）
+    pub processed_secs: f64,
+    /// 读取到源文件时长后才有百分比
+    pub percent: Option<f64>,
+    pub status: String, // "running" | "completed" | "failed"
+}
+
+/// 解析 ffmpeg 错误输出中的 `Duration: 00:01:02.50` 为微秒
+fn parse_duration_us(line: &str) -> Option<u64> {
+    let rest = line.trim().strip_prefix("Duration:")?;
+    let value = rest.split(',').next()?.trim();
+    let mut parts = value.split(':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    Some(((hours * 3600.0 + minutes * 60.0 + seconds) * 1_000_000.0) as u64)
+}
+
+/// 生成 ffmpeg 参数；GIF 先生成调色板再映射，画质明显好于默认的 256 色抖动
+fn build_args(format: ConversionFormat, fps: Option<u32>, scale: Option<u32>) -> Vec<String> {
+    match format {
+        ConversionFormat::Gif => {
+            let fps = fps.unwrap_or(DEFAULT_GIF_FPS);
+            let width = scale.unwrap_or(DEFAULT_GIF_WIDTH);
+            let filter = format!(
+                "fps={},scale={}:-1:flags=lanczos,split[s0][s1];[s0]palettegen[p];[s1][p]paletteuse",
+                fps, width
+            );
+            vec!["-vf".into(), filter, "-loop".into(), "0".into()]
+        }
+        ConversionFormat::Webm => {
+            let mut filters = Vec::new();
+            if let Some(fps) = fps {
+                filters.push(format!("fps={}", fps));
+            }
+            // VP9 要求偶数尺寸，高度用 -2 按比例取偶数
+            if let Some(width) = scale {
+                filters.push(format!("scale={}:-2", width));
+            }
+            let mut args: Vec<String> = Vec::new();
+            if !filters.is_empty() {
+                args.push("-vf".into());
+                args.push(filters.join(","));
+            }
+            args.extend(
+                ["-c:v", "libvpx-vp9", "-b:v", "0", "-crf", "35", "-row-mt", "1", "-c:a", "libopus"]
+                    .map(String::from),
+            );
+            args
+        }
+    }
+}
+
+/// 用 ffmpeg 将录屏转换为 GIF 或 WebM，输出到源文件同目录的同名文件，通过事件推送进度，返回输出路径
+#[tauri::command]
+pub async fn convert_recording(
+    app: AppHandle,
+    input_path: String,
+    format: ConversionFormat,
+    fps: Option<u32>,
+    scale: Option<u32>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    if !Path::new(&input_path).is_file() {
+        return Err(MdtError::invalid_argument("inputPath", format!("文件不存在: {}", input_path)));
+    }
+    if fps == Some(0) {
+        return Err(MdtError::invalid_argument("fps", "帧率必须大于 0"));
+    }
+    if scale == Some(0) {
+        return Err(MdtError::invalid_argument("scale", "宽度必须大于 0"));
+    }
+    let output_path = Path::new(&input_path)
+        .with_extension(format.extension())
+        .to_string_lossy()
+        .to_string();
+    if output_path == input_path {
+        return Err(MdtError::invalid_argument("format", "源文件已是目标格式"));
+    }
+
+    let mut cmd = tools::command_for("ffmpeg");
+    cmd.args(["-y", "-hide_banner", "-nostats", "-progress", "pipe:1", "-i", &input_path]);
+    cmd.args(build_args(format, fps, scale));
+    cmd.arg(&output_path);
+    let command = tools::describe("ffmpeg", &cmd);
+    let invocation = history::capture(&cmd);
+    let started = Instant::now();
+    let mut child = tokio::process::Command::from(cmd)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| MdtError::spawn("ffmpeg", e))?;
+
+    // 源文件时长从错误输出的文件信息中读取，用于计算百分比
+    let total_us = Arc::new(AtomicU64::new(0));
+    let stderr_task = child.stderr.take().map(|err| {
+        let total_us = total_us.clone();
+        tauri::async_runtime::spawn(async move {
+            use tokio::io::AsyncBufReadExt;
+            let mut lines = tokio::io::BufReader::new(err).lines();
+            let mut stderr = String::new();
+            while let Ok(Some(line)) = lines.next_line().await {
+                if let Some(duration) = parse_duration_us(&line) {
+                    total_us.compare_exchange(0, duration, Ordering::SeqCst, Ordering::SeqCst).ok();
+                }
+                stderr.push_str(&line);
+                stderr.push('\n');
+            }
+            stderr
+        })
+    });
+
+    // `-progress` 每次输出一组 key=value，以 `progress=continue|end` 结尾；
+    // `end` 只表示输出结束，是否成功要等进程退出后才能确定
+    let mut processed_us = 0u64;
+    if let Some(out) = child.stdout.take() {
+        use tokio::io::AsyncBufReadExt;
+        let mut lines = tokio::io::BufReader::new(out).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let Some((key, value)) = line.trim().split_once('=') else {
+                continue;
+            };
+            match key {
+                "out_time_us" => processed_us = value.parse().unwrap_or(processed_us),
+                "progress" if value == "continue" => {
+                    let total = total_us.load(Ordering::SeqCst);
+                    let percent = (total > 0).then(|| (processed_us as f64 / total as f64 * 100.0).min(100.0));
+                    let payload = ConversionProgress {
+                        input_path: input_path.clone(),
+                        output_path: output_path.clone(),
+                        processed_secs: processed_us as f64 / 1_000_000.0,
+                        percent,
+                        status: "running".to_string(),
+                    };
+                    let _ = app.emit(CONVERSION_PROGRESS_EVENT, payload);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| MdtError::io("等待 ffmpeg", e))?;
+    let stderr = match stderr_task {
+        Some(task) => task.await.unwrap_or_default(),
+        None => String::new(),
+    };
+    let output = std::process::Output {
+        status,
+        stdout: Vec::new(),
+        stderr: stderr.into_bytes(),
+    };
+    history::record(invocation, started.elapsed(), Ok(&output));
+    let succeeded = output.status.success();
+    let total = total_us.load(Ordering::SeqCst);
+    let payload = ConversionProgress {
+        input_path: input_path.clone(),
+        output_path: output_path.clone(),
+        processed_secs: processed_us as f64 / 1_000_000.0,
+        percent: if succeeded {
+            (total > 0).then_some(100.0)
+        } else {
+            (total > 0).then(|| (processed_us as f64 / total as f64 * 100.0).min(100.0))
+        },
+        status: if succeeded { "completed" } else { "failed" }.to_string(),
+    };
+    let _ = app.emit(CONVERSION_PROGRESS_EVENT, payload);
+    if !succeeded {
+        return Err(MdtError::from_output(command, &None, &output));
+    }
+    Ok(output_path)
+}
@@ -0,0 +1,324 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::perf;
+use crate::tools;
+use std::process::Stdio;
+use std::sync::atomic::Ordering;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// tombstone 头部解析结果（崩溃线程信息）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TombstoneReport {
+    pub path: String,
+    pub build_fingerprint: Option<String>,
+    pub abi: Option<String>,
+    pub timestamp: Option<String>,
+    pub pid: Option<u32>,
+    pub tid: Option<u32>,
+    pub thread_name: Option<String>,
+    pub process_name: Option<String>,
+    /// 如 `SIGSEGV`
+    pub signal: Option<String>,
+    pub signal_number: Option<i32>,
+    pub code: Option<String>,
+    pub fault_addr: Option<String>,
+    pub abort_message: Option<String>,
+    /// 崩溃线程的调用栈
+    pub backtrace: Vec<String>,
+}
+
+fn strip_quotes(value: &str) -> String {
+    value.trim().trim_matches('\'').to_string()
+}
+
+/// 解析 `pid: 1234, tid: 1250, name: RenderThread  >>> com.demo <<<`
+fn parse_pid_line(report: &mut TombstoneReport, line: &str) {
+    let (ids, process) = match line.split_once(">>>") {
+        Some((ids, rest)) => (ids, rest.split("<<<").next().map(|p| p.trim().to_string())),
+        None => (line, None),
+    };
+    report.process_name = process;
+    for field in ids.split(',') {
+        let Some((key, value)) = field.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "pid" => report.pid = value.parse().ok(),
+            "tid" => report.tid = value.parse().ok(),
+            "name" => report.thread_name = Some(value.to_string()),
+            _ => {}
+        }
+    }
+}
+
+/// 解析 `signal 11 (SIGSEGV), code 1 (SEGV_MAPERR), fault addr 0x0`
+fn parse_signal_line(report: &mut TombstoneReport, line: &str) {
+    let mut parts = line.splitn(3, ", ");
+    if let Some(signal) = parts.next().and_then(|s| s.strip_prefix("signal ")) {
+        let mut tokens = signal.split_whitespace();
+        report.signal_number = tokens.next().and_then(|n| n.parse().ok());
+        report.signal = tokens.next().map(|name| name.trim_matches(['(', ')']).to_string());
+    }
+    if let Some(code) = parts.next().and_then(|s| s.strip_prefix("code ")) {
+        report.code = Some(code.trim().to_string());
+    }
+    if let Some(addr) = parts.next().and_then(|s| s.strip_prefix("fault addr ")) {
+        report.fault_addr = Some(addr.trim().to_string());
+    }
+}
+
+/// 解析 tombstone 文本（也适用于 logcat 中 DEBUG 标签输出的 native crash 块）
+pub fn parse_tombstone(path: &str, content: &str) -> TombstoneReport {
+    let mut report = TombstoneReport {
+        path: path.to_string(),
+        ..Default::default()
+    };
+    let mut in_backtrace = false;
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if in_backtrace {
+            if trimmed.starts_with('#') {
+                report.backtrace.push(trimmed.to_string());
+                continue;
+            }
+            if report.backtrace.is_empty() && trimmed.is_empty() {
+                continue;
+            }
+            // 只保留第一段（崩溃线程）的调用栈
+            break;
+        }
+
+        if let Some(value) = trimmed.strip_prefix("Build fingerprint:") {
+            report.build_fingerprint = Some(strip_quotes(value));
+        } else if let Some(value) = trimmed.strip_prefix("ABI:") {
+            report.abi = Some(strip_quotes(value));
+        } else if let Some(value) = trimmed.strip_prefix("Timestamp:") {
+            report.timestamp = Some(value.trim().to_string());
+        } else if trimmed.starts_with("pid:") && report.pid.is_none() {
+            parse_pid_line(&mut report, trimmed);
+        } else if trimmed.starts_with("signal ") && report.signal.is_none() {
+            parse_signal_line(&mut report, trimmed);
+        } else if let Some(value) = trimmed.strip_prefix("Abort message:") {
+            report.abort_message = Some(strip_quotes(value));
+        } else if trimmed == "backtrace:" {
+            in_backtrace = true;
+        }
+    }
+    report
+}
+
+/// 拉取 `/data/tombstones/` 下的文本 tombstone 并解析崩溃信息（跳过 .pb 格式）
+#[tauri::command]
+pub async fn adb_pull_tombstones(
+    device_id: Option<String>,
+    output_dir: String,
+) -> MdtResult<Vec<TombstoneReport>> {
+    let _history = history::track();
+    let files = adb::adb_collect_protected(&device_id, "/data/tombstones", &output_dir).await?;
+    let mut reports = Vec::new();
+    for path in files.into_iter().filter(|path| !path.ends_with(".pb")) {
+        let content = std::fs::read(&path).map_err(|e| MdtError::io("读取 tombstone", e))?;
+        reports.push(parse_tombstone(&path, &String::from_utf8_lossy(&content)));
+    }
+    Ok(reports)
+}
+
+/// 应用崩溃事件名
+pub const APP_CRASHED_EVENT: &str = "app-crashed";
+
+/// 缓冲区空闲超过该时长时，认为当前崩溃块已输出完毕
+const CRASH_BLOCK_IDLE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CrashReport {
+    pub device_id: Option<String>,
+    pub timestamp: u64,
+    /// java / native
+    pub kind: String,
+    pub package: Option<String>,
+    pub pid: Option<u32>,
+    pub thread: Option<String>,
+    /// Java 异常首行，如 `java.lang.RuntimeException: boom`；native 为信号描述
+    pub exception: Option<String>,
+    pub stack_trace: Vec<String>,
+    /// native 崩溃的 tombstone 解析结果
+    pub tombstone: Option<TombstoneReport>,
+    pub raw: String,
+}
+
+struct CrashBlock {
+    kind: &'static str,
+    tag: String,
+    lines: Vec<String>,
+}
+
+/// 拆分 `-v brief` 格式的日志行：`E/AndroidRuntime( 1234): message`
+fn split_brief(line: &str) -> Option<(&str, &str)> {
+    let (head, message) = line.split_once("): ")?;
+    let tag = head.split_once('/')?.1.split('(').next()?.trim();
+    Some((tag, message))
+}
+
+fn parse_java_crash(device_id: &Option<String>, lines: &[String]) -> CrashReport {
+    let mut report = CrashReport {
+        device_id: device_id.clone(),
+        timestamp: tools::now_millis(),
+        kind: "java".to_string(),
+        package: None,
+        pid: None,
+        thread: None,
+        exception: None,
+        stack_trace: Vec::new(),
+        tombstone: None,
+        raw: lines.join("\n"),
+    };
+    for line in lines {
+        let trimmed = line.trim();
+        if let Some(thread) = trimmed.strip_prefix("FATAL EXCEPTION:") {
+            report.thread = Some(thread.trim().to_string());
+        } else if let Some(rest) = trimmed.strip_prefix("Process:") {
+            // Process: com.demo, PID: 1234
+            let mut parts = rest.split(", PID:");
+            report.package = parts.next().map(|p| p.trim().to_string());
+            report.pid = parts.next().and_then(|pid| pid.trim().parse().ok());
+        } else if report.exception.is_none() {
+            report.exception = Some(trimmed.to_string());
+        } else if !trimmed.is_empty() {
+            report.stack_trace.push(trimmed.to_string());
+        }
+    }
+    report
+}
+
+fn parse_native_crash(device_id: &Option<String>, lines: &[String]) -> CrashReport {
+    let raw = lines.join("\n");
+    let tombstone = parse_tombstone("logcat", &raw);
+    CrashReport {
+        device_id: device_id.clone(),
+        timestamp: tools::now_millis(),
+        kind: "native".to_string(),
+        package: tombstone.process_name.clone(),
+        pid: tombstone.pid,
+        thread: tombstone.thread_name.clone(),
+        exception: tombstone.signal.as_ref().map(|signal| match &tombstone.abort_message {
+            Some(message) => format!("{}: {}", signal, message),
+            None => signal.clone(),
+        }),
+        stack_trace: tombstone.backtrace.clone(),
+        tombstone: Some(tombstone),
+        raw,
+    }
+}
+
+fn flush_block(app: &AppHandle, device_id: &Option<String>, package: &Option<String>, block: CrashBlock) {
+    let report = match block.kind {
+        "java" => parse_java_crash(device_id, &block.lines),
+        _ => parse_native_crash(device_id, &block.lines),
+    };
+    let matches = match (package, &report.package) {
+        (Some(filter), Some(name)) => name == filter || name.starts_with(&format!("{}:", filter)),
+        (Some(_), None) => false,
+        (None, _) => true,
+    };
+    if matches {
+        println!("[crash] {} crash detected: {:?}", report.kind, report.package);
+        let _ = app.emit(APP_CRASHED_EVENT, report);
+    }
+}
+
+/// 监听 `logcat -b crash`，检测到 Java / native 崩溃时推送 `app-crashed` 事件
+#[tauri::command]
+pub async fn adb_watch_crashes(
+    app: AppHandle,
+    device_id: Option<String>,
+    package: Option<String>,
+) -> MdtResult<()> {
+    let device_key = tools::device_key(&device_id);
+    // 只监听从现在开始的日志，避免重复上报历史崩溃
+    let since = adb::adb_shell(&device_id, &["date", "+'%m-%d %H:%M:%S.000'"]).await?;
+    let stop_flag = perf::register_monitor("crash watcher", &device_key)?;
+
+    let mut cmd = adb::adb_command(&device_id);
+    // adb logcat 的参数同样由设备端 shell 解析，时间中的空格需要加引号
+    cmd.args(["logcat", "-b", "crash", "-v", "brief", "-T", &tools::shell_quote(since.trim())]);
+    let spawned = tokio::process::Command::from(cmd)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn();
+    let mut child = match spawned {
+        Ok(child) => child,
+        Err(e) => {
+            perf::finish_monitor("crash watcher", &device_key, &stop_flag);
+            return Err(MdtError::spawn("adb", e));
+        }
+    };
+    let Some(stdout) = child.stdout.take() else {
+        perf::finish_monitor("crash watcher", &device_key, &stop_flag);
+        return Err(MdtError::io("启动 logcat", "无法读取输出"));
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let mut lines = BufReader::new(stdout).lines();
+        let mut block: Option<CrashBlock> = None;
+        while !stop_flag.load(Ordering::SeqCst) {
+            let line = match tokio::time::timeout(CRASH_BLOCK_IDLE, lines.next_line()).await {
+                Err(_) => {
+                    if let Some(finished) = block.take() {
+                        flush_block(&app, &device_id, &package, finished);
+                    }
+                    continue;
+                }
+                Ok(Ok(Some(line))) => line,
+                // logcat 退出（如设备断开）
+                _ => break,
+            };
+            let Some((tag, message)) = split_brief(&line) else {
+                continue;
+            };
+
+            let kind = if tag == "AndroidRuntime" && message.starts_with("FATAL EXCEPTION") {
+                Some("java")
+            } else if message.starts_with("*** *** ***") {
+                Some("native")
+            } else {
+                None
+            };
+            if let Some(kind) = kind {
+                if let Some(finished) = block.take() {
+                    flush_block(&app, &device_id, &package, finished);
+                }
+                block = Some(CrashBlock {
+                    kind,
+                    tag: tag.to_string(),
+                    lines: vec![message.to_string()],
+                });
+            } else if let Some(current) = block.as_mut() {
+                if current.tag == tag {
+                    current.lines.push(message.to_string());
+                } else if let Some(finished) = block.take() {
+                    flush_block(&app, &device_id, &package, finished);
+                }
+            }
+        }
+        if let Some(finished) = block.take() {
+            flush_block(&app, &device_id, &package, finished);
+        }
+        let _ = child.kill().await;
+        perf::finish_monitor("crash watcher", &device_key, &stop_flag);
+        println!("[crash] watcher stopped for {}", device_key);
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn adb_stop_crash_watch(device_id: Option<String>) -> MdtResult<()> {
+    perf::stop_monitor("crash watcher", &tools::device_key(&device_id))
+}
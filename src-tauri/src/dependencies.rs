@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::process::Command;
+use crate::error::MdtResult;
 use crate::tools;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -82,7 +83,7 @@ fn check_command(command: &str, version_args: &[&str]) -> DependencyInfo {
 }
 
 #[tauri::command]
-pub async fn check_dependencies() -> Result<DependenciesStatus, String> {
+pub async fn check_dependencies() -> MdtResult<DependenciesStatus> {
     // 检测 adb
     let adb = Some(check_command("adb", &["version"]));
 
@@ -0,0 +1,189 @@
+use crate::error::MdtResult;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::async_runtime::JoinHandle;
+
+/// 设备信息缓存的有效期，界面轮询时在此期间内直接返回缓存
+const DEVICE_INFO_TTL: Duration = Duration::from_secs(30);
+/// 后台预取失败后，在此期间内不再重试同一设备（如未授权或离线中的设备）
+const PREFETCH_RETRY_BACKOFF: Duration = Duration::from_secs(60);
+/// 未指定设备时使用的缓存键，对应当前唯一连接的设备
+const DEFAULT_KEY: &str = "default";
+
+/// adb 与 hdc 共用的扩展设备信息，展开到各自的 DeviceInfo 中
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceDetails {
+    /// 当前生效的分辨率，如 `1080x2400`
+    pub resolution: Option<String>,
+    pub density: Option<u32>,
+    pub abis: Vec<String>,
+    pub sdk_level: Option<u32>,
+    pub total_ram_kb: Option<u64>,
+    pub available_ram_kb: Option<u64>,
+    pub cpu_hardware: Option<String>,
+    pub kernel_version: Option<String>,
+    pub uptime_secs: Option<u64>,
+    pub wifi_mac: Option<String>,
+    pub wifi_ip: Option<String>,
+    pub serial: Option<String>,
+}
+
+/// 按设备缓存设备信息，避免每次查询都启动十余个 shell 进程
+pub(crate) struct InfoCache<T> {
+    entries: Mutex<HashMap<String, (Instant, T)>>,
+    prefetch: Mutex<PrefetchState>,
+}
+
+/// 后台预取的状态：正在采集的设备、最近一次采集失败的时间与上次的在线设备列表
+#[derive(Default)]
+struct PrefetchState {
+    in_flight: HashSet<String>,
+    failed_at: HashMap<String, Instant>,
+    online: Vec<String>,
+}
+
+impl<T> Default for InfoCache<T> {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+            prefetch: Mutex::new(PrefetchState::default()),
+        }
+    }
+}
+
+impl<T: Clone> InfoCache<T> {
+    /// 取出未过期的缓存
+    pub(crate) fn get(&self, key: &str) -> Option<T> {
+        let entries = self.entries.lock().ok()?;
+        entries
+            .get(key)
+            .filter(|(cached_at, _)| cached_at.elapsed() < DEVICE_INFO_TTL)
+            .map(|(_, info)| info.clone())
+    }
+
+    pub(crate) fn put(&self, key: String, info: T) {
+        if let Ok(mut entries) = self.entries.lock() {
+            entries.insert(key, (Instant::now(), info));
+        }
+    }
+
+    /// 根据最新的在线设备列表清理已断开设备的缓存，返回需要后台预取的设备并标记为采集中；
+    /// 已在采集或最近采集失败的设备不会重复返回，设备列表变化时未指定设备的缓存同时失效
+    pub(crate) fn sync_online(&self, online: &[String]) -> Vec<String> {
+        let (Ok(mut entries), Ok(mut prefetch)) = (self.entries.lock(), self.prefetch.lock()) else {
+            return Vec::new();
+        };
+        let changed = prefetch.online != online;
+        entries.retain(|key, _| if key == DEFAULT_KEY { !changed } else { online.contains(key) });
+        prefetch.failed_at.retain(|key, failed_at| {
+            online.contains(key) && failed_at.elapsed() < PREFETCH_RETRY_BACKOFF
+        });
+        prefetch.online = online.to_vec();
+
+        let pending: Vec<String> = online
+            .iter()
+            .filter(|id| {
+                !entries.contains_key(*id)
+                    && !prefetch.in_flight.contains(*id)
+                    && !prefetch.failed_at.contains_key(*id)
+            })
+            .cloned()
+            .collect();
+        prefetch.in_flight.extend(pending.iter().cloned());
+        pending
+    }
+
+    /// 后台预取结束：成功时写入缓存，失败时记录时间以便退避
+    pub(crate) fn finish_prefetch(&self, key: String, info: Option<T>) {
+        if let Ok(mut prefetch) = self.prefetch.lock() {
+            prefetch.in_flight.remove(&key);
+            if info.is_none() {
+                prefetch.failed_at.insert(key.clone(), Instant::now());
+            }
+        }
+        if let Some(info) = info {
+            self.put(key, info);
+        }
+    }
+}
+
+/// 在后台执行一次查询，失败时结果为 None，用于并发采集设备信息
+pub(crate) fn spawn_query<F>(query: F) -> JoinHandle<Option<String>>
+where
+    F: Future<Output = MdtResult<String>> + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        query.await.ok().map(|value| value.trim().to_string()).filter(|value| !value.is_empty())
+    })
+}
+
+pub(crate) async fn join_query(handle: JoinHandle<Option<String>>) -> Option<String> {
+    handle.await.ok().flatten()
+}
+
+/// 从 `/proc/meminfo` 取出 MemTotal 与 MemAvailable（单位 kB）
+pub(crate) fn parse_meminfo(output: &str) -> (Option<u64>, Option<u64>) {
+    let field = |name: &str| {
+        output.lines().find_map(|line| {
+            line.strip_prefix(name)?
+                .trim_start_matches(':')
+                .split_whitespace()
+                .next()?
+                .parse()
+                .ok()
+        })
+    };
+    (field("MemTotal"), field("MemAvailable"))
+}
+
+/// `/proc/uptime` 的第一列为开机秒数
+pub(crate) fn parse_uptime(output: &str) -> Option<u64> {
+    let secs: f64 = output.split_whitespace().next()?.parse().ok()?;
+    Some(secs as u64)
+}
+
+/// `/proc/cpuinfo` 的 `Hardware : Qualcomm ...` 行（部分内核不输出）
+pub(crate) fn parse_cpu_hardware(output: &str) -> Option<String> {
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        (key.trim() == "Hardware").then(|| value.trim().to_string())
+    })
+}
+
+/// 兼容 `ip addr show wlan0` 与 toybox `ifconfig wlan0` 两种输出，返回 (MAC, IPv4)
+pub(crate) fn parse_interface(output: &str) -> (Option<String>, Option<String>) {
+    let mut mac = None;
+    let mut ip = None;
+    for line in output.lines() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        for pair in tokens.windows(2) {
+            match pair[0] {
+                "link/ether" | "HWaddr" if mac.is_none() => mac = Some(pair[1].to_string()),
+                "inet" if ip.is_none() => {
+                    let address = pair[1].trim_start_matches("addr:");
+                    ip = Some(address.split('/').next().unwrap_or(address).to_string());
+                }
+                _ => {}
+            }
+        }
+    }
+    (mac, ip)
+}
+
+/// `ro.product.cpu.abilist` 等逗号分隔的 ABI 列表
+pub(crate) fn parse_abis(value: Option<String>) -> Vec<String> {
+    value
+        .map(|value| {
+            value
+                .split(',')
+                .map(str::trim)
+                .filter(|abi| !abi.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default()
+}
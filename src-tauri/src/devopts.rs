@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+
+/// 开发者选项中的三项动画缩放
+const ANIMATION_SCALE_KEYS: [&str; 3] = [
+    "window_animation_scale",
+    "transition_animation_scale",
+    "animator_duration_scale",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnimationScales {
+    /// 未设置过时为 None（系统默认 1.0）
+    pub window: Option<f32>,
+    pub transition: Option<f32>,
+    pub animator: Option<f32>,
+}
+
+#[tauri::command]
+pub async fn adb_get_animation_scale(device_id: Option<String>) -> MdtResult<AnimationScales> {
+    let mut values = Vec::new();
+    for key in ANIMATION_SCALE_KEYS {
+        values.push(adb::adb_settings_value(&device_id, "global", key).await.and_then(|v| v.parse().ok()));
+    }
+    Ok(AnimationScales {
+        window: values[0],
+        transition: values[1],
+        animator: values[2],
+    })
+}
+
+/// 同时设置窗口、过渡和动画时长缩放，0 为关闭动画（UI 测试常用）
+#[tauri::command]
+pub async fn adb_set_animation_scale(device_id: Option<String>, scale: f32) -> MdtResult<AnimationScales> {
+    let _history = history::track();
+    if !(0.0..=10.0).contains(&scale) {
+        return Err(MdtError::invalid_argument("scale", "缩放需在 0 到 10 之间"));
+    }
+    let value = scale.to_string();
+    for key in ANIMATION_SCALE_KEYS {
+        adb::adb_shell(&device_id, &["settings", "put", "global", key, &value]).await?;
+    }
+    println!("[devopts] animation scale -> {}", scale);
+    adb_get_animation_scale(device_id).await
+}
+
+const DEMO_ACTION: &str = "com.android.systemui.demo";
+
+/// 发送 SystemUI 演示模式广播，`extras` 为 `-e` 键值对
+async fn demo_broadcast(device_id: &Option<String>, command: &str, extras: &[(&str, &str)]) -> MdtResult<()> {
+    let mut args = vec!["am", "broadcast", "-a", DEMO_ACTION, "-e", "command", command];
+    for (key, value) in extras {
+        args.extend(["-e", key, value]);
+    }
+    adb::adb_shell(device_id, &args).await?;
+    Ok(())
+}
+
+/// 开启或退出 SystemUI 演示模式：固定时钟、满电、满格信号并隐藏通知图标，便于截取干净的状态栏
+#[tauri::command]
+pub async fn adb_demo_mode(
+    device_id: Option<String>,
+    enable: bool,
+    clock: Option<String>,
+    battery: Option<u8>,
+    wifi: Option<u8>,
+) -> MdtResult<()> {
+    let _history = history::track();
+    if !enable {
+        demo_broadcast(&device_id, "exit", &[]).await?;
+        println!("[devopts] demo mode exited");
+        return Ok(());
+    }
+
+    // 时钟格式为 hhmm，如 1200
+    let clock = clock.unwrap_or_else(|| "1200".to_string()).replace(':', "");
+    if clock.len() != 4 || !clock.chars().all(|c| c.is_ascii_digit()) {
+        return Err(MdtError::invalid_argument("clock", "时钟格式应为 HHmm 或 HH:mm"));
+    }
+    let battery = battery.unwrap_or(100);
+    if battery > 100 {
+        return Err(MdtError::invalid_argument("battery", "电量需在 0 到 100 之间"));
+    }
+    let wifi = wifi.unwrap_or(4);
+    if wifi > 4 {
+        return Err(MdtError::invalid_argument("wifi", "信号格数需在 0 到 4 之间"));
+    }
+    let battery = battery.to_string();
+    let wifi = wifi.to_string();
+
+    adb::adb_shell(&device_id, &["settings", "put", "global", "sysui_demo_allowed", "1"]).await?;
+    demo_broadcast(&device_id, "enter", &[]).await?;
+    demo_broadcast(&device_id, "clock", &[("hhmm", &clock)]).await?;
+    demo_broadcast(&device_id, "battery", &[("level", &battery), ("plugged", "false")]).await?;
+    demo_broadcast(
+        &device_id,
+        "network",
+        &[("wifi", "show"), ("level", &wifi), ("mobile", "show"), ("datatype", "none")],
+    )
+    .await?;
+    demo_broadcast(&device_id, "notifications", &[("visible", "false")]).await?;
+    println!("[devopts] demo mode entered (clock {}, battery {}, wifi {})", clock, battery, wifi);
+    Ok(())
+}
+
+/// `IBinder.SYSPROPS_TRANSACTION`，通知已运行的应用重新读取 debug.* 属性
+const SYSPROPS_TRANSACTION: &str = "1599295570";
+
+/// 开关开发者选项中的调试叠加层：show_touches / pointer_location / layout_bounds / gpu_profile / overdraw
+#[tauri::command]
+pub async fn adb_dev_overlay(device_id: Option<String>, overlay: String, enable: bool) -> MdtResult<()> {
+    let _history = history::track();
+    let flag = if enable { "1" } else { "0" };
+    let prop = match overlay.as_str() {
+        "show_touches" | "pointer_location" => {
+            adb::adb_shell(&device_id, &["settings", "put", "system", &overlay, flag]).await?;
+            None
+        }
+        "layout_bounds" => Some(("debug.layout", if enable { "true" } else { "false" })),
+        "gpu_profile" => Some(("debug.hwui.profile", if enable { "visual_bars" } else { "false" })),
+        "overdraw" => Some(("debug.hwui.overdraw", if enable { "show" } else { "false" })),
+        _ => {
+            return Err(MdtError::invalid_argument(
+                "overlay",
+                format!("不支持的叠加层: {}", overlay),
+            ))
+        }
+    };
+    if let Some((name, value)) = prop {
+        adb::adb_shell(&device_id, &["setprop", name, value]).await?;
+        adb::adb_shell(&device_id, &["service", "call", "activity", SYSPROPS_TRANSACTION]).await?;
+    }
+    println!("[devopts] overlay {} -> {}", overlay, enable);
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleOptions {
+    /// 「不保留活动」
+    pub dont_keep_activities: bool,
+    /// 后台缓存进程上限，None 为系统默认
+    pub background_process_limit: Option<u32>,
+}
+
+#[tauri::command]
+pub async fn adb_get_lifecycle_options(device_id: Option<String>) -> MdtResult<LifecycleOptions> {
+    let dont_keep = adb::adb_settings_value(&device_id, "global", "always_finish_activities").await;
+    let limit = adb::adb_shell(&device_id, &["device_config", "get", "activity_manager", "max_cached_processes"])
+        .await
+        .ok()
+        .and_then(|value| value.trim().parse().ok());
+    Ok(LifecycleOptions {
+        dont_keep_activities: dont_keep.as_deref() == Some("1"),
+        background_process_limit: limit,
+    })
+}
+
+/// 开关「不保留活动」，Activity 离开前台即被销毁，用于复现状态恢复问题
+#[tauri::command]
+pub async fn adb_set_dont_keep_activities(device_id: Option<String>, enable: bool) -> MdtResult<LifecycleOptions> {
+    let _history = history::track();
+    let value = if enable { "1" } else { "0" };
+    adb::adb_shell(&device_id, &["settings", "put", "global", "always_finish_activities", value]).await?;
+    println!("[devopts] dont keep activities -> {}", enable);
+    adb_get_lifecycle_options(device_id).await
+}
+
+/// 限制后台缓存进程数（`device_config activity_manager max_cached_processes`），传 None 恢复默认
+#[tauri::command]
+pub async fn adb_set_background_process_limit(
+    device_id: Option<String>,
+    limit: Option<u32>,
+) -> MdtResult<LifecycleOptions> {
+    let _history = history::track();
+    match limit {
+        Some(limit) => {
+            adb::adb_shell(
+                &device_id,
+                &["device_config", "put", "activity_manager", "max_cached_processes", &limit.to_string()],
+            )
+            .await?;
+        }
+        None => {
+            adb::adb_shell(&device_id, &["device_config", "delete", "activity_manager", "max_cached_processes"])
+                .await?;
+        }
+    }
+    println!("[devopts] background process limit -> {:?}", limit);
+    adb_get_lifecycle_options(device_id).await
+}
@@ -0,0 +1,344 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::hdc;
+
+/// 屏幕尺寸 / 密度 / 方向的当前值及覆盖值
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DisplayState {
+    pub physical_size: Option<String>,
+    pub override_size: Option<String>,
+    pub physical_density: Option<u32>,
+    pub override_density: Option<u32>,
+    /// 是否开启自动旋转
+    pub auto_rotate: Option<bool>,
+    /// 0 / 1 / 2 / 3，分别对应 0° / 90° / 180° / 270°
+    pub user_rotation: Option<u32>,
+}
+
+/// 解析 `wm size` / `wm density` 的 `Physical size: 1080x2400`、`Override density: 320` 等行
+pub(crate) fn parse_wm_output(state: &mut DisplayState, output: &str) {
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Physical size" => state.physical_size = Some(value.to_string()),
+            "Override size" => state.override_size = Some(value.to_string()),
+            "Physical density" => state.physical_density = value.parse().ok(),
+            "Override density" => state.override_density = value.parse().ok(),
+            _ => {}
+        }
+    }
+}
+
+pub(crate) fn validate_size(size: &str) -> MdtResult<()> {
+    let valid = size
+        .split_once('x')
+        .map(|(w, h)| w.parse::<u32>().is_ok_and(|w| w > 0) && h.parse::<u32>().is_ok_and(|h| h > 0))
+        .unwrap_or(false);
+    if valid {
+        Ok(())
+    } else {
+        Err(MdtError::invalid_argument("size", format!("尺寸格式应为 宽x高，如 1080x1920: {}", size)))
+    }
+}
+
+#[tauri::command]
+pub async fn adb_wm_get(device_id: Option<String>) -> MdtResult<DisplayState> {
+    let mut state = DisplayState::default();
+    parse_wm_output(&mut state, &adb::adb_shell(&device_id, &["wm", "size"]).await?);
+    parse_wm_output(&mut state, &adb::adb_shell(&device_id, &["wm", "density"]).await?);
+    state.auto_rotate = adb::adb_settings_value(&device_id, "system", "accelerometer_rotation")
+        .await
+        .map(|v| v == "1");
+    state.user_rotation = adb::adb_settings_value(&device_id, "system", "user_rotation")
+        .await
+        .and_then(|v| v.parse().ok());
+    Ok(state)
+}
+
+/// 覆盖屏幕尺寸、密度和方向（设置方向时会关闭自动旋转），返回设置后的状态
+#[tauri::command]
+pub async fn adb_wm_set(
+    device_id: Option<String>,
+    size: Option<String>,
+    density: Option<u32>,
+    rotation: Option<u32>,
+) -> MdtResult<DisplayState> {
+    let _history = history::track();
+    if let Some(size) = &size {
+        validate_size(size)?;
+    }
+    if density == Some(0) {
+        return Err(MdtError::invalid_argument("density", "密度必须大于 0"));
+    }
+    if rotation.is_some_and(|r| r > 3) {
+        return Err(MdtError::invalid_argument("rotation", "方向需为 0 到 3"));
+    }
+
+    if let Some(size) = &size {
+        adb::adb_shell(&device_id, &["wm", "size", size]).await?;
+    }
+    if let Some(density) = density {
+        adb::adb_shell(&device_id, &["wm", "density", &density.to_string()]).await?;
+    }
+    if let Some(rotation) = rotation {
+        adb::adb_shell(&device_id, &["settings", "put", "system", "accelerometer_rotation", "0"]).await?;
+        adb::adb_shell(&device_id, &["settings", "put", "system", "user_rotation", &rotation.to_string()]).await?;
+    }
+    println!("[display] size={:?} density={:?} rotation={:?}", size, density, rotation);
+    adb_wm_get(device_id).await
+}
+
+/// 恢复物理尺寸与密度，并重新开启自动旋转
+#[tauri::command]
+pub async fn adb_wm_reset(device_id: Option<String>) -> MdtResult<DisplayState> {
+    let _history = history::track();
+    adb::adb_shell(&device_id, &["wm", "size", "reset"]).await?;
+    adb::adb_shell(&device_id, &["wm", "density", "reset"]).await?;
+    adb::adb_shell(&device_id, &["settings", "put", "system", "user_rotation", "0"]).await?;
+    adb::adb_shell(&device_id, &["settings", "put", "system", "accelerometer_rotation", "1"]).await?;
+    adb_wm_get(device_id).await
+}
+
+const NIGHT_MODES: &[&str] = &["yes", "no", "auto"];
+
+/// 解析 `cmd uimode night` 的输出 `Night mode: yes`
+fn parse_night_mode(output: &str) -> Option<String> {
+    output
+        .lines()
+        .find_map(|line| line.trim().strip_prefix("Night mode:"))
+        .map(|mode| mode.trim().to_string())
+}
+
+/// 返回当前深色模式：yes / no / auto（部分系统还可能为 custom）
+#[tauri::command]
+pub async fn adb_get_night_mode(device_id: Option<String>) -> MdtResult<String> {
+    let output = adb::adb_shell(&device_id, &["cmd", "uimode", "night"]).await?;
+    parse_night_mode(&output).ok_or_else(|| MdtError::CommandFailed {
+        command: "cmd uimode night".to_string(),
+        exit_code: None,
+        stderr: output,
+    })
+}
+
+#[tauri::command]
+pub async fn adb_set_night_mode(device_id: Option<String>, mode: String) -> MdtResult<String> {
+    let _history = history::track();
+    if !NIGHT_MODES.contains(&mode.as_str()) {
+        return Err(MdtError::invalid_argument("mode", "模式需为 yes / no / auto"));
+    }
+    adb::adb_shell(&device_id, &["cmd", "uimode", "night", &mode]).await?;
+    println!("[display] night mode -> {}", mode);
+    adb_get_night_mode(device_id).await
+}
+
+async fn read_font_scale(device_id: &Option<String>) -> f32 {
+    adb::adb_settings_value(device_id, "system", "font_scale")
+        .await
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(1.0)
+}
+
+/// 设置系统字体缩放（如无障碍测试常用的 1.3 / 2.0），返回设置后的值
+#[tauri::command]
+pub async fn adb_set_font_scale(device_id: Option<String>, scale: f32) -> MdtResult<f32> {
+    let _history = history::track();
+    if !(0.5..=3.0).contains(&scale) {
+        return Err(MdtError::invalid_argument("scale", "字体缩放需在 0.5 到 3.0 之间"));
+    }
+    adb::adb_shell(&device_id, &["settings", "put", "system", "font_scale", &scale.to_string()]).await?;
+    println!("[display] font scale -> {}", scale);
+    Ok(read_font_scale(&device_id).await)
+}
+
+#[tauri::command]
+pub async fn adb_reset_font_scale(device_id: Option<String>) -> MdtResult<f32> {
+    let _history = history::track();
+    adb::adb_shell(&device_id, &["settings", "put", "system", "font_scale", "1.0"]).await?;
+    Ok(read_font_scale(&device_id).await)
+}
+
+/// HarmonyOS 保持亮屏时使用的息屏超时覆盖值（24 小时）
+const HDC_STAY_AWAKE_TIMEOUT_MS: &str = "86400000";
+
+/// 充电时保持屏幕常亮（`svc power stayon`），避免长时间镜像 / 演示时息屏
+#[tauri::command]
+pub async fn adb_set_stay_awake(device_id: Option<String>, enable: bool) -> MdtResult<()> {
+    let _history = history::track();
+    let value = if enable { "true" } else { "false" };
+    adb::adb_shell(&device_id, &["svc", "power", "stayon", value]).await?;
+    println!("[display] stay awake -> {}", enable);
+    Ok(())
+}
+
+/// 设置屏幕亮度（0-255）；`auto` 为 true 时开启自动亮度，此时 level 作为初始值
+#[tauri::command]
+pub async fn adb_set_brightness(device_id: Option<String>, level: u8, auto: Option<bool>) -> MdtResult<()> {
+    let _history = history::track();
+    let mode = if auto.unwrap_or(false) { "1" } else { "0" };
+    adb::adb_shell(&device_id, &["settings", "put", "system", "screen_brightness_mode", mode]).await?;
+    adb::adb_shell(&device_id, &["settings", "put", "system", "screen_brightness", &level.to_string()]).await?;
+    println!("[display] brightness -> {} (auto {})", level, mode);
+    Ok(())
+}
+
+/// 点亮屏幕并覆盖息屏超时（`power-shell timeout -o`），关闭时恢复系统设置
+#[tauri::command]
+pub async fn hdc_set_stay_awake(device_id: Option<String>, enable: bool) -> MdtResult<()> {
+    let _history = history::track();
+    if enable {
+        hdc::hdc_shell(&device_id, &["power-shell", "wakeup"]).await?;
+        hdc::hdc_shell(&device_id, &["power-shell", "timeout", "-o", HDC_STAY_AWAKE_TIMEOUT_MS]).await?;
+    } else {
+        hdc::hdc_shell(&device_id, &["power-shell", "timeout", "-r"]).await?;
+    }
+    println!("[display] hdc stay awake -> {}", enable);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn hdc_set_brightness(device_id: Option<String>, level: u8) -> MdtResult<()> {
+    let _history = history::track();
+    hdc::hdc_shell(&device_id, &["power-shell", "display", "-s", &level.to_string()]).await?;
+    println!("[display] hdc brightness -> {}", level);
+    Ok(())
+}
+
+/// 设备上的一个逻辑显示器（含副屏、虚拟屏）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceDisplay {
+    /// 逻辑显示器 ID，scrcpy 的 `display_id` 使用此值
+    pub display_id: u32,
+    /// 物理显示器 ID（`uniqueId "local:<id>"`），Android 10+ 的 screencap / screenrecord 使用此值
+    pub physical_id: Option<String>,
+    pub name: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub density: Option<u32>,
+    /// INTERNAL / EXTERNAL / VIRTUAL / OVERLAY 等
+    pub kind: Option<String>,
+    pub state: Option<String>,
+}
+
+/// 取出 `key value` 形式字段的值，如 `displayId 0`、`type INTERNAL`
+fn info_field<'a>(info: &'a str, key: &str) -> Option<&'a str> {
+    let start = info.find(&format!(", {} ", key))? + key.len() + 3;
+    info[start..].split([',', ' ', '}']).next()
+}
+
+/// 解析 `DisplayInfo{"Built-in Screen", displayId 0, ..., real 1080 x 2400, ..., uniqueId "local:4619827259835644672", ...}`
+fn parse_display_info(info: &str, fallback_id: Option<u32>) -> Option<DeviceDisplay> {
+    let display_id = info_field(info, "displayId")
+        .and_then(|id| id.parse().ok())
+        .or(fallback_id)?;
+    let name = info
+        .split_once("DisplayInfo{\"")
+        .and_then(|(_, rest)| rest.split_once('"'))
+        .map(|(name, _)| name.to_string());
+    let (width, height) = info
+        .split_once(", real ")
+        .and_then(|(_, rest)| {
+            let mut parts = rest.split_whitespace();
+            let width = parts.next()?.parse().ok()?;
+            parts.next().filter(|x| *x == "x")?;
+            let height = parts.next()?.trim_end_matches(',').parse().ok()?;
+            Some((Some(width), Some(height)))
+        })
+        .unwrap_or((None, None));
+    let physical_id = info
+        .split_once("uniqueId \"")
+        .and_then(|(_, rest)| rest.split_once('"'))
+        .and_then(|(unique_id, _)| unique_id.strip_prefix("local:"))
+        .map(str::to_string);
+    Some(DeviceDisplay {
+        display_id,
+        physical_id,
+        name,
+        width,
+        height,
+        density: info_field(info, "density").and_then(|density| density.parse().ok()),
+        kind: info_field(info, "type").map(str::to_string),
+        state: info_field(info, "state").map(str::to_string),
+    })
+}
+
+/// 解析 `dumpsys display` 中各逻辑显示器的 `mBaseDisplayInfo`
+fn parse_dumpsys_display(output: &str) -> Vec<DeviceDisplay> {
+    let mut displays: Vec<DeviceDisplay> = Vec::new();
+    let mut current_id = None;
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(id) = trimmed.strip_prefix("mDisplayId=") {
+            current_id = id.parse().ok();
+        } else if let Some(info) = trimmed.strip_prefix("mBaseDisplayInfo=") {
+            if let Some(display) = parse_display_info(info, current_id) {
+                if !displays.iter().any(|known| known.display_id == display.display_id) {
+                    displays.push(display);
+                }
+            }
+        }
+    }
+    displays
+}
+
+/// 解析 `dumpsys SurfaceFlinger --display-id` 的 `Display 4619827259835644672 (HWC display 0): ... displayName="..."`
+fn parse_surface_flinger_displays(output: &str) -> Vec<DeviceDisplay> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let rest = line.trim().strip_prefix("Display ")?;
+            let (physical_id, rest) = rest.split_once(' ')?;
+            let index = rest
+                .split_once("HWC display ")
+                .and_then(|(_, rest)| rest.split(')').next())
+                .and_then(|index| index.parse().ok())?;
+            let name = rest
+                .split_once("displayName=\"")
+                .and_then(|(_, rest)| rest.split_once('"'))
+                .map(|(name, _)| name.to_string());
+            Some(DeviceDisplay {
+                display_id: index,
+                physical_id: Some(physical_id.to_string()),
+                name,
+                width: None,
+                height: None,
+                density: None,
+                kind: None,
+                state: None,
+            })
+        })
+        .collect()
+}
+
+/// 列出设备上的显示器，优先解析 `dumpsys display`，失败时退回 SurfaceFlinger 的物理显示器列表
+#[tauri::command]
+pub async fn adb_list_displays(device_id: Option<String>) -> MdtResult<Vec<DeviceDisplay>> {
+    if let Ok(output) = adb::adb_shell(&device_id, &["dumpsys", "display"]).await {
+        let displays = parse_dumpsys_display(&output);
+        if !displays.is_empty() {
+            return Ok(displays);
+        }
+    }
+    let output = adb::adb_shell(&device_id, &["dumpsys", "SurfaceFlinger", "--display-id"]).await?;
+    Ok(parse_surface_flinger_displays(&output))
+}
+
+/// 将逻辑显示器 ID 转换为 screencap `-d` / screenrecord `--display-id` 需要的 ID：
+/// 有物理 ID 时使用物理 ID，否则（Android 9 及以下或虚拟屏）直接使用逻辑 ID
+pub(crate) async fn capture_display_arg(device_id: &Option<String>, display_id: Option<u32>) -> MdtResult<Option<String>> {
+    let Some(display_id) = display_id else {
+        return Ok(None);
+    };
+    let displays = adb_list_displays(device_id.clone()).await?;
+    let display = displays
+        .iter()
+        .find(|display| display.display_id == display_id)
+        .ok_or_else(|| MdtError::invalid_argument("displayId", format!("显示器不存在: {}", display_id)))?;
+    Ok(Some(display.physical_id.clone().unwrap_or_else(|| display_id.to_string())))
+}
@@ -0,0 +1,329 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::tools;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::io::AsyncReadExt;
+
+const DEFAULT_DUMPSYS_TIMEOUT_SECS: u64 = 30;
+const DEFAULT_DUMPSYS_MAX_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DumpsysOutput {
+    pub service: String,
+    pub output: String,
+    pub bytes: usize,
+    /// 超过大小限制被截断
+    pub truncated: bool,
+    /// 超时后提前结束
+    pub timed_out: bool,
+}
+
+fn validate_service(service: &str) -> MdtResult<()> {
+    if !service.is_empty() && service.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '/')) {
+        Ok(())
+    } else {
+        Err(MdtError::invalid_argument("service", format!("无效的服务名: {}", service)))
+    }
+}
+
+/// 列出设备上注册的系统服务（`dumpsys -l`）
+#[tauri::command]
+pub async fn adb_dumpsys_services(device_id: Option<String>) -> MdtResult<Vec<String>> {
+    let output = adb::adb_shell(&device_id, &["dumpsys", "-l"]).await?;
+    Ok(output
+        .lines()
+        .skip_while(|line| !line.starts_with("Currently running services"))
+        .skip(1)
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(str::to_string)
+        .collect())
+}
+
+/// 执行 `dumpsys <service> [args]`，超过 `max_bytes` 或 `timeout_secs` 时截断返回
+#[tauri::command]
+pub async fn adb_dumpsys(
+    device_id: Option<String>,
+    service: String,
+    args: Option<Vec<String>>,
+    timeout_secs: Option<u64>,
+    max_bytes: Option<usize>,
+) -> MdtResult<DumpsysOutput> {
+    validate_service(&service)?;
+    let timeout = Duration::from_secs(timeout_secs.unwrap_or(DEFAULT_DUMPSYS_TIMEOUT_SECS).max(1));
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_DUMPSYS_MAX_BYTES).max(1);
+    let quoted_args: Vec<String> = args.unwrap_or_default().iter().map(|arg| tools::shell_quote(arg)).collect();
+
+    let mut cmd = adb::adb_command(&device_id);
+    // -t 限制设备端单个服务的 dump 时长
+    cmd.args(["shell", "dumpsys", "-t", &timeout.as_secs().to_string(), &service]);
+    cmd.args(&quoted_args);
+    let mut child = tokio::process::Command::from(cmd)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| MdtError::spawn("adb", e))?;
+    let mut stdout = child
+        .stdout
+        .take()
+        .ok_or_else(|| MdtError::io("读取 dumpsys 输出", "stdout 不可用"))?;
+
+    let mut data = Vec::new();
+    let mut truncated = false;
+    let read_all = async {
+        let mut buf = [0u8; 16 * 1024];
+        loop {
+            let read = stdout.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            let room = max_bytes - data.len();
+            data.extend_from_slice(&buf[..read.min(room)]);
+            if read > room {
+                truncated = true;
+                break;
+            }
+        }
+        Ok::<_, std::io::Error>(())
+    };
+    let timed_out = match tokio::time::timeout(timeout, read_all).await {
+        Ok(result) => {
+            result.map_err(|e| MdtError::io("读取 dumpsys 输出", e))?;
+            false
+        }
+        Err(_) => true,
+    };
+    let _ = child.kill().await;
+
+    let output = String::from_utf8_lossy(&data).to_string();
+    if output.starts_with("Can't find service:") {
+        return Err(MdtError::invalid_argument("service", format!("设备上没有服务 {}", service)));
+    }
+    Ok(DumpsysOutput {
+        service,
+        bytes: data.len(),
+        output,
+        truncated,
+        timed_out,
+    })
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WakeLockInfo {
+    /// PARTIAL_WAKE_LOCK / SCREEN_BRIGHT_WAKE_LOCK 等
+    pub level: String,
+    pub tag: String,
+    pub uid: Option<u32>,
+    pub pid: Option<u32>,
+    /// 持有者包名（按 uid 解析，WorkSource 中的包名优先）
+    pub packages: Vec<String>,
+    /// 已持有时长，如 `-3m2s123ms`
+    pub acquired: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AlarmInfo {
+    /// RTC_WAKEUP / ELAPSED_WAKEUP / RTC / ELAPSED
+    pub alarm_type: String,
+    pub tag: Option<String>,
+    pub when: Option<String>,
+    pub repeat_interval: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PackageAlarms {
+    pub package: String,
+    pub wakeup_count: usize,
+    pub alarms: Vec<AlarmInfo>,
+}
+
+/// 从 `(uid=1041 pid=123 ws=WorkSource{10100 com.demo})` 等片段中取出 `key=` 后的数字
+fn field_number(text: &str, key: &str) -> Option<u32> {
+    let start = text.find(key)? + key.len();
+    let digits: String = text[start..].chars().take_while(|c| c.is_ascii_digit()).collect();
+    digits.parse().ok()
+}
+
+/// 解析 `dumpsys power` 的 `Wake Locks: size=N` 小节
+fn parse_wakelocks(output: &str) -> Vec<WakeLockInfo> {
+    let mut locks = Vec::new();
+    let mut in_section = false;
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("Wake Locks:") {
+            in_section = true;
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if trimmed.is_empty() || !trimmed.contains("WAKE_LOCK") {
+            break;
+        }
+        let level = trimmed.split_whitespace().next().unwrap_or_default().to_string();
+        let tag = trimmed
+            .split_once('\'')
+            .and_then(|(_, rest)| rest.rsplit_once('\''))
+            .map(|(tag, _)| tag.to_string())
+            .unwrap_or_default();
+        let acquired = trimmed
+            .split_whitespace()
+            .find_map(|token| token.strip_prefix("ACQ="))
+            .map(str::to_string);
+        // WorkSource{10100 com.demo, 10101 com.other}
+        let packages = trimmed
+            .split_once("WorkSource{")
+            .and_then(|(_, rest)| rest.split_once('}'))
+            .map(|(ws, _)| {
+                ws.split([',', ' '])
+                    .filter(|token| token.contains('.'))
+                    .map(str::to_string)
+                    .collect()
+            })
+            .unwrap_or_default();
+        locks.push(WakeLockInfo {
+            level,
+            tag,
+            uid: field_number(trimmed, "uid=").or_else(|| field_number(trimmed, "UID=")),
+            pid: field_number(trimmed, "pid=").or_else(|| field_number(trimmed, "PID=")),
+            packages,
+            acquired,
+        });
+    }
+    locks
+}
+
+/// `pm list packages -U` 的 uid 到包名映射
+async fn packages_by_uid(device_id: &Option<String>) -> std::collections::HashMap<u32, Vec<String>> {
+    let mut map: std::collections::HashMap<u32, Vec<String>> = std::collections::HashMap::new();
+    let output = adb::adb_shell(device_id, &["pm", "list", "packages", "-U"]).await.unwrap_or_default();
+    for line in output.lines() {
+        // package:com.demo uid:10100
+        let Some((package, uid)) = line.trim().strip_prefix("package:").and_then(|rest| rest.split_once(" uid:")) else {
+            continue;
+        };
+        // 共享 uid 时可能为 `10100,10101`
+        for uid in uid.split(',').filter_map(|uid| uid.trim().parse().ok()) {
+            map.entry(uid).or_default().push(package.to_string());
+        }
+    }
+    map
+}
+
+/// 列出当前持有的 wakelock 及其持有者
+#[tauri::command]
+pub async fn adb_list_wakelocks(device_id: Option<String>) -> MdtResult<Vec<WakeLockInfo>> {
+    let output = adb::adb_shell(&device_id, &["dumpsys", "power"]).await?;
+    let mut locks = parse_wakelocks(&output);
+    if locks.iter().any(|lock| lock.packages.is_empty()) {
+        let owners = packages_by_uid(&device_id).await;
+        for lock in locks.iter_mut().filter(|lock| lock.packages.is_empty()) {
+            if let Some(packages) = lock.uid.and_then(|uid| owners.get(&uid)) {
+                lock.packages = packages.clone();
+            }
+        }
+    }
+    Ok(locks)
+}
+
+/// 解析 `RTC_WAKEUP #12: Alarm{2f3c1a0 type 0 origWhen 1700000000000 whenElapsed 123456 com.demo}`
+fn parse_alarm_head(line: &str) -> Option<(String, AlarmInfo)> {
+    let (head, rest) = line.split_once(": Alarm{")?;
+    let alarm_type = head.split_whitespace().next()?.to_string();
+    let body = rest.strip_suffix('}').unwrap_or(rest);
+    let tokens: Vec<&str> = body.split_whitespace().collect();
+    let package = tokens.last()?.to_string();
+    let when = tokens
+        .windows(2)
+        .find(|pair| pair[0] == "origWhen" || pair[0] == "when")
+        .map(|pair| pair[1].to_string());
+    Some((
+        package,
+        AlarmInfo {
+            alarm_type,
+            tag: None,
+            when,
+            repeat_interval: None,
+        },
+    ))
+}
+
+/// 解析 `dumpsys alarm` 中待触发的闹钟，按包名分组
+fn parse_alarms(output: &str) -> Vec<PackageAlarms> {
+    let mut groups: Vec<PackageAlarms> = Vec::new();
+    let mut in_pending = false;
+    let mut current: Option<(usize, usize)> = None;
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("Pending alarm") {
+            in_pending = true;
+            continue;
+        }
+        if !in_pending {
+            continue;
+        }
+        // 旧版本以 Batch{...} 分组，新版本直接列出
+        if trimmed.starts_with("Batch{") {
+            continue;
+        }
+        if let Some((package, alarm)) = parse_alarm_head(trimmed) {
+            let index = match groups.iter().position(|group| group.package == package) {
+                Some(index) => index,
+                None => {
+                    groups.push(PackageAlarms {
+                        package,
+                        wakeup_count: 0,
+                        alarms: Vec::new(),
+                    });
+                    groups.len() - 1
+                }
+            };
+            let group = &mut groups[index];
+            if alarm.alarm_type.ends_with("WAKEUP") {
+                group.wakeup_count += 1;
+            }
+            group.alarms.push(alarm);
+            current = Some((index, group.alarms.len() - 1));
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        if current.is_none() && indent <= 2 && !trimmed.is_empty() {
+            continue;
+        }
+        let Some((group, alarm)) = current else {
+            continue;
+        };
+        let alarm = &mut groups[group].alarms[alarm];
+        if let Some(tag) = trimmed.strip_prefix("tag=") {
+            alarm.tag = Some(tag.to_string());
+        } else if trimmed.starts_with("type=") {
+            alarm.repeat_interval = trimmed
+                .split_whitespace()
+                .find_map(|token| token.strip_prefix("repeatInterval="))
+                .and_then(|value| value.parse().ok());
+        } else if indent <= 2 && !trimmed.is_empty() {
+            // 进入下一个小节（如 Past-due / Recent problems）
+            break;
+        }
+    }
+    groups.sort_by_key(|group| std::cmp::Reverse(group.alarms.len()));
+    groups
+}
+
+/// 列出待触发的闹钟，按包名分组并统计可唤醒设备的闹钟数
+#[tauri::command]
+pub async fn adb_list_alarms(device_id: Option<String>, package: Option<String>) -> MdtResult<Vec<PackageAlarms>> {
+    let output = adb::adb_shell(&device_id, &["dumpsys", "alarm"]).await?;
+    let mut groups = parse_alarms(&output);
+    if let Some(package) = package {
+        groups.retain(|group| group.package == package);
+    }
+    Ok(groups)
+}
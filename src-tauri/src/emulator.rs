@@ -0,0 +1,324 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::hdc;
+use crate::tools;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+
+/// 模拟器控制台端口范围（adb 序列号为 `emulator-<port>`）
+const EMULATOR_PORT_START: u16 = 5554;
+const EMULATOR_PORT_END: u16 = 5682;
+
+struct EmulatorSession {
+    child: Child,
+    port: u16,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct EmulatorOptions {
+    /// `-no-snapshot-load`：冷启动
+    #[serde(default)]
+    pub cold_boot: bool,
+    /// `-wipe-data`：清除用户数据
+    #[serde(default)]
+    pub wipe_data: bool,
+    /// `-gpu`：auto / host / swiftshader_indirect / angle_indirect 等
+    pub gpu: Option<String>,
+    /// 控制台端口（偶数，5554-5682），未指定时自动分配
+    pub port: Option<u16>,
+    /// `-no-window`：无界面运行
+    #[serde(default)]
+    pub no_window: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RunningEmulator {
+    pub avd: String,
+    pub port: u16,
+    /// 对应的 adb 序列号
+    pub serial: String,
+}
+
+fn emulator_sessions() -> &'static Mutex<HashMap<String, EmulatorSession>> {
+    static STORE: OnceLock<Mutex<HashMap<String, EmulatorSession>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 列出本机的 AVD（`emulator -list-avds`）
+#[tauri::command]
+pub async fn emulator_list_avds() -> MdtResult<Vec<String>> {
+    let mut cmd = tools::command_for("emulator");
+    cmd.arg("-list-avds");
+    let output = tools::run_checked("emulator", &None, cmd).await?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        // 部分版本会输出 `INFO | ...` 等日志行
+        .filter(|line| !line.is_empty() && !line.contains('|'))
+        .map(str::to_string)
+        .collect())
+}
+
+/// 选择未被 adb 设备或已启动模拟器占用的控制台端口
+async fn pick_emulator_port() -> MdtResult<u16> {
+    let devices = adb::adb_devices().await.map(|list| list.devices).unwrap_or_default();
+    let tracked: Vec<u16> = emulator_sessions()
+        .lock()
+        .map_err(|_| MdtError::lock("emulator"))?
+        .values()
+        .map(|session| session.port)
+        .collect();
+    (EMULATOR_PORT_START..=EMULATOR_PORT_END)
+        .step_by(2)
+        .find(|port| {
+            let serial = format!("emulator-{}", port);
+            !tracked.contains(port) && !devices.iter().any(|device| device.id == serial)
+        })
+        .ok_or_else(|| MdtError::io("分配模拟器端口", "没有可用端口"))
+}
+
+/// 启动 AVD 并跟踪其进程，返回对应的 adb 序列号
+#[tauri::command]
+pub async fn emulator_start(avd: String, options: Option<EmulatorOptions>) -> MdtResult<RunningEmulator> {
+    let _history = history::track();
+    let options = options.unwrap_or_default();
+    {
+        let store = emulator_sessions()
+            .lock()
+            .map_err(|_| MdtError::lock("emulator"))?;
+        if store.contains_key(&avd) {
+            return Err(MdtError::session_exists("emulator", &avd));
+        }
+    }
+    let port = match options.port {
+        Some(port) if port % 2 == 0 && (EMULATOR_PORT_START..=EMULATOR_PORT_END).contains(&port) => port,
+        Some(port) => {
+            return Err(MdtError::invalid_argument(
+                "port",
+                format!("端口需为 {}-{} 之间的偶数: {}", EMULATOR_PORT_START, EMULATOR_PORT_END, port),
+            ))
+        }
+        None => pick_emulator_port().await?,
+    };
+
+    let mut cmd = tools::command_for("emulator");
+    cmd.args(["-avd", &avd, "-port", &port.to_string()]);
+    if options.cold_boot {
+        cmd.arg("-no-snapshot-load");
+    }
+    if options.wipe_data {
+        cmd.arg("-wipe-data");
+    }
+    if let Some(gpu) = &options.gpu {
+        cmd.args(["-gpu", gpu]);
+    }
+    if options.no_window {
+        cmd.arg("-no-window");
+    }
+    history::record_launch(&cmd);
+    let child = cmd
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| MdtError::spawn("emulator", e))?;
+
+    emulator_sessions()
+        .lock()
+        .map_err(|_| MdtError::lock("emulator"))?
+        .insert(avd.clone(), EmulatorSession { child, port });
+    println!("[emulator] started {} on port {}", avd, port);
+    Ok(RunningEmulator {
+        avd,
+        port,
+        serial: format!("emulator-{}", port),
+    })
+}
+
+/// 列出由本工具启动且仍在运行的模拟器
+#[tauri::command]
+pub async fn emulator_list_running() -> MdtResult<Vec<RunningEmulator>> {
+    let mut store = emulator_sessions()
+        .lock()
+        .map_err(|_| MdtError::lock("emulator"))?;
+    // 清理已退出的进程
+    store.retain(|_, session| matches!(session.child.try_wait(), Ok(None)));
+    Ok(store
+        .iter()
+        .map(|(avd, session)| RunningEmulator {
+            avd: avd.clone(),
+            port: session.port,
+            serial: format!("emulator-{}", session.port),
+        })
+        .collect())
+}
+
+/// 通过控制台命令 `adb emu kill` 关闭模拟器，失败时直接结束进程
+#[tauri::command]
+pub async fn emulator_stop(avd: String) -> MdtResult<()> {
+    let _history = history::track();
+    let session = emulator_sessions()
+        .lock()
+        .map_err(|_| MdtError::lock("emulator"))?
+        .remove(&avd)
+        .ok_or_else(|| MdtError::session_not_found("emulator", &avd))?;
+    let serial = Some(format!("emulator-{}", session.port));
+    let mut child = session.child;
+    if adb::adb_run(&serial, &["emu", "kill"]).await.is_err() {
+        let _ = child.kill();
+    }
+    let _ = tauri::async_runtime::spawn_blocking(move || child.wait()).await;
+    println!("[emulator] stopped {}", avd);
+    Ok(())
+}
+
+/// DevEco Studio 自带模拟器的默认安装位置
+const HARMONY_EMULATOR_CANDIDATES: &[&str] = &[
+    "/Applications/DevEco-Studio.app/Contents/tools/emulator/Emulator",
+    "C:\\Program Files\\Huawei\\DevEco Studio\\tools\\emulator\\Emulator.exe",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarmonyEmulator {
+    pub name: String,
+    /// 实例所在目录
+    pub path: String,
+    /// 是否由本工具启动且仍在运行
+    pub running: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HarmonyEmulatorTargets {
+    pub instances: Vec<HarmonyEmulator>,
+    /// hdc 中以网络方式连接的模拟器目标，如 `127.0.0.1:5555`
+    pub hdc_targets: Vec<String>,
+}
+
+fn harmony_emulator_sessions() -> &'static Mutex<HashMap<String, Child>> {
+    static STORE: OnceLock<Mutex<HashMap<String, Child>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 解析模拟器可执行文件：`MDT_HARMONY_EMULATOR_PATH`、工具目录 / PATH，最后是 DevEco 默认位置
+fn resolve_harmony_emulator() -> MdtResult<PathBuf> {
+    tools::resolve_tool_path("harmony-emulator")
+        .or_else(|| {
+            HARMONY_EMULATOR_CANDIDATES
+                .iter()
+                .map(PathBuf::from)
+                .find(|path| path.exists())
+        })
+        .ok_or_else(|| MdtError::ToolNotFound {
+            tool: "harmony-emulator".to_string(),
+        })
+}
+
+/// 模拟器实例目录：`MDT_HARMONY_EMULATOR_DEPLOYED_DIR`，默认 `~/.Huawei/Emulator/deployed`
+/// （Windows 为 `%LOCALAPPDATA%\Huawei\Emulator\deployed`）
+fn harmony_deployed_dir() -> Option<PathBuf> {
+    if let Ok(dir) = std::env::var("MDT_HARMONY_EMULATOR_DEPLOYED_DIR") {
+        return Some(PathBuf::from(dir));
+    }
+    if cfg!(target_os = "windows") {
+        std::env::var("LOCALAPPDATA")
+            .ok()
+            .map(|dir| PathBuf::from(dir).join("Huawei").join("Emulator").join("deployed"))
+    } else {
+        std::env::var("HOME")
+            .ok()
+            .map(|home| PathBuf::from(home).join(".Huawei").join("Emulator").join("deployed"))
+    }
+}
+
+/// 列出 DevEco 中创建的模拟器实例，以及 hdc 已连接的模拟器目标
+#[tauri::command]
+pub async fn harmony_emulator_list() -> MdtResult<HarmonyEmulatorTargets> {
+    let running: Vec<String> = {
+        let mut store = harmony_emulator_sessions()
+            .lock()
+            .map_err(|_| MdtError::lock("harmony emulator"))?;
+        store.retain(|_, child| matches!(child.try_wait(), Ok(None)));
+        store.keys().cloned().collect()
+    };
+
+    let mut instances = Vec::new();
+    if let Some(dir) = harmony_deployed_dir() {
+        if let Ok(entries) = std::fs::read_dir(&dir) {
+            for entry in entries.flatten() {
+                if !entry.path().is_dir() {
+                    continue;
+                }
+                let name = entry.file_name().to_string_lossy().to_string();
+                instances.push(HarmonyEmulator {
+                    running: running.contains(&name),
+                    path: entry.path().to_string_lossy().to_string(),
+                    name,
+                });
+            }
+        }
+    }
+    instances.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let hdc_targets = hdc::hdc_list_targets()
+        .await
+        .map(|list| list.devices)
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|device| device.id.starts_with("127.0.0.1:") || device.id.starts_with("localhost:"))
+        .map(|device| device.id)
+        .collect();
+    Ok(HarmonyEmulatorTargets { instances, hdc_targets })
+}
+
+/// 启动 DevEco 模拟器实例（`Emulator -hvd <name> -path <deployed>`）
+#[tauri::command]
+pub async fn harmony_emulator_start(name: String) -> MdtResult<()> {
+    let _history = history::track();
+    {
+        let store = harmony_emulator_sessions()
+            .lock()
+            .map_err(|_| MdtError::lock("harmony emulator"))?;
+        if store.contains_key(&name) {
+            return Err(MdtError::session_exists("harmony emulator", &name));
+        }
+    }
+    let binary = resolve_harmony_emulator()?;
+    let deployed = harmony_deployed_dir()
+        .filter(|dir| dir.join(&name).is_dir())
+        .ok_or_else(|| MdtError::invalid_argument("name", format!("找不到模拟器实例: {}", name)))?;
+
+    let mut cmd = Command::new(&binary);
+    cmd.args(["-hvd", &name, "-path"])
+        .arg(&deployed)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+    history::record_launch(&cmd);
+    let child = cmd.spawn().map_err(|e| MdtError::spawn("harmony-emulator", e))?;
+    harmony_emulator_sessions()
+        .lock()
+        .map_err(|_| MdtError::lock("harmony emulator"))?
+        .insert(name.clone(), child);
+    println!("[emulator] started harmony emulator {}", name);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn harmony_emulator_stop(name: String) -> MdtResult<()> {
+    let _history = history::track();
+    let mut child = harmony_emulator_sessions()
+        .lock()
+        .map_err(|_| MdtError::lock("harmony emulator"))?
+        .remove(&name)
+        .ok_or_else(|| MdtError::session_not_found("harmony emulator", &name))?;
+    let _ = child.kill();
+    let _ = tauri::async_runtime::spawn_blocking(move || child.wait()).await;
+    println!("[emulator] stopped harmony emulator {}", name);
+    Ok(())
+}
@@ -0,0 +1,268 @@
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use serde_json::{json, Value};
+use std::fmt;
+use std::io;
+use std::process::Output;
+
+/// 后端命令统一的错误类型
+///
+/// 序列化为 `{ code, message, context }`：前端根据 `code` 分支处理或做本地化，
+/// `message` 仅作为兜底展示文案，`context` 携带设备、工具等上下文字段。
+#[derive(Debug)]
+pub enum MdtError {
+    /// 外部工具（adb、hdc、scrcpy-server 等）未找到
+    ToolNotFound { tool: String },
+    /// 设备离线
+    DeviceOffline { device_id: Option<String> },
+    /// 设备未授权调试
+    DeviceUnauthorized { device_id: Option<String> },
+    /// 设备不存在或未连接
+    DeviceNotFound { device_id: Option<String> },
+    /// 设备端或本地权限不足
+    PermissionDenied { detail: String },
+    /// 参数不合法
+    InvalidArgument { field: String, detail: String },
+    /// 外部命令以非零状态退出
+    CommandFailed {
+        command: String,
+        exit_code: Option<i32>,
+        stderr: String,
+    },
+    /// 本地 IO 错误
+    Io { context: String, detail: String },
+    /// 同一设备上已存在同类会话（录屏、镜像等）
+    SessionExists { kind: String, device_id: String },
+    /// 找不到对应的会话
+    SessionNotFound { kind: String, device_id: String },
+    /// 内部状态锁异常
+    StateLock { kind: String },
+    /// 操作被用户取消
+    Cancelled { operation: String },
+    /// 传输后本地与设备端文件校验和不一致
+    ChecksumMismatch {
+        path: String,
+        algorithm: String,
+        local: String,
+        remote: String,
+    },
+    /// 传输结束后设备端文件大小与本地不一致，`actual` 为 None 表示设备端文件不存在
+    TransferIncomplete {
+        path: String,
+        expected: u64,
+        actual: Option<u64>,
+    },
+    /// 设备系统版本或环境不支持该操作
+    Unsupported { feature: String, detail: String },
+}
+
+impl MdtError {
+    pub fn code(&self) -> &'static str {
+        match self {
+            MdtError::ToolNotFound { .. } => "TOOL_NOT_FOUND",
+            MdtError::DeviceOffline { .. } => "DEVICE_OFFLINE",
+            MdtError::DeviceUnauthorized { .. } => "DEVICE_UNAUTHORIZED",
+            MdtError::DeviceNotFound { .. } => "DEVICE_NOT_FOUND",
+            MdtError::PermissionDenied { .. } => "PERMISSION_DENIED",
+            MdtError::InvalidArgument { .. } => "INVALID_ARGUMENT",
+            MdtError::CommandFailed { .. } => "COMMAND_FAILED",
+            MdtError::Io { .. } => "IO_ERROR",
+            MdtError::SessionExists { .. } => "SESSION_EXISTS",
+            MdtError::SessionNotFound { .. } => "SESSION_NOT_FOUND",
+            MdtError::StateLock { .. } => "STATE_LOCK",
+            MdtError::Cancelled { .. } => "CANCELLED",
+            MdtError::ChecksumMismatch { .. } => "CHECKSUM_MISMATCH",
+            MdtError::TransferIncomplete { .. } => "TRANSFER_INCOMPLETE",
+            MdtError::Unsupported { .. } => "UNSUPPORTED",
+        }
+    }
+
+    fn context(&self) -> Value {
+        match self {
+            MdtError::ToolNotFound { tool } => json!({ "tool": tool }),
+            MdtError::DeviceOffline { device_id }
+            | MdtError::DeviceUnauthorized { device_id }
+            | MdtError::DeviceNotFound { device_id } => json!({ "deviceId": device_id }),
+            MdtError::PermissionDenied { detail } => json!({ "detail": detail }),
+            MdtError::InvalidArgument { field, detail } => {
+                json!({ "field": field, "detail": detail })
+            }
+            MdtError::CommandFailed {
+                command,
+                exit_code,
+                stderr,
+            } => json!({ "command": command, "exitCode": exit_code, "stderr": stderr }),
+            MdtError::Io { context, detail } => json!({ "context": context, "detail": detail }),
+            MdtError::SessionExists { kind, device_id }
+            | MdtError::SessionNotFound { kind, device_id } => {
+                json!({ "kind": kind, "deviceId": device_id })
+            }
+            MdtError::StateLock { kind } => json!({ "kind": kind }),
+            MdtError::Cancelled { operation } => json!({ "operation": operation }),
+            MdtError::ChecksumMismatch {
+                path,
+                algorithm,
+                local,
+                remote,
+            } => json!({ "path": path, "algorithm": algorithm, "local": local, "remote": remote }),
+            MdtError::TransferIncomplete { path, expected, actual } => {
+                json!({ "path": path, "expected": expected, "actual": actual })
+            }
+            MdtError::Unsupported { feature, detail } => json!({ "feature": feature, "detail": detail }),
+        }
+    }
+
+    pub fn invalid_argument(field: &str, detail: impl Into<String>) -> Self {
+        MdtError::InvalidArgument {
+            field: field.to_string(),
+            detail: detail.into(),
+        }
+    }
+
+    pub fn io(context: &str, err: impl fmt::Display) -> Self {
+        MdtError::Io {
+            context: context.to_string(),
+            detail: err.to_string(),
+        }
+    }
+
+    pub fn lock(kind: &str) -> Self {
+        MdtError::StateLock {
+            kind: kind.to_string(),
+        }
+    }
+
+    pub fn session_exists(kind: &str, device_id: &str) -> Self {
+        MdtError::SessionExists {
+            kind: kind.to_string(),
+            device_id: device_id.to_string(),
+        }
+    }
+
+    pub fn session_not_found(kind: &str, device_id: &str) -> Self {
+        MdtError::SessionNotFound {
+            kind: kind.to_string(),
+            device_id: device_id.to_string(),
+        }
+    }
+
+    pub fn unsupported(feature: &str, detail: impl Into<String>) -> Self {
+        MdtError::Unsupported {
+            feature: feature.to_string(),
+            detail: detail.into(),
+        }
+    }
+
+    /// 启动外部工具失败：找不到可执行文件时归类为 `TOOL_NOT_FOUND`
+    pub fn spawn(tool: &str, err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => MdtError::ToolNotFound {
+                tool: tool.to_string(),
+            },
+            io::ErrorKind::PermissionDenied => MdtError::PermissionDenied {
+                detail: format!("{}: {}", tool, err),
+            },
+            _ => MdtError::io(&format!("启动 {}", tool), err),
+        }
+    }
+
+    /// 根据命令输出归类错误（设备离线、未授权、权限不足等），无法识别时返回 `COMMAND_FAILED`
+    pub fn from_output(command: String, device_id: &Option<String>, output: &Output) -> Self {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        // adb 的部分错误输出在 stdout 中
+        let detail = if stderr.is_empty() {
+            String::from_utf8_lossy(&output.stdout).trim().to_string()
+        } else {
+            stderr
+        };
+        let lower = detail.to_lowercase();
+
+        if lower.contains("device offline") || lower.contains("device is offline") {
+            MdtError::DeviceOffline {
+                device_id: device_id.clone(),
+            }
+        } else if lower.contains("unauthorized") {
+            MdtError::DeviceUnauthorized {
+                device_id: device_id.clone(),
+            }
+        } else if lower.contains("no devices/emulators found")
+            || (lower.contains("device") && lower.contains("not found"))
+            || lower.contains("not match target")
+        {
+            MdtError::DeviceNotFound {
+                device_id: device_id.clone(),
+            }
+        } else if lower.contains("permission denied") || lower.contains("operation not permitted") {
+            MdtError::PermissionDenied { detail }
+        } else {
+            MdtError::CommandFailed {
+                command,
+                exit_code: output.status.code(),
+                stderr: detail,
+            }
+        }
+    }
+}
+
+impl fmt::Display for MdtError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MdtError::ToolNotFound { tool } => write!(f, "未找到工具 '{}'", tool),
+            MdtError::DeviceOffline { device_id } => {
+                write!(f, "设备 {} 已离线", device_id.as_deref().unwrap_or("default"))
+            }
+            MdtError::DeviceUnauthorized { device_id } => write!(
+                f,
+                "设备 {} 未授权调试，请在设备上确认授权",
+                device_id.as_deref().unwrap_or("default")
+            ),
+            MdtError::DeviceNotFound { device_id } => {
+                write!(f, "未找到设备 {}", device_id.as_deref().unwrap_or("default"))
+            }
+            MdtError::PermissionDenied { detail } => write!(f, "权限不足: {}", detail),
+            MdtError::InvalidArgument { field, detail } => {
+                write!(f, "参数 {} 无效: {}", field, detail)
+            }
+            MdtError::CommandFailed { command, stderr, .. } => {
+                write!(f, "执行 {} 失败: {}", command, stderr)
+            }
+            MdtError::Io { context, detail } => write!(f, "{}失败: {}", context, detail),
+            MdtError::SessionExists { kind, device_id } => {
+                write!(f, "设备 {} 已存在进行中的 {} 会话", device_id, kind)
+            }
+            MdtError::SessionNotFound { kind, device_id } => {
+                write!(f, "设备 {} 没有进行中的 {} 会话", device_id, kind)
+            }
+            MdtError::StateLock { kind } => write!(f, "{} 状态锁定失败", kind),
+            MdtError::Cancelled { operation } => write!(f, "{} 已取消", operation),
+            MdtError::ChecksumMismatch {
+                path,
+                algorithm,
+                local,
+                remote,
+            } => write!(
+                f,
+                "{} 校验失败（{}）: 本地 {}，设备 {}",
+                path, algorithm, local, remote
+            ),
+            MdtError::TransferIncomplete { path, expected, actual } => match actual {
+                Some(actual) => write!(f, "{} 传输不完整: 应为 {} 字节，设备上为 {} 字节", path, expected, actual),
+                None => write!(f, "{} 传输不完整: 设备上未找到该文件", path),
+            },
+            MdtError::Unsupported { feature, detail } => write!(f, "设备不支持{}: {}", feature, detail),
+        }
+    }
+}
+
+impl std::error::Error for MdtError {}
+
+impl Serialize for MdtError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("MdtError", 3)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("context", &self.context())?;
+        state.end()
+    }
+}
+
+pub type MdtResult<T> = Result<T, MdtError>;
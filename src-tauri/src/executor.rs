@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::process::Command;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
 use crate::tools;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -14,13 +16,15 @@ pub struct CommandOutput {
 pub async fn execute_command(
     program: String,
     args: Vec<String>,
-) -> Result<CommandOutput, String> {
+) -> MdtResult<CommandOutput> {
+    let _history = history::track();
     let mut cmd = match program.as_str() {
         "adb" | "hdc" | "idevice_id" | "ideviceinstaller" => tools::command_for(&program),
         _ => Command::new(&program),
     };
+    cmd.args(&args);
 
-    let output = cmd.args(&args).output().map_err(|e| format!("执行命令失败: {}", e))?;
+    let output = tools::output(cmd).await.map_err(|e| MdtError::spawn(&program, e))?;
 
     Ok(CommandOutput {
         success: output.status.success(),
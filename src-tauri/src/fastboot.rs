@@ -0,0 +1,149 @@
+use serde::{Deserialize, Serialize};
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FastbootDevice {
+    pub serial: String,
+    /// bootloader 中为 fastboot，用户态 fastbootd 中为 fastbootd
+    pub mode: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnlockStatus {
+    pub unlocked: Option<bool>,
+    pub secure: Option<bool>,
+    pub product: Option<String>,
+    pub current_slot: Option<String>,
+    /// 是否处于 fastbootd（用户态 fastboot）
+    pub userspace: Option<bool>,
+}
+
+fn fastboot_command(device_id: &Option<String>) -> Command {
+    let mut cmd = tools::command_for("fastboot");
+    if let Some(device) = device_id {
+        cmd.args(["-s", device]);
+    }
+    cmd
+}
+
+/// 执行 fastboot 并返回合并后的输出（fastboot 的大部分信息写在 stderr）
+async fn fastboot_run(device_id: &Option<String>, args: &[&str]) -> MdtResult<String> {
+    let mut cmd = fastboot_command(device_id);
+    cmd.args(args);
+    let output = tools::run_checked("fastboot", device_id, cmd).await?;
+    Ok(format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+    .trim()
+    .to_string())
+}
+
+/// 解析 getvar 输出中的 `name: value` 行（`getvar all` 为 `(bootloader) name: value`）
+fn parse_getvar(output: &str) -> HashMap<String, String> {
+    let mut vars = HashMap::new();
+    for line in output.lines() {
+        let line = line.trim().trim_start_matches("(bootloader)").trim();
+        if line.starts_with("Finished.") || line.starts_with("getvar:") {
+            continue;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            vars.insert(name.trim().to_string(), value.trim().to_string());
+        }
+    }
+    vars
+}
+
+fn parse_yes_no(value: Option<&String>) -> Option<bool> {
+    match value.map(|v| v.as_str()) {
+        Some("yes") => Some(true),
+        Some("no") => Some(false),
+        _ => None,
+    }
+}
+
+#[tauri::command]
+pub async fn fastboot_devices() -> MdtResult<Vec<FastbootDevice>> {
+    let output = fastboot_run(&None, &["devices"]).await?;
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some(FastbootDevice {
+                serial: parts.next()?.to_string(),
+                mode: parts.next().unwrap_or("fastboot").to_string(),
+            })
+        })
+        .collect())
+}
+
+/// 读取 bootloader 变量；`variable` 为 `all` 时返回全部变量
+#[tauri::command]
+pub async fn fastboot_getvar(device_id: Option<String>, variable: String) -> MdtResult<HashMap<String, String>> {
+    if variable.is_empty() || !variable.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | ':')) {
+        return Err(MdtError::invalid_argument("variable", format!("无效的变量名: {}", variable)));
+    }
+    let output = fastboot_run(&device_id, &["getvar", &variable]).await?;
+    Ok(parse_getvar(&output))
+}
+
+#[tauri::command]
+pub async fn fastboot_unlock_status(device_id: Option<String>) -> MdtResult<UnlockStatus> {
+    let vars = parse_getvar(&fastboot_run(&device_id, &["getvar", "all"]).await?);
+    Ok(UnlockStatus {
+        unlocked: parse_yes_no(vars.get("unlocked")),
+        secure: parse_yes_no(vars.get("secure")),
+        product: vars.get("product").cloned(),
+        current_slot: vars.get("current-slot").cloned(),
+        userspace: parse_yes_no(vars.get("is-userspace")),
+    })
+}
+
+/// 刷写分区镜像，需由界面确认后传入 `confirm = true`
+#[tauri::command]
+pub async fn fastboot_flash(
+    device_id: Option<String>,
+    partition: String,
+    image_path: String,
+    confirm: bool,
+) -> MdtResult<String> {
+    let _history = history::track();
+    if !confirm {
+        return Err(MdtError::invalid_argument("confirm", "刷写分区需要确认"));
+    }
+    if partition.is_empty() || !partition.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(MdtError::invalid_argument("partition", format!("无效的分区名: {}", partition)));
+    }
+    if !Path::new(&image_path).is_file() {
+        return Err(MdtError::invalid_argument("imagePath", format!("镜像文件不存在: {}", image_path)));
+    }
+    println!("[fastboot] flashing {} with {}", partition, image_path);
+    fastboot_run(&device_id, &["flash", &partition, &image_path]).await
+}
+
+/// 重启设备，`target` 可选 system（默认）/ bootloader / fastboot / recovery
+#[tauri::command]
+pub async fn fastboot_reboot(device_id: Option<String>, target: Option<String>) -> MdtResult<String> {
+    let _history = history::track();
+    let target = target.unwrap_or_else(|| "system".to_string());
+    let mut args = vec!["reboot"];
+    match target.as_str() {
+        "system" => {}
+        "bootloader" | "fastboot" | "recovery" => args.push(&target),
+        _ => {
+            return Err(MdtError::invalid_argument(
+                "target",
+                format!("不支持的重启目标: {}", target),
+            ))
+        }
+    }
+    fastboot_run(&device_id, &args).await
+}
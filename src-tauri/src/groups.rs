@@ -0,0 +1,129 @@
+use crate::error::{MdtError, MdtResult};
+use crate::store;
+use serde::{Deserialize, Serialize};
+use std::sync::{Mutex, OnceLock};
+
+const GROUPS_FILE: &str = "device-groups";
+
+/// 命名的设备分组（如“回归测试机架”），批量操作可直接传分组名
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceGroup {
+    pub name: String,
+    pub devices: Vec<String>,
+    /// 收藏的分组在列表中置顶
+    #[serde(default)]
+    pub favorite: bool,
+}
+
+fn groups() -> &'static Mutex<Vec<DeviceGroup>> {
+    static STORE: OnceLock<Mutex<Vec<DeviceGroup>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(store::load(GROUPS_FILE)))
+}
+
+fn normalize_devices(devices: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for device in devices.into_iter().map(|device| device.trim().to_string()) {
+        if !device.is_empty() && !normalized.contains(&device) {
+            normalized.push(device);
+        }
+    }
+    normalized
+}
+
+/// 在锁内修改分组并持久化，写入完成前不释放锁，保证文件与内存中的顺序一致
+fn update_groups<R>(update: impl FnOnce(&mut Vec<DeviceGroup>) -> MdtResult<R>) -> MdtResult<R> {
+    let mut entries = groups().lock().map_err(|_| MdtError::lock("device groups"))?;
+    let result = update(&mut entries)?;
+    store::save(GROUPS_FILE, &*entries)?;
+    Ok(result)
+}
+
+fn group_not_found(name: &str) -> MdtError {
+    MdtError::invalid_argument("group", format!("设备分组不存在: {}", name))
+}
+
+/// 解析批量操作的目标设备：指定了分组时使用分组成员，否则使用显式的设备列表
+pub fn resolve_devices(device_ids: Option<Vec<String>>, group: Option<String>) -> MdtResult<Vec<String>> {
+    let devices = match group.filter(|group| !group.trim().is_empty()) {
+        Some(name) => {
+            let store = groups().lock().map_err(|_| MdtError::lock("device groups"))?;
+            store
+                .iter()
+                .find(|group| group.name == name.trim())
+                .map(|group| group.devices.clone())
+                .ok_or_else(|| group_not_found(&name))?
+        }
+        None => normalize_devices(device_ids.unwrap_or_default()),
+    };
+    if devices.is_empty() {
+        return Err(MdtError::invalid_argument("deviceIds", "至少需要选择一台设备"));
+    }
+    Ok(devices)
+}
+
+/// 列出设备分组，收藏的分组在前
+#[tauri::command]
+pub async fn list_device_groups() -> MdtResult<Vec<DeviceGroup>> {
+    let store = groups().lock().map_err(|_| MdtError::lock("device groups"))?;
+    let mut list = store.clone();
+    list.sort_by_key(|group| !group.favorite);
+    Ok(list)
+}
+
+#[tauri::command]
+pub async fn create_device_group(name: String, devices: Option<Vec<String>>) -> MdtResult<DeviceGroup> {
+    let name = name.trim().to_string();
+    if name.is_empty() {
+        return Err(MdtError::invalid_argument("name", "分组名不能为空"));
+    }
+    let group = DeviceGroup {
+        name,
+        devices: normalize_devices(devices.unwrap_or_default()),
+        favorite: false,
+    };
+    update_groups(|store| {
+        if store.iter().any(|existing| existing.name == group.name) {
+            return Err(MdtError::invalid_argument("name", format!("设备分组已存在: {}", group.name)));
+        }
+        store.push(group.clone());
+        Ok(group)
+    })
+}
+
+#[tauri::command]
+pub async fn delete_device_group(name: String) -> MdtResult<()> {
+    update_groups(|store| {
+        let before = store.len();
+        store.retain(|group| group.name != name);
+        if store.len() == before {
+            return Err(group_not_found(&name));
+        }
+        Ok(())
+    })
+}
+
+/// 替换分组成员
+#[tauri::command]
+pub async fn set_device_group_members(name: String, devices: Vec<String>) -> MdtResult<DeviceGroup> {
+    update_groups(|store| {
+        let group = store
+            .iter_mut()
+            .find(|group| group.name == name)
+            .ok_or_else(|| group_not_found(&name))?;
+        group.devices = normalize_devices(devices);
+        Ok(group.clone())
+    })
+}
+
+#[tauri::command]
+pub async fn set_device_group_favorite(name: String, favorite: bool) -> MdtResult<DeviceGroup> {
+    update_groups(|store| {
+        let group = store
+            .iter_mut()
+            .find(|group| group.name == name)
+            .ok_or_else(|| group_not_found(&name))?;
+        group.favorite = favorite;
+        Ok(group.clone())
+    })
+}
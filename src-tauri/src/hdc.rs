@@ -1,7 +1,25 @@
 use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use crate::aliases;
+use crate::config;
+use crate::batch::{self, BatchDeviceResult};
+use crate::device_info::{self, DeviceDetails, InfoCache};
+use crate::error::{MdtError, MdtResult};
+use crate::groups;
+use crate::history;
+use crate::process::{self, ProcessInfo};
+use crate::screenshot::{self, ScreenshotFormat, ScreenshotOptions, ScreenshotPreview};
+use crate::sessions::{ActiveSession, SessionKind};
+use crate::remote_fs::{self, RemoteFileEntry};
 use crate::tools;
+use crate::transfer::{
+    self, ChecksumAlgorithm, DirectoryEntry, DirectoryTransferRequest, DirectoryTransferResult,
+    EntryCommandBuilder, RemoteFile, SizeProbe, TransferRequest,
+};
 use std::collections::HashMap;
-use std::sync::{Mutex, OnceLock};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Arc, Mutex, OnceLock};
 use std::time::SystemTime;
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -9,6 +27,8 @@ pub struct Device {
     pub id: String,
     pub status: String,
     pub model: Option<String>,
+    /// 用户设置的设备别名
+    pub alias: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -16,7 +36,7 @@ pub struct DeviceList {
     pub devices: Vec<Device>,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct DeviceInfo {
     pub model: Option<String>,
@@ -25,6 +45,8 @@ pub struct DeviceInfo {
     pub version: Option<String>,
     pub battery_level: Option<u8>,
     pub battery_status: Option<String>,
+    #[serde(flatten)]
+    pub details: DeviceDetails,
 }
 
 struct ScreenRecordSession {
@@ -38,48 +60,120 @@ fn screen_recordings() -> &'static Mutex<HashMap<String, ScreenRecordSession>> {
     STORE.get_or_init(|| Mutex::new(HashMap::new()))
 }
 
-fn device_key(device_id: &Option<String>) -> String {
-    device_id.clone().unwrap_or_else(|| "default".to_string())
+/// 当前的录屏会话
+pub(crate) fn active_sessions() -> MdtResult<Vec<ActiveSession>> {
+    let store = screen_recordings()
+        .lock()
+        .map_err(|_| MdtError::lock("screenrecord"))?;
+    Ok(store
+        .iter()
+        .map(|(device, session)| {
+            let mut active = ActiveSession::new("harmony", device, SessionKind::Screenrecord, session.start_time);
+            active.output = Some(session.remote_path.clone());
+            active
+        })
+        .collect())
 }
 
-fn hdc_shell(device_id: &Option<String>, args: &[&str]) -> Result<String, String> {
-    use std::process::Command;
-
+/// 创建指定设备的 hdc 命令（自动附加 `-t <device>`）
+fn hdc_command(device_id: &Option<String>) -> Command {
     let mut cmd = tools::command_for("hdc");
     if let Some(device) = device_id {
-        cmd.args(&["-t", device]);
+        cmd.args(["-t", device]);
     }
+    cmd
+}
+
+/// 执行 hdc 子命令，成功时返回标准输出
+pub(crate) async fn hdc_run(device_id: &Option<String>, args: &[&str]) -> MdtResult<String> {
+    let mut cmd = hdc_command(device_id);
+    cmd.args(args);
+
+    let output = tools::run_checked("hdc", device_id, cmd).await?;
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+pub(crate) async fn hdc_shell(device_id: &Option<String>, args: &[&str]) -> MdtResult<String> {
+    let mut cmd = hdc_command(device_id);
     cmd.arg("shell");
     cmd.args(args);
 
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 hdc shell 失败: {}", e))?;
+    let output = tools::run_checked("hdc", device_id, cmd).await?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+/// 推送目标为设备端目录时补全文件名，便于统计设备端已写入的大小
+async fn hdc_push_target(device_id: &Option<String>, local_path: &str, remote_path: &str) -> String {
+    let file_name = Path::new(local_path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let is_dir = remote_path.ends_with('/')
+        || hdc_shell(device_id, &["stat", "-c", "%F", &tools::shell_quote(remote_path)])
+            .await
+            .map(|kind| kind.contains("directory"))
+            .unwrap_or(false);
+
+    if is_dir && !file_name.is_empty() {
+        format!("{}/{}", remote_path.trim_end_matches('/'), file_name)
     } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+        remote_path.to_string()
     }
 }
 
-#[tauri::command]
-pub async fn hdc_list_targets() -> Result<DeviceList, String> {
-    use std::process::Command;
+/// 推送进度统计：定时查询设备端目标文件大小
+fn hdc_remote_size_probe(device_id: Option<String>, target: String) -> SizeProbe {
+    SizeProbe::Remote(Box::new(move || {
+        let mut cmd = hdc_command(&device_id);
+        cmd.args(["shell", "stat", "-c", "%s", &tools::shell_quote(&target)]);
+        cmd
+    }))
+}
 
-    let output = tools::command_for("hdc")
-        .args(&["list", "targets"])
-        .output()
-        .map_err(|e| format!("执行 hdc list targets 失败: {}", e))?;
+/// 递归列出设备端目录下的文件
+async fn hdc_remote_listing(
+    device_id: &Option<String>,
+    remote_dir: &str,
+) -> MdtResult<HashMap<String, RemoteFile>> {
+    let args = transfer::remote_listing_args(remote_dir);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = hdc_shell(device_id, &args).await?;
+    Ok(transfer::parse_remote_listing(remote_dir, &output))
+}
 
-    if !output.status.success() {
-        return Err(format!(
-            "hdc list targets 执行失败: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ));
+/// 传输完成后对比本地与设备端文件的校验和，设备不支持 sha256sum 时回退到 md5sum
+async fn hdc_verify_checksum(
+    device_id: &Option<String>,
+    local_path: PathBuf,
+    remote_path: &str,
+) -> MdtResult<()> {
+    let quoted = tools::shell_quote(remote_path);
+    let mut last_error = None;
+    for algorithm in ChecksumAlgorithm::ALL {
+        match hdc_shell(device_id, &[algorithm.device_command(), &quoted]).await {
+            Ok(output) => {
+                return transfer::compare_checksum(algorithm, local_path, remote_path, &output).await;
+            }
+            Err(err) => last_error = Some(err),
+        }
     }
+    Err(last_error.unwrap_or_else(|| MdtError::ToolNotFound {
+        tool: "sha256sum".to_string(),
+    }))
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+/// 查询设备端文件大小
+async fn hdc_remote_size(device_id: &Option<String>, remote_path: &str) -> Option<u64> {
+    hdc_shell(device_id, &["stat", "-c", "%s", &tools::shell_quote(remote_path)])
+        .await
+        .ok()?
+        .parse()
+        .ok()
+}
+
+#[tauri::command]
+pub async fn hdc_list_targets() -> MdtResult<DeviceList> {
+    let stdout = hdc_run(&None, &["list", "targets"]).await?;
     let mut devices = Vec::new();
 
     // 解析 hdc list targets 输出
@@ -99,6 +193,7 @@ pub async fn hdc_list_targets() -> Result<DeviceList, String> {
                 "device"
             };
             devices.push(Device {
+                alias: aliases::alias_for(&id),
                 id,
                 status: status.to_string(),
                 model: None,
@@ -106,26 +201,143 @@ pub async fn hdc_list_targets() -> Result<DeviceList, String> {
         }
     }
 
+    let online: Vec<String> = devices
+        .iter()
+        .filter(|device| device.status == "device")
+        .map(|device| device.id.clone())
+        .collect();
+    prefetch_device_info(&online);
+
     Ok(DeviceList { devices })
 }
 
+/// `param get` 在参数不存在时仍以 0 退出并输出 `Get parameter "x" fail!`
+fn param_value(value: Option<String>) -> Option<String> {
+    value.filter(|value| !value.contains("fail!"))
+}
+
+/// 从 `hidumper -s RenderService -a screen` 中取出 `render size: 1260x2720`，
+/// 缺失时退回 `physical screen resolution`
+fn parse_screen_resolution(output: &str) -> Option<String> {
+    ["render size:", "physical screen resolution:"].iter().find_map(|key| {
+        let start = output.find(key)? + key.len();
+        let size: String = output[start..]
+            .trim_start()
+            .chars()
+            .take_while(|c| c.is_ascii_digit() || *c == 'x')
+            .collect();
+        size.contains('x').then_some(size)
+    })
+}
+
+/// `hidumper -s DisplayManagerService -a -a` 中的 `VirtualPixelRatio`，按 160 dpi 基准换算为密度
+fn parse_density(output: &str) -> Option<u32> {
+    output.lines().find_map(|line| {
+        let (key, value) = line.split_once(':')?;
+        if !key.trim().eq_ignore_ascii_case("VirtualPixelRatio") {
+            return None;
+        }
+        let ratio: f64 = value.trim().parse().ok()?;
+        Some((ratio * 160.0).round() as u32)
+    })
+}
+
+fn device_info_cache() -> &'static InfoCache<DeviceInfo> {
+    static CACHE: OnceLock<InfoCache<DeviceInfo>> = OnceLock::new();
+    CACHE.get_or_init(InfoCache::default)
+}
+
+/// 对新上线的设备在后台预取设备信息，并清理已断开设备的缓存
+fn prefetch_device_info(online: &[String]) {
+    for serial in device_info_cache().sync_online(online) {
+        tauri::async_runtime::spawn(async move {
+            let device_id = Some(serial.clone());
+            let info = match collect_device_info(&device_id).await {
+                Ok(info) => Some(info),
+                Err(err) => {
+                    println!("[hdc] prefetch device info failed on {}: {}", serial, err);
+                    None
+                }
+            };
+            device_info_cache().finish_prefetch(serial, info);
+        });
+    }
+}
+
+/// 设备信息在缓存有效期内直接返回缓存，`refresh` 为 true 时强制重新采集
 #[tauri::command]
-pub async fn hdc_device_info(device_id: Option<String>) -> Result<DeviceInfo, String> {
-    let model = hdc_shell(&device_id, &["param", "get", "ro.product.model"]).ok();
-    let brand = hdc_shell(&device_id, &["param", "get", "ro.product.brand"]).ok();
-    let name = hdc_shell(&device_id, &["param", "get", "ro.product.name"]).ok();
-    let version = hdc_shell(&device_id, &["param", "get", "ro.build.version.release"]).ok();
+pub async fn hdc_device_info(device_id: Option<String>, refresh: Option<bool>) -> MdtResult<DeviceInfo> {
+    let key = tools::device_key(&device_id);
+    if !refresh.unwrap_or(false) {
+        if let Some(info) = device_info_cache().get(&key) {
+            return Ok(info);
+        }
+    }
+    let info = collect_device_info(&device_id).await?;
+    device_info_cache().put(key, info.clone());
+    Ok(info)
+}
+
+async fn collect_device_info(device_id: &Option<String>) -> MdtResult<DeviceInfo> {
+    // 各项查询互不依赖，并发执行以缩短等待时间
+    let query = |args: &'static [&'static str]| {
+        let device_id = device_id.clone();
+        device_info::spawn_query(async move { hdc_shell(&device_id, args).await })
+    };
+    let model = query(&["param", "get", "ro.product.model"]);
+    let brand = query(&["param", "get", "ro.product.brand"]);
+    let name = query(&["param", "get", "ro.product.name"]);
+    let version = query(&["param", "get", "ro.build.version.release"]);
+    let battery = query(&["hidumper", "-s", "3301"]);
+    let screen = query(&["hidumper", "-s", "RenderService", "-a", "screen"]);
+    let display = query(&["hidumper", "-s", "DisplayManagerService", "-a", "-a"]);
+    let abis = query(&["param", "get", "const.product.cpu.abilist"]);
+    let sdk = query(&["param", "get", "const.ohos.apiversion"]);
+    let meminfo = query(&["cat", "/proc/meminfo"]);
+    let cpuinfo = query(&["cat", "/proc/cpuinfo"]);
+    let hardware = query(&["param", "get", "ohos.boot.hardware"]);
+    let kernel = query(&["uname", "-r"]);
+    let uptime = query(&["cat", "/proc/uptime"]);
+    let wlan = query(&["ifconfig", "wlan0"]);
+    let serial = query(&["param", "get", "ohos.boot.sn"]);
+
+    let (total_ram_kb, available_ram_kb) = device_info::join_query(meminfo)
+        .await
+        .map(|output| device_info::parse_meminfo(&output))
+        .unwrap_or_default();
+    let cpu_hardware = match device_info::join_query(cpuinfo).await.and_then(|output| device_info::parse_cpu_hardware(&output)) {
+        Some(hardware) => Some(hardware),
+        None => param_value(device_info::join_query(hardware).await),
+    };
+    let (wifi_mac, wifi_ip) = device_info::join_query(wlan)
+        .await
+        .map(|output| device_info::parse_interface(&output))
+        .unwrap_or_default();
 
     let mut info = DeviceInfo {
-        model,
-        brand,
-        name,
-        version,
+        model: param_value(device_info::join_query(model).await),
+        brand: param_value(device_info::join_query(brand).await),
+        name: param_value(device_info::join_query(name).await),
+        version: param_value(device_info::join_query(version).await),
         battery_level: None,
         battery_status: None,
+        details: DeviceDetails {
+            resolution: device_info::join_query(screen).await.and_then(|output| parse_screen_resolution(&output)),
+            density: device_info::join_query(display).await.and_then(|output| parse_density(&output)),
+            abis: device_info::parse_abis(param_value(device_info::join_query(abis).await)),
+            sdk_level: param_value(device_info::join_query(sdk).await).and_then(|value| value.parse().ok()),
+            total_ram_kb,
+            available_ram_kb,
+            cpu_hardware,
+            kernel_version: device_info::join_query(kernel).await,
+            uptime_secs: device_info::join_query(uptime).await.and_then(|output| device_info::parse_uptime(&output)),
+            wifi_mac,
+            wifi_ip,
+            serial: param_value(device_info::join_query(serial).await),
+        },
     };
 
-    if let Ok(battery_dump) = hdc_shell(&device_id, &["hidumper", "-s", "3301"]) {
+    if let Some(battery_dump) = device_info::join_query(battery).await {
         for line in battery_dump.lines() {
             let trimmed = line.trim().to_lowercase();
             if trimmed.contains("level") {
@@ -152,73 +364,275 @@ pub async fn hdc_device_info(device_id: Option<String>) -> Result<DeviceInfo, St
     Ok(info)
 }
 
-#[tauri::command]
-pub async fn hdc_install(device_id: Option<String>, app_path: String) -> Result<String, String> {
-    use std::process::Command;
+/// `hidumper -s BatteryService` 解析出的电池详情
+#[derive(Debug, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BatteryDetails {
+    pub level: Option<u8>,
+    pub status: Option<String>,
+    /// good / overheat / overvoltage / cold / dead / unknown
+    pub health: Option<String>,
+    /// ac / usb / wireless，未充电时为空
+    pub plug_type: Option<String>,
+    pub present: Option<bool>,
+    /// 电压（mV）
+    pub voltage_mv: Option<u32>,
+    /// 温度（摄氏度）
+    pub temperature_c: Option<f32>,
+    /// 剩余电量（µAh）
+    pub charge_counter_uah: Option<u64>,
+    pub technology: Option<String>,
+}
 
-    let mut cmd = tools::command_for("hdc");
-    
-    if let Some(device) = device_id {
-        cmd.args(&["-t", &device]);
+fn parse_battery_details(output: &str) -> BatteryDetails {
+    let mut details = BatteryDetails::default();
+    for line in output.lines() {
+        let Some((key, value)) = line.trim().split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "capacity" => details.level = value.parse().ok(),
+            "chargingStatus" => {
+                details.status = Some(
+                    match value {
+                        "1" => "charging",
+                        "0" => "discharging",
+                        // 已连接充电器但未在充电
+                        "2" => "not_charging",
+                        "3" => "full",
+                        _ => "unknown",
+                    }
+                    .to_string(),
+                )
+            }
+            "healthState" => {
+                details.health = Some(
+                    match value {
+                        "1" => "good",
+                        "2" => "overheat",
+                        "3" => "overvoltage",
+                        "4" => "cold",
+                        "5" => "dead",
+                        _ => "unknown",
+                    }
+                    .to_string(),
+                )
+            }
+            "pluggedType" => {
+                details.plug_type = match value {
+                    "1" => Some("ac".to_string()),
+                    "2" => Some("usb".to_string()),
+                    "3" => Some("wireless".to_string()),
+                    _ => None,
+                }
+            }
+            "present" => details.present = Some(value == "1" || value == "true"),
+            // 电压、电量单位为 µV / µAh
+            "voltage" => details.voltage_mv = value.parse::<u32>().ok().map(|v| v / 1000),
+            "temperature" => {
+                details.temperature_c = value.parse::<f32>().ok().map(|t| t / 10.0)
+            }
+            "remainingEnergy" => details.charge_counter_uah = value.parse().ok(),
+            "technology" => details.technology = Some(value.to_string()),
+            _ => {}
+        }
     }
-    
-    cmd.args(&["install", &app_path]);
-    
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 hdc install 失败: {}", e))?;
+    details
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+/// 查询电池温度、健康度、电压、剩余电量、充电方式等详情
+#[tauri::command]
+pub async fn hdc_battery_details(device_id: Option<String>) -> MdtResult<BatteryDetails> {
+    let output = hdc_shell(&device_id, &["hidumper", "-s", "BatteryService", "-a", "-i"]).await?;
+    Ok(parse_battery_details(&output))
+}
+
+/// 重启设备（`hdc target boot`），需由界面确认后传入 `confirm = true`
+#[tauri::command]
+pub async fn hdc_reboot(device_id: Option<String>, confirm: bool) -> MdtResult<()> {
+    let _history = history::track();
+    if !confirm {
+        return Err(MdtError::invalid_argument("confirm", "重启设备需要确认"));
     }
+    hdc_run(&device_id, &["target", "boot"]).await?;
+    println!("[hdc] rebooting {}", device_id.as_deref().unwrap_or("default"));
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn hdc_uninstall(device_id: Option<String>, package_name: String) -> Result<String, String> {
-    use std::process::Command;
+pub async fn hdc_install(device_id: Option<String>, app_path: String) -> MdtResult<String> {
+    let _history = history::track();
+    hdc_run(&device_id, &["install", &app_path]).await
+}
 
-    let mut cmd = tools::command_for("hdc");
-    
-    if let Some(device) = device_id {
-        cmd.args(&["-t", &device]);
+#[tauri::command]
+pub async fn hdc_uninstall(device_id: Option<String>, package_name: String) -> MdtResult<String> {
+    let _history = history::track();
+    hdc_run(&device_id, &["uninstall", &package_name]).await
+}
+
+/// 从多台设备上卸载同一个包，返回以设备 ID 为键的结果
+#[tauri::command]
+pub async fn hdc_uninstall_batch(
+    app: AppHandle,
+    device_ids: Option<Vec<String>>,
+    group: Option<String>,
+    package_name: String,
+    parallel: Option<usize>,
+) -> MdtResult<HashMap<String, BatchDeviceResult>> {
+    let _history = history::track();
+    let device_ids = groups::resolve_devices(device_ids, group)?;
+    let results = batch::run_batch(&app, "uninstall", device_ids, parallel, move |device_id| {
+        let package_name = package_name.clone();
+        async move {
+            let output = hdc_run(&Some(device_id.clone()), &["uninstall", &package_name]).await?;
+            // hdc 卸载失败时仍以 0 退出，需要根据输出判断
+            if output.contains("[Fail]") {
+                return Err(MdtError::CommandFailed {
+                    command: format!("hdc -t {} uninstall {}", device_id, package_name),
+                    exit_code: None,
+                    stderr: output.trim().to_string(),
+                });
+            }
+            Ok(output)
+        }
+    })
+    .await;
+    Ok(results
+        .into_iter()
+        .map(|result| (result.device_id.clone(), result))
+        .collect())
+}
+
+/// 校验包名 / Ability 名，只允许字母、数字、`.` 与 `_`，避免拼入设备端 shell 时被解析
+pub(crate) fn validate_bundle_name(field: &str, name: &str) -> MdtResult<()> {
+    let valid = !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(MdtError::invalid_argument(field, format!("无效的名称: {}", name)))
     }
-    
-    cmd.args(&["uninstall", &package_name]);
-    
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 hdc uninstall 失败: {}", e))?;
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+/// 确认设备上已安装指定应用
+async fn hdc_ensure_bundle(device_id: &Option<String>, bundle_name: &str) -> MdtResult<()> {
+    validate_bundle_name("bundleName", bundle_name)?;
+    let output = hdc_shell(device_id, &["bm", "dump", "-n", &tools::shell_quote(bundle_name)])
+        .await
+        .unwrap_or_default();
+    if output.is_empty() || output.to_lowercase().contains("error") {
+        Err(MdtError::invalid_argument(
+            "bundleName",
+            format!("设备上未安装 {}", bundle_name),
+        ))
     } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+        Ok(())
     }
 }
 
+/// 强制停止应用（`aa force-stop`）
 #[tauri::command]
-pub async fn hdc_list_packages(device_id: Option<String>) -> Result<Vec<String>, String> {
-    use std::process::Command;
+pub async fn hdc_force_stop(device_id: Option<String>, bundle_name: String) -> MdtResult<()> {
+    let _history = history::track();
+    hdc_ensure_bundle(&device_id, &bundle_name).await?;
+    hdc_shell(&device_id, &["aa", "force-stop", &tools::shell_quote(&bundle_name)]).await?;
+    Ok(())
+}
 
-    let mut cmd = tools::command_for("hdc");
-    
-    if let Some(device) = device_id {
-        cmd.args(&["-t", &device]);
+/// 从 `bm dump -n` 的 JSON 输出中查找入口 Ability 名称
+fn find_main_ability(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::Object(map) => {
+            for key in ["mainAbility", "mainElementName"] {
+                if let Some(name) = map.get(key).and_then(|v| v.as_str()) {
+                    if !name.is_empty() {
+                        return Some(name.to_string());
+                    }
+                }
+            }
+            map.values().find_map(find_main_ability)
+        }
+        serde_json::Value::Array(items) => items.iter().find_map(find_main_ability),
+        _ => None,
     }
-    
-    cmd.args(&["shell", "bm", "dump", "-n"]);
-    
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 hdc shell bm dump -n 失败: {}", e))?;
-
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
+}
+
+/// 启动应用（`aa start`），未指定 Ability 时自动使用入口 Ability
+#[tauri::command]
+pub async fn hdc_launch_app(
+    device_id: Option<String>,
+    bundle_name: String,
+    ability_name: Option<String>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    validate_bundle_name("bundleName", &bundle_name)?;
+    let ability = match ability_name {
+        Some(name) => name,
+        None => {
+            let output = hdc_shell(&device_id, &["bm", "dump", "-n", &tools::shell_quote(&bundle_name)]).await?;
+            // 输出首行为包名，其后为 JSON
+            output
+                .find('{')
+                .and_then(|start| serde_json::from_str::<serde_json::Value>(&output[start..]).ok())
+                .as_ref()
+                .and_then(find_main_ability)
+                .ok_or_else(|| {
+                    MdtError::invalid_argument(
+                        "bundleName",
+                        format!("无法解析 {} 的入口 Ability", bundle_name),
+                    )
+                })?
+        }
+    };
+    validate_bundle_name("abilityName", &ability)?;
+    hdc_shell(
+        &device_id,
+        &["aa", "start", "-b", &tools::shell_quote(&bundle_name), "-a", &tools::shell_quote(&ability)],
+    )
+    .await
+}
+
+/// 列出设备上运行中的进程
+#[tauri::command]
+pub async fn hdc_list_processes(device_id: Option<String>) -> MdtResult<Vec<ProcessInfo>> {
+    let output = hdc_shell(&device_id, &process::PS_ARGS).await?;
+    Ok(process::parse_ps(&output))
+}
+
+/// 按 PID 结束进程
+#[tauri::command]
+pub async fn hdc_kill_process(device_id: Option<String>, pid: u32) -> MdtResult<()> {
+    let _history = history::track();
+    hdc_shell(&device_id, &["kill", &pid.to_string()]).await?;
+    Ok(())
+}
+
+/// 清除应用数据（`bm clean -d`）或仅清除缓存（`bm clean -c`）
+#[tauri::command]
+pub async fn hdc_clean_app(
+    device_id: Option<String>,
+    bundle_name: String,
+    cache_only: Option<bool>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    validate_bundle_name("bundleName", &bundle_name)?;
+    let flag = if cache_only.unwrap_or(false) { "-c" } else { "-d" };
+    let output = hdc_shell(&device_id, &["bm", "clean", "-n", &tools::shell_quote(&bundle_name), flag]).await?;
+    if output.to_lowercase().contains("success") {
+        Ok(output)
+    } else {
+        Err(MdtError::CommandFailed {
+            command: format!("bm clean -n {} {}", bundle_name, flag),
+            exit_code: None,
+            stderr: output,
+        })
     }
+}
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
+#[tauri::command]
+pub async fn hdc_list_packages(device_id: Option<String>) -> MdtResult<Vec<String>> {
+    let stdout = hdc_run(&device_id, &["shell", "bm", "dump", "-n"]).await?;
     let packages: Vec<String> = stdout
         .lines()
         .filter_map(|line| {
@@ -240,78 +654,169 @@ pub async fn hdc_list_packages(device_id: Option<String>) -> Result<Vec<String>,
     Ok(packages)
 }
 
-#[tauri::command]
-pub async fn hdc_screenshot(
-    device_id: Option<String>,
-    output_path: Option<String>,
-) -> Result<String, String> {
-    use std::process::Command;
+/// 按设置的目录与文件名模板生成截图 / 录屏的默认本地路径
+async fn default_capture_path(
+    device_id: &Option<String>,
+    kind: config::OutputKind,
+    timestamp: u64,
+    extension: &str,
+) -> MdtResult<String> {
+    let model = if config::template_uses_model() {
+        match device_info_cache().get(&tools::device_key(device_id)).and_then(|info| info.model) {
+            Some(model) => Some(model),
+            None => hdc_shell(device_id, &["param", "get", "const.product.model"]).await.ok(),
+        }
+    } else {
+        None
+    };
+    config::capture_output_path(kind, device_id, model.as_deref(), timestamp, extension)
+}
 
+/// 通过 `snapshot_display` 截图到设备临时文件，拉取到本地临时目录后读取 PNG 数据
+async fn capture_screenshot(device_id: &Option<String>) -> MdtResult<Vec<u8>> {
     let timestamp = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
-        .unwrap()
-        .as_secs();
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
     let remote_path = format!("/data/local/tmp/screenshot_{}.png", timestamp);
+    let local_path = std::env::temp_dir()
+        .join(format!("mdt_screenshot_{}_{}.png", batch::file_safe_name(&tools::device_key(device_id)), timestamp))
+        .to_string_lossy()
+        .to_string();
 
-    // 先截图到设备
-    let mut cmd = tools::command_for("hdc");
-    if let Some(device) = device_id.clone() {
-        cmd.args(&["-t", &device]);
-    }
-    cmd.args(&["shell", "snapshot_display", "-f", &remote_path]);
-    
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 hdc shell snapshot_display 失败: {}", e))?;
-
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
-    }
+    // 先截图到设备，再拉取到本地
+    hdc_run(device_id, &["shell", "snapshot_display", "-f", &remote_path]).await?;
+    let received = hdc_run(device_id, &["file", "recv", &remote_path, &local_path]).await;
+
+    // 清理设备上的临时文件
+    let _ = hdc_shell(device_id, &["rm", "-f", &remote_path]).await;
+    received?;
+
+    let png = std::fs::read(&local_path).map_err(|e| MdtError::io("读取截图", e));
+    let _ = std::fs::remove_file(&local_path);
+    png
+}
+
+/// 截图并按选项转换格式 / 缩放后保存，返回本地路径
+#[tauri::command]
+pub async fn hdc_screenshot(
+    device_id: Option<String>,
+    output_path: Option<String>,
+    options: Option<ScreenshotOptions>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let options = options.unwrap_or_default();
+    let extension = options.format.extension();
+    let png = capture_screenshot(&device_id).await?;
+    let encoded = screenshot::encode(png, options).await?;
 
     // 确定本地输出路径
     let final_path = if let Some(path) = output_path {
         path
     } else {
-        format!("screenshot_{}.png", timestamp)
+        let timestamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        default_capture_path(&device_id, config::OutputKind::Screenshot, timestamp, extension).await?
     };
 
-    // 拉取文件到本地
-    let mut pull_cmd = tools::command_for("hdc");
-    if let Some(device) = device_id.clone() {
-        pull_cmd.args(&["-t", &device]);
-    }
-    pull_cmd.args(&["file", "recv", &remote_path, &final_path]);
-
-    let pull_output = pull_cmd
-        .output()
-        .map_err(|e| format!("拉取截图文件失败: {}", e))?;
+    std::fs::write(&final_path, &encoded.bytes)
+        .map_err(|e| MdtError::io("写入截图文件", e))?;
+    history::attach_output("hdc", &device_id, &final_path);
 
-    if !pull_output.status.success() {
-        return Err(String::from_utf8_lossy(&pull_output.stderr).to_string());
-    }
+    Ok(final_path)
+}
 
-    // 清理设备上的临时文件
-    let mut rm_cmd = tools::command_for("hdc");
-    if let Some(device) = device_id {
-        rm_cmd.args(&["-t", &device]);
-    }
-    rm_cmd.args(&["shell", "rm", "-f", &remote_path]);
-    let _ = rm_cmd.output();
+/// 截图但不写入输出目录，返回 base64 编码的图片供界面即时预览
+#[tauri::command]
+pub async fn hdc_screenshot_preview(
+    device_id: Option<String>,
+    options: Option<ScreenshotOptions>,
+) -> MdtResult<ScreenshotPreview> {
+    let png = capture_screenshot(&device_id).await?;
+    screenshot::build_preview(png, options.unwrap_or_default()).await
+}
 
+/// 保存预览得到的截图数据，未指定路径时按截图目录与文件名模板生成（扩展名取自 `format`）
+#[tauri::command]
+pub async fn hdc_save_screenshot(
+    device_id: Option<String>,
+    data: String,
+    output_path: Option<String>,
+    format: Option<ScreenshotFormat>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let final_path = match output_path {
+        Some(path) => path,
+        None => {
+            let timestamp = SystemTime::now()
+                .duration_since(SystemTime::UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(0);
+            let extension = format.unwrap_or_default().extension();
+            default_capture_path(&device_id, config::OutputKind::Screenshot, timestamp, extension).await?
+        }
+    };
+    screenshot::write_preview(&data, &final_path)?;
     Ok(final_path)
 }
 
+/// 截图并直接放入本机剪贴板，便于粘贴到 IM / 缺陷单
 #[tauri::command]
-pub async fn hdc_start_screenrecord(device_id: Option<String>) -> Result<String, String> {
-    use std::process::{Command, Stdio};
+pub async fn hdc_copy_screenshot_to_clipboard(device_id: Option<String>) -> MdtResult<()> {
+    let _history = history::track();
+    let png = capture_screenshot(&device_id).await?;
+    screenshot::copy_to_host_clipboard(png).await
+}
 
-    let device_key = device_key(&device_id);
+/// 在多台设备（或一个分组）上同时截图，文件保存为 `<output_dir>/<设备>_<时间戳>.<扩展名>`
+#[tauri::command]
+pub async fn hdc_screenshot_batch(
+    app: AppHandle,
+    device_ids: Option<Vec<String>>,
+    group: Option<String>,
+    output_dir: String,
+    options: Option<ScreenshotOptions>,
+    parallel: Option<usize>,
+) -> MdtResult<Vec<BatchDeviceResult>> {
+    let _history = history::track();
+    let device_ids = groups::resolve_devices(device_ids, group)?;
+    std::fs::create_dir_all(&output_dir).map_err(|e| MdtError::io("创建输出目录", e))?;
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    let options = options.unwrap_or_default();
+
+    Ok(batch::run_batch(&app, "screenshot", device_ids, parallel, move |device_id| {
+        let path = Path::new(&output_dir)
+            .join(format!(
+                "{}_{}.{}",
+                batch::file_safe_name(&device_id),
+                timestamp,
+                options.format.extension()
+            ))
+            .to_string_lossy()
+            .to_string();
+        let options = options.clone();
+        async move { hdc_screenshot(Some(device_id), Some(path), Some(options)).await }
+    })
+    .await)
+}
+
+#[tauri::command]
+pub async fn hdc_start_screenrecord(device_id: Option<String>) -> MdtResult<String> {
+    use std::process::Stdio;
+    let _history = history::track();
+
+    let device_key = tools::device_key(&device_id);
     let mut store = screen_recordings()
         .lock()
-        .map_err(|_| "录屏状态锁定失败".to_string())?;
+        .map_err(|_| MdtError::lock("screenrecord"))?;
 
     if store.contains_key(&device_key) {
-        return Err("当前设备正在录屏中".to_string());
+        return Err(MdtError::session_exists("screenrecord", &device_key));
     }
 
     let timestamp = SystemTime::now()
@@ -320,17 +825,15 @@ pub async fn hdc_start_screenrecord(device_id: Option<String>) -> Result<String,
         .as_secs();
     let remote_path = format!("/data/local/tmp/screenrecord_{}.mp4", timestamp);
 
-    let mut cmd = tools::command_for("hdc");
-    if let Some(device) = device_id.clone() {
-        cmd.args(&["-t", &device]);
-    }
-    cmd.args(&["shell", "screenrecord", &remote_path])
+    let mut cmd = hdc_command(&device_id);
+    cmd.args(["shell", "screenrecord", &remote_path])
         .stdout(Stdio::null())
         .stderr(Stdio::null());
 
+    history::record_launch(&cmd);
     let child = cmd
         .spawn()
-        .map_err(|e| format!("启动录屏失败: {}", e))?;
+        .map_err(|e| MdtError::spawn("hdc", e))?;
 
     store.insert(
         device_key,
@@ -348,17 +851,14 @@ pub async fn hdc_start_screenrecord(device_id: Option<String>) -> Result<String,
 pub async fn hdc_stop_screenrecord(
     device_id: Option<String>,
     output_path: Option<String>,
-) -> Result<String, String> {
-    use std::process::Command;
-
-    let device_key = device_key(&device_id);
-    let mut store = screen_recordings()
+) -> MdtResult<String> {
+    let _history = history::track();
+    let device_key = tools::device_key(&device_id);
+    let session = screen_recordings()
         .lock()
-        .map_err(|_| "录屏状态锁定失败".to_string())?;
-
-    let session = store
+        .map_err(|_| MdtError::lock("screenrecord"))?
         .remove(&device_key)
-        .ok_or_else(|| "当前设备没有正在进行的录屏".to_string())?;
+        .ok_or_else(|| MdtError::session_not_found("screenrecord", &device_key))?;
 
     let mut child = session.child;
     let _ = child.kill();
@@ -367,102 +867,320 @@ pub async fn hdc_stop_screenrecord(
     let final_path = if let Some(path) = output_path {
         path
     } else {
-        format!("screenrecord_{}.mp4", session.start_time)
+        default_capture_path(&device_id, config::OutputKind::Recording, session.start_time, "mp4").await?
     };
 
     // 拉取录屏文件到本地
-    let mut pull_cmd = tools::command_for("hdc");
-    if let Some(device) = device_id.clone() {
-        pull_cmd.args(&["-t", &device]);
-    }
-    pull_cmd.args(&["file", "recv", &session.remote_path, &final_path]);
-
-    let output = pull_cmd
-        .output()
-        .map_err(|e| format!("拉取录屏文件失败: {}", e))?;
-
-    if !output.status.success() {
-        return Err(String::from_utf8_lossy(&output.stderr).to_string());
-    }
+    hdc_run(&device_id, &["file", "recv", &session.remote_path, &final_path])
+    .await?;
 
     // 清理设备上的临时文件
-    let mut rm_cmd = tools::command_for("hdc");
-    if let Some(device) = device_id {
-        rm_cmd.args(&["-t", &device]);
-    }
-    rm_cmd.args(&["shell", "rm", "-f", &session.remote_path]);
-    let _ = rm_cmd.output();
+    let _ = hdc_shell(&device_id, &["rm", "-f", &session.remote_path]).await;
 
     Ok(final_path)
 }
 
 #[tauri::command]
 pub async fn hdc_push_file(
+    app: AppHandle,
     device_id: Option<String>,
     local_path: String,
     remote_path: String,
-) -> Result<String, String> {
-    use std::process::Command;
-
-    let mut cmd = tools::command_for("hdc");
-    if let Some(device) = device_id {
-        cmd.args(&["-t", &device]);
-    }
-    cmd.args(&["file", "push", &local_path, &remote_path]);
-
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 hdc file push 失败: {}", e))?;
+    transfer_id: Option<String>,
+    verify: Option<bool>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let total_bytes = std::fs::metadata(&local_path).map(|m| m.len()).ok();
+    let target = hdc_push_target(&device_id, &local_path, &remote_path).await;
+    let probe = hdc_remote_size_probe(device_id.clone(), target.clone());
+
+    let mut cmd = hdc_command(&device_id);
+    cmd.args(["file", "push", &local_path, &remote_path]);
+
+    let request = TransferRequest {
+        transfer_id: transfer_id.unwrap_or_else(transfer::new_transfer_id),
+        device_id: device_id.clone(),
+        tool: "hdc",
+        direction: "push",
+        local_path: local_path.clone(),
+        remote_path,
+        total_bytes,
+    };
+    let output = transfer::run_transfer(&app, request, cmd, probe).await?;
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
-    } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+    if verify.unwrap_or(false) {
+        hdc_verify_checksum(&device_id, PathBuf::from(&local_path), &target).await?;
     }
+    Ok(output)
 }
 
 #[tauri::command]
 pub async fn hdc_pull_file(
+    app: AppHandle,
     device_id: Option<String>,
     remote_path: String,
     local_path: String,
-) -> Result<String, String> {
-    use std::process::Command;
+    transfer_id: Option<String>,
+    verify: Option<bool>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let total_bytes = hdc_remote_size(&device_id, &remote_path).await;
+
+    // 本地路径为目录时，实际写入的是同名文件
+    let target = if Path::new(&local_path).is_dir() {
+        let file_name = remote_path.rsplit('/').next().unwrap_or_default();
+        Path::new(&local_path).join(file_name)
+    } else {
+        PathBuf::from(&local_path)
+    };
 
-    let mut cmd = tools::command_for("hdc");
-    if let Some(device) = device_id {
-        cmd.args(&["-t", &device]);
-    }
-    cmd.args(&["file", "recv", &remote_path, &local_path]);
+    let mut cmd = hdc_command(&device_id);
+    cmd.args(["file", "recv", &remote_path, &local_path]);
+
+    let request = TransferRequest {
+        transfer_id: transfer_id.unwrap_or_else(transfer::new_transfer_id),
+        device_id: device_id.clone(),
+        tool: "hdc",
+        direction: "pull",
+        local_path,
+        remote_path: remote_path.clone(),
+        total_bytes,
+    };
+    let output = transfer::run_transfer(&app, request, cmd, SizeProbe::Local(target.clone())).await?;
 
-    let output = cmd
-        .output()
-        .map_err(|e| format!("执行 hdc file recv 失败: {}", e))?;
+    if verify.unwrap_or(false) {
+        hdc_verify_checksum(&device_id, target, &remote_path).await?;
+    }
+    Ok(output)
+}
 
-    if output.status.success() {
-        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+#[tauri::command]
+pub async fn hdc_push_dir(
+    app: AppHandle,
+    device_id: Option<String>,
+    local_dir: String,
+    remote_dir: String,
+    parallel: Option<usize>,
+    skip_unchanged: Option<bool>,
+    transfer_id: Option<String>,
+) -> MdtResult<DirectoryTransferResult> {
+    let _history = history::track();
+    let local_files = transfer::walk_local_dir(Path::new(&local_dir))?;
+    let remote_root = remote_dir.trim_end_matches('/').to_string();
+    // 目标目录可能尚不存在，列举失败时视为空目录
+    let existing = if skip_unchanged.unwrap_or(true) {
+        hdc_remote_listing(&device_id, &remote_root)
+            .await
+            .unwrap_or_default()
     } else {
-        Err(String::from_utf8_lossy(&output.stderr).to_string())
+        HashMap::new()
+    };
+
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+    for file in local_files {
+        if transfer::is_unchanged(file.size, file.modified, existing.get(&file.relative_path)) {
+            skipped.push(file.relative_path);
+            continue;
+        }
+        entries.push(DirectoryEntry {
+            remote_path: format!("{}/{}", remote_root, file.relative_path),
+            local_path: file.path,
+            relative_path: file.relative_path,
+            size: file.size,
+        });
+    }
+
+    // 预先创建设备端目录结构
+    let mut dirs: Vec<String> = entries
+        .iter()
+        .filter_map(|entry| entry.remote_path.rsplit_once('/').map(|(dir, _)| dir.to_string()))
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+    for batch in dirs.chunks(50) {
+        let mut args = vec!["mkdir".to_string(), "-p".to_string()];
+        args.extend(batch.iter().map(|dir| tools::shell_quote(dir)));
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        hdc_shell(&device_id, &args).await?;
+    }
+
+    let build_device = device_id.clone();
+    let build: Arc<EntryCommandBuilder> = Arc::new(move |entry: &DirectoryEntry| {
+        let mut cmd = hdc_command(&build_device);
+        cmd.args(["file", "push"]);
+        cmd.arg(&entry.local_path).arg(&entry.remote_path);
+        let probe = hdc_remote_size_probe(build_device.clone(), entry.remote_path.clone());
+        (cmd, probe)
+    });
+
+    let request = DirectoryTransferRequest {
+        transfer_id: transfer_id.unwrap_or_else(transfer::new_transfer_id),
+        device_id,
+        direction: "push",
+        local_dir,
+        remote_dir,
+        skipped,
+        parallel: parallel.unwrap_or(1).clamp(1, 8),
+    };
+    transfer::run_directory_transfer(&app, request, "hdc", entries, build).await
+}
+
+#[tauri::command]
+pub async fn hdc_pull_dir(
+    app: AppHandle,
+    device_id: Option<String>,
+    remote_dir: String,
+    local_dir: String,
+    parallel: Option<usize>,
+    skip_unchanged: Option<bool>,
+    transfer_id: Option<String>,
+) -> MdtResult<DirectoryTransferResult> {
+    let _history = history::track();
+    let remote_root = remote_dir.trim_end_matches('/').to_string();
+    let remote_files = hdc_remote_listing(&device_id, &remote_root).await?;
+    let skip_unchanged = skip_unchanged.unwrap_or(true);
+
+    let mut names: Vec<&String> = remote_files.keys().collect();
+    names.sort();
+
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+    for relative_path in names {
+        let remote = &remote_files[relative_path];
+        let local_path = Path::new(&local_dir).join(relative_path);
+        if skip_unchanged {
+            let local = std::fs::metadata(&local_path).ok().map(|metadata| RemoteFile {
+                size: metadata.len(),
+                modified: transfer::modified_secs(&metadata),
+            });
+            if transfer::is_unchanged(remote.size, remote.modified, local.as_ref()) {
+                skipped.push(relative_path.clone());
+                continue;
+            }
+        }
+        if let Some(parent) = local_path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| MdtError::io("创建本地目录", e))?;
+        }
+        entries.push(DirectoryEntry {
+            relative_path: relative_path.clone(),
+            remote_path: format!("{}/{}", remote_root, relative_path),
+            local_path,
+            size: remote.size,
+        });
+    }
+
+    let build_device = device_id.clone();
+    let build: Arc<EntryCommandBuilder> = Arc::new(move |entry: &DirectoryEntry| {
+        let mut cmd = hdc_command(&build_device);
+        cmd.args(["file", "recv"]);
+        cmd.arg(&entry.remote_path).arg(&entry.local_path);
+        (cmd, SizeProbe::Local(entry.local_path.clone()))
+    });
+
+    let request = DirectoryTransferRequest {
+        transfer_id: transfer_id.unwrap_or_else(transfer::new_transfer_id),
+        device_id,
+        direction: "pull",
+        local_dir,
+        remote_dir,
+        skipped,
+        parallel: parallel.unwrap_or(1).clamp(1, 8),
+    };
+    transfer::run_directory_transfer(&app, request, "hdc", entries, build).await
+}
+
+#[tauri::command]
+pub async fn hdc_fs_list(device_id: Option<String>, path: String) -> MdtResult<Vec<RemoteFileEntry>> {
+    let args = remote_fs::list_args(&path);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = hdc_shell(&device_id, &args).await?;
+    Ok(remote_fs::parse_entries(&output))
+}
+
+#[tauri::command]
+pub async fn hdc_fs_stat(device_id: Option<String>, path: String) -> MdtResult<RemoteFileEntry> {
+    let args = remote_fs::stat_args(&path);
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let output = hdc_shell(&device_id, &args).await?;
+    remote_fs::parse_entries(&output)
+        .into_iter()
+        .next()
+        .ok_or_else(|| MdtError::invalid_argument("path", format!("无法读取 {} 的文件信息", path)))
+}
+
+#[tauri::command]
+pub async fn hdc_fs_mkdir(device_id: Option<String>, path: String) -> MdtResult<()> {
+    let _history = history::track();
+    hdc_shell(&device_id, &["mkdir", "-p", &tools::shell_quote(&path)]).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn hdc_fs_remove(
+    device_id: Option<String>,
+    path: String,
+    recursive: Option<bool>,
+) -> MdtResult<()> {
+    let _history = history::track();
+    remote_fs::validate_removable(&path)?;
+    let flags = if recursive.unwrap_or(false) { "-rf" } else { "-f" };
+    hdc_shell(&device_id, &["rm", flags, &tools::shell_quote(&path)]).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn hdc_fs_move(
+    device_id: Option<String>,
+    source: String,
+    destination: String,
+) -> MdtResult<()> {
+    let _history = history::track();
+    hdc_shell(
+        &device_id,
+        &["mv", &tools::shell_quote(&source), &tools::shell_quote(&destination)],
+    )
+    .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn hdc_fs_chmod(
+    device_id: Option<String>,
+    path: String,
+    mode: String,
+    recursive: Option<bool>,
+) -> MdtResult<()> {
+    let _history = history::track();
+    remote_fs::validate_mode(&mode)?;
+    let mut args = vec!["chmod"];
+    if recursive.unwrap_or(false) {
+        args.push("-R");
     }
+    let quoted = tools::shell_quote(&path);
+    args.push(&mode);
+    args.push(&quoted);
+    hdc_shell(&device_id, &args).await?;
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn hdc_push_certificate(
+    app: AppHandle,
     device_id: Option<String>,
     cert_path: String,
     remote_dir: Option<String>,
-) -> Result<String, String> {
-    use std::path::Path;
-
+) -> MdtResult<String> {
+    let _history = history::track();
     let file_name = Path::new(&cert_path)
         .file_name()
-        .ok_or_else(|| "证书文件名无效".to_string())?
+        .ok_or_else(|| MdtError::invalid_argument("certPath", "证书文件名无效"))?
         .to_string_lossy()
         .to_string();
     let base_dir = remote_dir.unwrap_or_else(|| "/data/local/tmp".to_string());
     let remote_path = format!("{}/{}", base_dir.trim_end_matches('/'), file_name);
 
-    hdc_push_file(device_id, cert_path, remote_path.clone()).await?;
+    hdc_push_file(app, device_id, cert_path, remote_path.clone(), None, None).await?;
     Ok(remote_path)
 }
 
@@ -470,20 +1188,16 @@ pub async fn hdc_push_certificate(
 pub async fn hdc_open_cert_installer(
     device_id: Option<String>,
     remote_path: String,
-) -> Result<String, String> {
-    use std::process::Command;
-
+) -> MdtResult<String> {
+    let _history = history::track();
     // HarmonyOS 使用不同的方式打开证书安装器
     // 通过 shell 命令打开文件管理器或证书安装界面
-    let mut cmd = tools::command_for("hdc");
-    if let Some(device) = device_id {
-        cmd.args(&["-t", &device]);
-    }
-    cmd.args(&["shell", "aa", "start", "-a", "ohos.settings.ability", "-b", "com.ohos.settings"]);
+    let mut cmd = hdc_command(&device_id);
+    cmd.args(["shell", "aa", "start", "-a", "ohos.settings.ability", "-b", "com.ohos.settings"]);
 
-    let output = cmd
-        .output()
-        .map_err(|e| format!("打开证书安装向导失败: {}", e))?;
+    let output = tools::output(cmd)
+        .await
+        .map_err(|e| MdtError::spawn("hdc", e))?;
 
     if output.status.success() {
         Ok(String::from_utf8_lossy(&output.stdout).to_string())
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::hdc;
+use crate::tools;
+use std::collections::HashMap;
+
+/// 统计中保留的错误标签数量
+const TOP_ERROR_TAGS: usize = 10;
+
+/// hilog 日志级别，从低到高
+const LEVELS: [&str; 5] = ["D", "I", "W", "E", "F"];
+
+/// 导出日志中的级别统计
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct HilogStats {
+    pub total_lines: usize,
+    pub debug: usize,
+    pub info: usize,
+    pub warn: usize,
+    pub error: usize,
+    pub fatal: usize,
+    /// 错误与致命日志最多的标签，按次数降序
+    pub error_tags: Vec<TagCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HilogExport {
+    pub output_path: String,
+    pub stats: HilogStats,
+}
+
+/// 解析 `08-05 14:21:34.123  1234  1235 E C01510/Tag: message`，返回时间、级别与标签
+fn parse_line(line: &str) -> Option<(String, &str, &str)> {
+    let mut parts = line.split_whitespace();
+    let date = parts.next()?;
+    let time = parts.next()?;
+    let _pid = parts.next()?;
+    let _tid = parts.next()?;
+    let level = parts.next()?;
+    if !LEVELS.contains(&level) {
+        return None;
+    }
+    let domain_tag = parts.next()?.trim_end_matches(':');
+    let tag = domain_tag.split_once('/').map(|(_, tag)| tag).unwrap_or(domain_tag);
+    Some((format!("{} {}", date, time), level, tag))
+}
+
+/// `-L` 只匹配列出的级别，传入最低级别时展开为该级别及以上的所有级别
+fn level_filter(level: &str) -> MdtResult<String> {
+    let level = level.trim().to_uppercase();
+    let index = LEVELS
+        .iter()
+        .position(|candidate| level.starts_with(candidate))
+        .ok_or_else(|| MdtError::invalid_argument("level", format!("不支持的日志级别: {}", level)))?;
+    Ok(LEVELS[index..].join(","))
+}
+
+/// 校验 `since`（`MM-DD HH:MM:SS`，可带毫秒），与日志行的时间按字符串比较
+fn validate_since(since: &str) -> MdtResult<String> {
+    let since = since.trim();
+    let valid = since.len() >= 14
+        && since.chars().enumerate().all(|(i, c)| match i {
+            2 => c == '-',
+            5 => c == ' ',
+            8 | 11 => c == ':',
+            14 => c == '.',
+            _ => c.is_ascii_digit(),
+        });
+    if valid {
+        Ok(since.to_string())
+    } else {
+        Err(MdtError::invalid_argument("since", "时间格式应为 MM-DD HH:MM:SS"))
+    }
+}
+
+/// 导出设备当前缓冲区中的 hilog（`hilog -x`），按级别 / 标签 / 起始时间过滤后写入本地文件，并返回级别统计
+#[tauri::command]
+pub async fn hdc_export_hilog(
+    device_id: Option<String>,
+    level: Option<String>,
+    tag: Option<String>,
+    since: Option<String>,
+    output_path: String,
+) -> MdtResult<HilogExport> {
+    let _history = history::track();
+    let since = since.as_deref().map(validate_since).transpose()?;
+    let mut args = vec!["shell".to_string(), "hilog".to_string(), "-x".to_string()];
+    if let Some(level) = &level {
+        args.push("-L".to_string());
+        args.push(level_filter(level)?);
+    }
+    if let Some(tag) = tag.as_deref().filter(|tag| !tag.trim().is_empty()) {
+        args.push("-T".to_string());
+        args.push(tools::shell_quote(tag.trim()));
+    }
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let raw = hdc::hdc_run(&device_id, &args).await?;
+
+    let mut stats = HilogStats::default();
+    let mut error_tags: HashMap<String, usize> = HashMap::new();
+    let mut content = String::with_capacity(raw.len());
+    let mut skipping = false;
+    for line in raw.lines() {
+        let parsed = parse_line(line);
+        // 无法解析时间的行（如多行日志的续行）跟随上一行的过滤结果
+        if let (Some(since), Some((timestamp, _, _))) = (&since, &parsed) {
+            skipping = timestamp.as_str() < since.as_str();
+        }
+        if skipping {
+            continue;
+        }
+        content.push_str(line);
+        content.push('\n');
+        stats.total_lines += 1;
+        let Some((_, level, tag)) = parsed else {
+            continue;
+        };
+        match level {
+            "D" => stats.debug += 1,
+            "I" => stats.info += 1,
+            "W" => stats.warn += 1,
+            "E" => stats.error += 1,
+            _ => stats.fatal += 1,
+        }
+        if level == "E" || level == "F" {
+            *error_tags.entry(tag.to_string()).or_default() += 1;
+        }
+    }
+
+    let mut error_tags: Vec<TagCount> = error_tags
+        .into_iter()
+        .map(|(tag, count)| TagCount { tag, count })
+        .collect();
+    error_tags.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.tag.cmp(&b.tag)));
+    error_tags.truncate(TOP_ERROR_TAGS);
+    stats.error_tags = error_tags;
+
+    std::fs::write(&output_path, content).map_err(|e| MdtError::io("写入 hilog 文件", e))?;
+    Ok(HilogExport { output_path, stats })
+}
@@ -0,0 +1,272 @@
+use serde::{Deserialize, Serialize};
+use crate::tools;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::hdc;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+
+/// hiperf 采样文件在设备上的目录
+const HIPERF_DIR: &str = "/data/local/tmp";
+/// `SP_daemon -stop` 后的汇总数据文件
+const SMARTPERF_RESULT_PATH: &str = "/data/local/tmp/smartperf/1/t_index_info.csv";
+/// 摘要中保留的热点函数数量
+const TOP_HOTSPOTS: usize = 20;
+
+/// HarmonyOS 性能采集工具
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HarmonyProfiler {
+    /// `hiperf record` CPU 采样
+    Hiperf,
+    /// SmartPerf（`SP_daemon`）采集 CPU / FPS / 内存等指标
+    SmartPerf,
+}
+
+struct ProfilingSession {
+    profiler: HarmonyProfiler,
+    bundle_name: String,
+    /// 设备上的数据文件路径，启动完成前为空
+    remote_path: String,
+    start_millis: u64,
+}
+
+fn profiling_sessions() -> &'static Mutex<HashMap<String, ProfilingSession>> {
+    static STORE: OnceLock<Mutex<HashMap<String, ProfilingSession>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// hiperf 报告中的热点函数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Hotspot {
+    pub percent: f64,
+    /// 报告行中百分比之后的内容（样本数、DSO、符号）
+    pub symbol: String,
+}
+
+/// SmartPerf 单项指标的统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MetricSummary {
+    pub name: String,
+    pub avg: f64,
+    pub min: f64,
+    pub max: f64,
+    pub samples: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfilingResult {
+    pub profiler: HarmonyProfiler,
+    pub bundle_name: String,
+    /// 拉取到本地的原始数据（hiperf 为 perf.data，SmartPerf 为 csv）
+    pub output_path: String,
+    /// hiperf 文本报告的本地路径
+    pub report_path: Option<String>,
+    pub duration_secs: u64,
+    pub hotspots: Vec<Hotspot>,
+    pub metrics: Vec<MetricSummary>,
+    /// SmartPerf 数据中 fps 列的平均值
+    pub avg_fps: Option<f64>,
+}
+
+/// 解析 `hiperf report` 中以百分比开头的行，如 `12.34%  1024  libace.z.so  Render`
+fn parse_hotspots(report: &str) -> Vec<Hotspot> {
+    let mut hotspots: Vec<Hotspot> = report
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let (percent, rest) = line.split_once(char::is_whitespace)?;
+            let percent = percent.strip_suffix('%')?.parse().ok()?;
+            Some(Hotspot {
+                percent,
+                symbol: rest.split_whitespace().collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect();
+    hotspots.sort_by(|a, b| b.percent.total_cmp(&a.percent));
+    hotspots.truncate(TOP_HOTSPOTS);
+    hotspots
+}
+
+/// 统计 SmartPerf csv 中每个数值列的平均 / 最小 / 最大值
+fn summarize_csv(csv: &str) -> Vec<MetricSummary> {
+    let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
+    let Some(header) = lines.next() else {
+        return Vec::new();
+    };
+    let names: Vec<&str> = header.split(',').map(str::trim).collect();
+    let mut columns: Vec<Vec<f64>> = vec![Vec::new(); names.len()];
+    for line in lines {
+        for (index, value) in line.split(',').enumerate().take(names.len()) {
+            if let Ok(value) = value.trim().parse::<f64>() {
+                columns[index].push(value);
+            }
+        }
+    }
+    names
+        .iter()
+        .zip(columns)
+        .filter(|(name, values)| !values.is_empty() && !name.eq_ignore_ascii_case("timestamp"))
+        .map(|(name, values)| MetricSummary {
+            name: name.to_string(),
+            avg: values.iter().sum::<f64>() / values.len() as f64,
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            samples: values.len(),
+        })
+        .collect()
+}
+
+/// 开始对指定应用采集性能数据：hiperf 按进程 CPU 采样，SmartPerf 采集 CPU / GPU / FPS 等指标
+#[tauri::command]
+pub async fn hdc_start_profiling(
+    device_id: Option<String>,
+    bundle_name: String,
+    profiler: HarmonyProfiler,
+) -> MdtResult<()> {
+    let _history = history::track();
+    hdc::validate_bundle_name("bundleName", &bundle_name)?;
+    let device_key = tools::device_key(&device_id);
+    let start_millis = tools::now_millis();
+    // 在启动采集前占住会话，避免同一设备上并发启动两次
+    {
+        let mut store = profiling_sessions().lock().map_err(|_| MdtError::lock("profiling"))?;
+        if store.contains_key(&device_key) {
+            return Err(MdtError::session_exists("profiling", &device_key));
+        }
+        store.insert(
+            device_key.clone(),
+            ProfilingSession {
+                profiler,
+                bundle_name: bundle_name.clone(),
+                remote_path: String::new(),
+                start_millis,
+            },
+        );
+    }
+
+    let started = start_profiler(&device_id, &bundle_name, profiler, start_millis).await;
+    let mut store = profiling_sessions().lock().map_err(|_| MdtError::lock("profiling"))?;
+    match started {
+        Ok(remote_path) => {
+            if let Some(session) = store.get_mut(&device_key) {
+                session.remote_path = remote_path;
+            }
+            Ok(())
+        }
+        Err(err) => {
+            store.remove(&device_key);
+            Err(err)
+        }
+    }
+}
+
+/// 在设备上启动采集，返回停止后数据文件在设备上的路径
+async fn start_profiler(
+    device_id: &Option<String>,
+    bundle_name: &str,
+    profiler: HarmonyProfiler,
+    start_millis: u64,
+) -> MdtResult<String> {
+    let remote_path = match profiler {
+        HarmonyProfiler::Hiperf => {
+            let pids = hdc::hdc_shell(device_id, &["pidof", bundle_name]).await.unwrap_or_default();
+            let pid = pids
+                .split_whitespace()
+                .find(|pid| pid.parse::<u32>().is_ok())
+                .ok_or_else(|| MdtError::invalid_argument("bundleName", format!("{} 未在运行", bundle_name)))?
+                .to_string();
+            let remote_path = format!("{}/mdt_{}.data", HIPERF_DIR, start_millis);
+            // control 模式在后台采样，stop 时写入输出文件
+            hdc::hdc_shell(
+                device_id,
+                &["hiperf", "record", "--control", "prepare", "-p", &pid, "-o", &remote_path],
+            )
+            .await?;
+            hdc::hdc_shell(device_id, &["hiperf", "record", "--control", "start"]).await?;
+            remote_path
+        }
+        HarmonyProfiler::SmartPerf => {
+            hdc::hdc_shell(device_id, &["SP_daemon", "-start", "-PKG", bundle_name, "-c", "-g", "-f", "-r"])
+                .await?;
+            SMARTPERF_RESULT_PATH.to_string()
+        }
+    };
+    Ok(remote_path)
+}
+
+/// 停止采集并把数据拉取到 `output_path`，hiperf 额外生成文本报告（`<output_path>.txt`），返回 CPU / FPS 摘要
+#[tauri::command]
+pub async fn hdc_stop_profiling(device_id: Option<String>, output_path: String) -> MdtResult<ProfilingResult> {
+    let _history = history::track();
+    let device_key = tools::device_key(&device_id);
+    let session = {
+        let mut store = profiling_sessions().lock().map_err(|_| MdtError::lock("profiling"))?;
+        // 启动尚未完成时数据路径为空，此时不能停止
+        if store.get(&device_key).is_some_and(|session| session.remote_path.is_empty()) {
+            return Err(MdtError::invalid_argument("deviceId", "性能采集正在启动，请稍后再停止"));
+        }
+        store
+            .remove(&device_key)
+            .ok_or_else(|| MdtError::session_not_found("profiling", &device_key))?
+    };
+    let duration_secs = tools::now_millis().saturating_sub(session.start_millis) / 1000;
+
+    if let Some(parent) = Path::new(&output_path).parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent).map_err(|e| MdtError::io("创建输出目录", e))?;
+        }
+    }
+
+    let mut result = ProfilingResult {
+        profiler: session.profiler,
+        bundle_name: session.bundle_name,
+        output_path: output_path.clone(),
+        report_path: None,
+        duration_secs,
+        hotspots: Vec::new(),
+        metrics: Vec::new(),
+        avg_fps: None,
+    };
+    match session.profiler {
+        HarmonyProfiler::Hiperf => {
+            hdc::hdc_shell(&device_id, &["hiperf", "record", "--control", "stop"]).await?;
+            let remote_report = format!("{}.txt", session.remote_path);
+            let report = hdc::hdc_shell(
+                &device_id,
+                &["hiperf", "report", "-i", &session.remote_path, "-o", &remote_report],
+            )
+            .await;
+            let pulled = hdc::hdc_run(&device_id, &["file", "recv", &session.remote_path, &output_path]).await;
+            if report.is_ok() {
+                let report_path = format!("{}.txt", output_path);
+                match hdc::hdc_run(&device_id, &["file", "recv", &remote_report, &report_path]).await {
+                    Ok(_) => {
+                        let text = std::fs::read_to_string(&report_path).unwrap_or_default();
+                        result.hotspots = parse_hotspots(&text);
+                        result.report_path = Some(report_path);
+                    }
+                    Err(err) => println!("[hiperf] pull report failed: {}", err),
+                }
+            }
+            let _ = hdc::hdc_shell(&device_id, &["rm", "-f", &session.remote_path, &remote_report]).await;
+            pulled?;
+        }
+        HarmonyProfiler::SmartPerf => {
+            hdc::hdc_shell(&device_id, &["SP_daemon", "-stop"]).await?;
+            hdc::hdc_run(&device_id, &["file", "recv", &session.remote_path, &output_path]).await?;
+            let csv = std::fs::read_to_string(&output_path).map_err(|e| MdtError::io("读取 SmartPerf 数据", e))?;
+            result.metrics = summarize_csv(&csv);
+            result.avg_fps = result
+                .metrics
+                .iter()
+                .find(|metric| metric.name.eq_ignore_ascii_case("fps"))
+                .map(|metric| metric.avg);
+        }
+    }
+    Ok(result)
+}
@@ -0,0 +1,307 @@
+use crate::error::{MdtError, MdtResult};
+use crate::executor::CommandOutput;
+use crate::store;
+use crate::tools;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+const HISTORY_FILE: &str = "history";
+/// 最多保留的记录条数，超出后丢弃最早的记录
+const MAX_ENTRIES: usize = 2000;
+/// 合并短时间内的多次写入，避免每条命令都重写整个历史文件
+const FLUSH_DELAY: Duration = Duration::from_secs(2);
+const DEFAULT_QUERY_LIMIT: usize = 200;
+
+/// 一次外部工具调用的记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryEntry {
+    pub id: u64,
+    /// 毫秒时间戳
+    pub timestamp: u64,
+    pub tool: String,
+    /// 实际执行的程序路径，重新执行时使用
+    pub program: String,
+    /// 已隐藏密码参数
+    pub args: Vec<String>,
+    pub device_id: Option<String>,
+    pub duration_ms: u64,
+    pub success: bool,
+    pub exit_code: Option<i32>,
+    pub error: Option<String>,
+    /// 命令生成的本地文件（截图、拉取的文件等）
+    pub output_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct HistoryFilter {
+    pub device_id: Option<String>,
+    pub tool: Option<String>,
+    /// 在命令参数中模糊匹配
+    pub text: Option<String>,
+    pub success: Option<bool>,
+    /// 只返回该毫秒时间戳之后的记录
+    pub since: Option<u64>,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct HistoryStore {
+    next_id: u64,
+    entries: Vec<HistoryEntry>,
+}
+
+fn history() -> &'static Mutex<HistoryStore> {
+    static STORE: OnceLock<Mutex<HistoryStore>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(store::load(HISTORY_FILE)))
+}
+
+/// 正在执行用户操作的 tokio 任务（值为嵌套层数），只有这些任务中执行的命令会被记录，
+/// 设备列表轮询、设备信息预取、性能监控等后台查询不会进入历史
+fn tracked_tasks() -> &'static Mutex<HashMap<tokio::task::Id, usize>> {
+    static STORE: OnceLock<Mutex<HashMap<tokio::task::Id, usize>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 由 `track` 返回，离开作用域时结束记录
+pub struct TrackGuard(Option<tokio::task::Id>);
+
+/// 在用户操作的 Tauri 命令入口调用，之后当前任务中执行的外部命令都会写入历史
+pub fn track() -> TrackGuard {
+    let id = tokio::task::try_id();
+    if let (Some(id), Ok(mut tasks)) = (id, tracked_tasks().lock()) {
+        *tasks.entry(id).or_default() += 1;
+    }
+    TrackGuard(id)
+}
+
+impl Drop for TrackGuard {
+    fn drop(&mut self) {
+        let (Some(id), Ok(mut tasks)) = (self.0, tracked_tasks().lock()) else {
+            return;
+        };
+        if let Some(depth) = tasks.get_mut(&id) {
+            *depth -= 1;
+            if *depth == 0 {
+                tasks.remove(&id);
+            }
+        }
+    }
+}
+
+fn is_tracked() -> bool {
+    match (tokio::task::try_id(), tracked_tasks().lock()) {
+        (Some(id), Ok(tasks)) => tasks.contains_key(&id),
+        _ => false,
+    }
+}
+
+/// 延迟写盘：同一时间只保留一个待执行的写入任务
+fn schedule_flush() {
+    static FLUSH_PENDING: AtomicBool = AtomicBool::new(false);
+    if FLUSH_PENDING.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    tauri::async_runtime::spawn(async {
+        tokio::time::sleep(FLUSH_DELAY).await;
+        FLUSH_PENDING.store(false, Ordering::SeqCst);
+        let snapshot = match history().lock() {
+            Ok(store) => serde_json::to_value(&*store).ok(),
+            Err(_) => None,
+        };
+        if let Some(snapshot) = snapshot {
+            if let Err(err) = store::save(HISTORY_FILE, &snapshot) {
+                println!("[history] save failed: {}", err);
+            }
+        }
+    });
+}
+
+/// 拉取类命令（`adb pull`、`hdc file recv`）的最后一个参数即本地输出路径
+fn infer_output_path(args: &[String]) -> Option<String> {
+    let pulls = args.iter().any(|arg| arg == "pull" || arg == "recv");
+    if pulls {
+        args.last().cloned()
+    } else {
+        None
+    }
+}
+
+/// 待记录的命令：工具名、设备、程序路径与（已隐藏敏感内容的）参数
+pub struct Invocation {
+    tool: String,
+    device_id: Option<String>,
+    program: String,
+    args: Vec<String>,
+}
+
+/// 在命令被执行（消耗）前取出程序路径与参数；设备取自 adb / fastboot 的 `-s` 或 hdc 的 `-t`
+pub fn capture(cmd: &Command) -> Invocation {
+    let raw: Vec<String> = cmd.get_args().map(|arg| arg.to_string_lossy().to_string()).collect();
+    let device_id = match raw.first().map(String::as_str) {
+        Some("-s") | Some("-t") => raw.get(1).cloned(),
+        _ => None,
+    };
+    let program = cmd.get_program().to_string_lossy().to_string();
+    let tool = Path::new(&program)
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| program.clone());
+    Invocation {
+        tool,
+        device_id,
+        program,
+        args: tools::redact_args(&raw),
+    }
+}
+
+/// 记录一次外部工具调用，由 `tools::output` 统一调用；不在用户操作（`track`）中时忽略
+pub fn record(invocation: Invocation, duration: Duration, result: Result<&Output, String>) {
+    if !is_tracked() {
+        return;
+    }
+    let Invocation {
+        tool,
+        device_id,
+        program,
+        args,
+    } = invocation;
+    let (success, exit_code, error) = match result {
+        Ok(output) => (
+            output.status.success(),
+            output.status.code(),
+            (!output.status.success())
+                .then(|| String::from_utf8_lossy(&output.stderr).trim().to_string())
+                .filter(|stderr| !stderr.is_empty()),
+        ),
+        Err(err) => (false, None, Some(err)),
+    };
+    let Ok(mut store) = history().lock() else {
+        return;
+    };
+    store.next_id += 1;
+    let entry = HistoryEntry {
+        id: store.next_id,
+        timestamp: tools::now_millis(),
+        tool,
+        program,
+        output_path: infer_output_path(&args),
+        args,
+        device_id,
+        duration_ms: duration.as_millis() as u64,
+        success,
+        exit_code,
+        error,
+    };
+    store.entries.push(entry);
+    if store.entries.len() > MAX_ENTRIES {
+        let overflow = store.entries.len() - MAX_ENTRIES;
+        store.entries.drain(..overflow);
+    }
+    drop(store);
+    schedule_flush();
+}
+
+/// 记录即将启动的长时间运行进程（录屏、投屏、日志流等），这类进程的耗时不计入历史
+pub fn record_launch(cmd: &Command) {
+    let output = Output {
+        status: Default::default(),
+        stdout: Vec::new(),
+        stderr: Vec::new(),
+    };
+    record(capture(cmd), Duration::ZERO, Ok(&output));
+}
+
+/// 为该设备上最近一次由 `tool` 执行的命令补充输出文件路径（如 exec-out 截图写入的本地文件）
+pub fn attach_output(tool: &str, device_id: &Option<String>, path: &str) {
+    if let Ok(mut store) = history().lock() {
+        if let Some(entry) = store
+            .entries
+            .iter_mut()
+            .rev()
+            .find(|entry| entry.tool == tool && entry.device_id == *device_id)
+        {
+            entry.output_path = Some(path.to_string());
+        }
+    }
+    schedule_flush();
+}
+
+fn matches(entry: &HistoryEntry, filter: &HistoryFilter) -> bool {
+    if filter.device_id.as_ref().is_some_and(|id| entry.device_id.as_ref() != Some(id)) {
+        return false;
+    }
+    if filter.tool.as_ref().is_some_and(|tool| &entry.tool != tool) {
+        return false;
+    }
+    if filter.success.is_some_and(|success| entry.success != success) {
+        return false;
+    }
+    if filter.since.is_some_and(|since| entry.timestamp < since) {
+        return false;
+    }
+    match &filter.text {
+        Some(text) if !text.is_empty() => entry.args.join(" ").contains(text.as_str()),
+        _ => true,
+    }
+}
+
+/// 查询操作历史，按时间倒序返回
+#[tauri::command]
+pub async fn history_query(filter: Option<HistoryFilter>, limit: Option<usize>) -> MdtResult<Vec<HistoryEntry>> {
+    let filter = filter.unwrap_or_default();
+    let store = history().lock().map_err(|_| MdtError::lock("history"))?;
+    Ok(store
+        .entries
+        .iter()
+        .rev()
+        .filter(|entry| matches(entry, &filter))
+        .take(limit.unwrap_or(DEFAULT_QUERY_LIMIT))
+        .cloned()
+        .collect())
+}
+
+/// 清空历史，指定 `device_id` 时只清除该设备的记录，返回删除的条数
+#[tauri::command]
+pub async fn history_clear(device_id: Option<String>) -> MdtResult<usize> {
+    let removed = {
+        let mut store = history().lock().map_err(|_| MdtError::lock("history"))?;
+        let before = store.entries.len();
+        match &device_id {
+            Some(id) => store.entries.retain(|entry| entry.device_id.as_ref() != Some(id)),
+            None => store.entries.clear(),
+        }
+        before - store.entries.len()
+    };
+    schedule_flush();
+    Ok(removed)
+}
+
+/// 按原参数重新执行一条历史命令
+#[tauri::command]
+pub async fn history_rerun(id: u64) -> MdtResult<CommandOutput> {
+    let entry = {
+        let store = history().lock().map_err(|_| MdtError::lock("history"))?;
+        store.entries.iter().find(|entry| entry.id == id).cloned()
+    }
+    .ok_or_else(|| MdtError::invalid_argument("id", format!("历史记录不存在: {}", id)))?;
+    let _history = track();
+    if entry.args.iter().any(|arg| arg.ends_with(tools::REDACTED)) {
+        return Err(MdtError::invalid_argument("id", "该命令包含已隐藏的敏感参数，无法重新执行"));
+    }
+
+    let mut cmd = Command::new(&entry.program);
+    cmd.args(&entry.args);
+    let output = tools::output(cmd).await.map_err(|e| MdtError::spawn(&entry.tool, e))?;
+    Ok(CommandOutput {
+        success: output.status.success(),
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        exit_code: output.status.code(),
+    })
+}
@@ -0,0 +1,175 @@
+use base64::Engine;
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::hdc;
+use crate::tools;
+
+const DEFAULT_SWIPE_DURATION_MS: u32 = 300;
+const DEFAULT_LONG_PRESS_MS: u32 = 800;
+/// ADBKeyBoard 输入法，用于输入 `input text` 不支持的中文等字符
+const ADB_KEYBOARD_IME: &str = "com.android.adbkeyboard/.AdbIME";
+
+/// 按键名校验：`KEYCODE_HOME` / `HOME` / 数字键码
+fn validate_keycode(keycode: &str) -> MdtResult<()> {
+    if !keycode.is_empty() && keycode.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        Ok(())
+    } else {
+        Err(MdtError::invalid_argument("keycode", format!("无效的按键: {}", keycode)))
+    }
+}
+
+/// 转义 `input text` 的参数：空格写作 `%s`，其余特殊字符交给 shell 引号处理
+fn escape_input_text(text: &str) -> String {
+    tools::shell_quote(&text.replace(' ', "%s"))
+}
+
+#[tauri::command]
+pub async fn adb_input_tap(device_id: Option<String>, x: u32, y: u32) -> MdtResult<()> {
+    let _history = history::track();
+    adb::adb_shell(&device_id, &["input", "tap", &x.to_string(), &y.to_string()]).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn adb_input_swipe(
+    device_id: Option<String>,
+    x1: u32,
+    y1: u32,
+    x2: u32,
+    y2: u32,
+    duration_ms: Option<u32>,
+) -> MdtResult<()> {
+    let _history = history::track();
+    let duration = duration_ms.unwrap_or(DEFAULT_SWIPE_DURATION_MS).to_string();
+    adb::adb_shell(
+        &device_id,
+        &[
+            "input",
+            "swipe",
+            &x1.to_string(),
+            &y1.to_string(),
+            &x2.to_string(),
+            &y2.to_string(),
+            &duration,
+        ],
+    )
+    .await?;
+    Ok(())
+}
+
+/// 长按：起点与终点相同的滑动
+#[tauri::command]
+pub async fn adb_input_long_press(
+    device_id: Option<String>,
+    x: u32,
+    y: u32,
+    duration_ms: Option<u32>,
+) -> MdtResult<()> {
+    let _history = history::track();
+    adb_input_swipe(device_id, x, y, x, y, Some(duration_ms.unwrap_or(DEFAULT_LONG_PRESS_MS))).await
+}
+
+#[tauri::command]
+pub async fn adb_input_keyevent(device_id: Option<String>, keycode: String) -> MdtResult<()> {
+    let _history = history::track();
+    validate_keycode(&keycode)?;
+    adb::adb_shell(&device_id, &["input", "keyevent", &keycode]).await?;
+    Ok(())
+}
+
+/// 输入文本；非 ASCII 字符需要设备已安装并启用 ADBKeyBoard
+#[tauri::command]
+pub async fn adb_input_text(device_id: Option<String>, text: String) -> MdtResult<()> {
+    let _history = history::track();
+    if text.is_ascii() {
+        adb::adb_shell(&device_id, &["input", "text", &escape_input_text(&text)]).await?;
+        return Ok(());
+    }
+
+    let imes = adb::adb_shell(&device_id, &["ime", "list", "-s"]).await?;
+    if !imes.lines().any(|ime| ime.trim() == ADB_KEYBOARD_IME) {
+        return Err(MdtError::invalid_argument(
+            "text",
+            "输入非 ASCII 文本需要在设备上安装并启用 ADBKeyBoard",
+        ));
+    }
+    let encoded = base64::engine::general_purpose::STANDARD.encode(text.as_bytes());
+    adb::adb_shell(
+        &device_id,
+        &["am", "broadcast", "-a", "ADB_INPUT_B64", "--es", "msg", &encoded],
+    )
+    .await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn hdc_input_tap(device_id: Option<String>, x: u32, y: u32) -> MdtResult<()> {
+    let _history = history::track();
+    hdc::hdc_shell(&device_id, &["uinput", "-T", "-c", &x.to_string(), &y.to_string()]).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn hdc_input_swipe(
+    device_id: Option<String>,
+    x1: u32,
+    y1: u32,
+    x2: u32,
+    y2: u32,
+    duration_ms: Option<u32>,
+) -> MdtResult<()> {
+    let _history = history::track();
+    let duration = duration_ms.unwrap_or(DEFAULT_SWIPE_DURATION_MS).to_string();
+    hdc::hdc_shell(
+        &device_id,
+        &[
+            "uinput",
+            "-T",
+            "-m",
+            &x1.to_string(),
+            &y1.to_string(),
+            &x2.to_string(),
+            &y2.to_string(),
+            &duration,
+        ],
+    )
+    .await?;
+    Ok(())
+}
+
+/// 长按：按下后等待指定时长再抬起
+#[tauri::command]
+pub async fn hdc_input_long_press(
+    device_id: Option<String>,
+    x: u32,
+    y: u32,
+    duration_ms: Option<u32>,
+) -> MdtResult<()> {
+    let _history = history::track();
+    let (x, y) = (x.to_string(), y.to_string());
+    hdc::hdc_shell(&device_id, &["uinput", "-T", "-d", &x, &y]).await?;
+    tokio::time::sleep(std::time::Duration::from_millis(
+        duration_ms.unwrap_or(DEFAULT_LONG_PRESS_MS) as u64,
+    ))
+    .await;
+    hdc::hdc_shell(&device_id, &["uinput", "-T", "-u", &x, &y]).await?;
+    Ok(())
+}
+
+/// 按键事件，HarmonyOS 使用数字键码（如 2 为返回键）
+#[tauri::command]
+pub async fn hdc_input_keyevent(device_id: Option<String>, keycode: u32) -> MdtResult<()> {
+    let _history = history::track();
+    let keycode = keycode.to_string();
+    hdc::hdc_shell(&device_id, &["uinput", "-K", "-d", &keycode, "-u", &keycode]).await?;
+    Ok(())
+}
+
+/// 在当前焦点输入框中输入文本（`uitest uiInput text`）
+#[tauri::command]
+pub async fn hdc_input_text(device_id: Option<String>, text: String) -> MdtResult<()> {
+    let _history = history::track();
+    hdc::hdc_shell(&device_id, &["uitest", "uiInput", "text", &tools::shell_quote(&text)]).await?;
+    Ok(())
+}
@@ -0,0 +1,257 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+
+const ACTION_VIEW: &str = "android.intent.action.VIEW";
+
+/// 常用 Intent flag，可用名称（带或不带 `FLAG_` 前缀）代替数值
+const INTENT_FLAGS: &[(&str, u32)] = &[
+    ("GRANT_READ_URI_PERMISSION", 0x0000_0001),
+    ("GRANT_WRITE_URI_PERMISSION", 0x0000_0002),
+    ("INCLUDE_STOPPED_PACKAGES", 0x0000_0020),
+    ("ACTIVITY_CLEAR_TASK", 0x0000_8000),
+    ("ACTIVITY_NO_ANIMATION", 0x0001_0000),
+    ("ACTIVITY_REORDER_TO_FRONT", 0x0002_0000),
+    ("ACTIVITY_EXCLUDE_FROM_RECENTS", 0x0080_0000),
+    ("ACTIVITY_CLEAR_TOP", 0x0400_0000),
+    ("ACTIVITY_MULTIPLE_TASK", 0x0800_0000),
+    ("ACTIVITY_NEW_TASK", 0x1000_0000),
+    ("ACTIVITY_SINGLE_TOP", 0x2000_0000),
+    ("ACTIVITY_NO_HISTORY", 0x4000_0000),
+];
+
+/// Intent extra 的类型与取值，对应 `am start` 的 `--es` / `--ei` / `--esa` 等参数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "camelCase")]
+pub enum ExtraValue {
+    String(String),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Bool(bool),
+    Uri(String),
+    Component(String),
+    Null,
+    StringArray(Vec<String>),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+    FloatArray(Vec<f32>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntentExtra {
+    pub key: String,
+    #[serde(flatten)]
+    pub value: ExtraValue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct IntentSpec {
+    pub action: Option<String>,
+    pub data: Option<String>,
+    /// MIME 类型（`-t`）
+    pub mime_type: Option<String>,
+    /// `包名/类名`
+    pub component: Option<String>,
+    /// 未指定 component 时限定目标应用
+    pub package: Option<String>,
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// flag 名称（如 `FLAG_ACTIVITY_NEW_TASK`）或数值（如 `0x10000000`）
+    #[serde(default)]
+    pub flags: Vec<String>,
+    #[serde(default)]
+    pub extras: Vec<IntentExtra>,
+}
+
+fn parse_intent_flag(flag: &str) -> MdtResult<u32> {
+    let flag = flag.trim();
+    let parsed = match flag.strip_prefix("0x").or_else(|| flag.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => flag.parse().ok(),
+    };
+    parsed
+        .or_else(|| {
+            let name = flag.strip_prefix("FLAG_").unwrap_or(flag);
+            INTENT_FLAGS.iter().find(|(known, _)| *known == name).map(|(_, value)| *value)
+        })
+        .ok_or_else(|| MdtError::invalid_argument("flags", format!("无法识别的 flag: {}", flag)))
+}
+
+/// 数组 extra 以逗号分隔，元素内的逗号需要转义
+fn join_array<T: ToString>(values: &[T]) -> String {
+    values
+        .iter()
+        .map(|value| value.to_string().replace(',', "\\,"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+impl ExtraValue {
+    /// 返回 `am` 参数名及（未加引号的）取值
+    fn to_arg(&self) -> (&'static str, Option<String>) {
+        match self {
+            ExtraValue::String(value) => ("--es", Some(value.clone())),
+            ExtraValue::Int(value) => ("--ei", Some(value.to_string())),
+            ExtraValue::Long(value) => ("--el", Some(value.to_string())),
+            ExtraValue::Float(value) => ("--ef", Some(value.to_string())),
+            ExtraValue::Bool(value) => ("--ez", Some(value.to_string())),
+            ExtraValue::Uri(value) => ("--eu", Some(value.clone())),
+            ExtraValue::Component(value) => ("--ecn", Some(value.clone())),
+            ExtraValue::Null => ("--esn", None),
+            ExtraValue::StringArray(values) => ("--esa", Some(join_array(values))),
+            ExtraValue::IntArray(values) => ("--eia", Some(join_array(values))),
+            ExtraValue::LongArray(values) => ("--ela", Some(join_array(values))),
+            ExtraValue::FloatArray(values) => ("--efa", Some(join_array(values))),
+        }
+    }
+}
+
+impl IntentSpec {
+    /// 转换为 `am start` 的 Intent 参数（已做 shell 引号处理）
+    fn to_args(&self) -> MdtResult<Vec<String>> {
+        let mut args = Vec::new();
+        let mut push = |flag: &str, value: &str| {
+            args.push(flag.to_string());
+            args.push(tools::shell_quote(value));
+        };
+        if let Some(action) = &self.action {
+            push("-a", action);
+        }
+        if let Some(data) = &self.data {
+            push("-d", data);
+        }
+        if let Some(mime_type) = &self.mime_type {
+            push("-t", mime_type);
+        }
+        for category in &self.categories {
+            push("-c", category);
+        }
+        if let Some(component) = &self.component {
+            push("-n", component);
+        }
+        if !self.flags.is_empty() {
+            let mut flags = 0u32;
+            for flag in &self.flags {
+                flags |= parse_intent_flag(flag)?;
+            }
+            push("-f", &format!("0x{:08x}", flags));
+        }
+        for extra in &self.extras {
+            if extra.key.is_empty() {
+                return Err(MdtError::invalid_argument("extras", "extra 的 key 不能为空"));
+            }
+            let (flag, value) = extra.value.to_arg();
+            args.push(flag.to_string());
+            args.push(tools::shell_quote(&extra.key));
+            if let Some(value) = value {
+                args.push(tools::shell_quote(&value));
+            }
+        }
+        // 包名需放在最后，作为 Intent 的目标应用
+        if self.component.is_none() {
+            if let Some(package) = &self.package {
+                args.push(tools::shell_quote(package));
+            }
+        }
+        if args.is_empty() {
+            return Err(MdtError::invalid_argument("intent", "至少需要指定 action、data 或 component"));
+        }
+        Ok(args)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntentLaunchResult {
+    /// `cmd package resolve-activity` 解析出的目标，多个应用可处理时为系统选择器
+    pub resolved_activity: Option<String>,
+    /// `am start -W` 报告的实际启动的 Activity
+    pub launched_activity: Option<String>,
+    pub status: Option<String>,
+    pub total_time: Option<u64>,
+    pub output: String,
+}
+
+/// 解析 `am start -W` 输出；`am start` 失败时退出码仍为 0，需要检查 `Error:` 行
+fn parse_start_output(command: &str, output: String) -> MdtResult<IntentLaunchResult> {
+    let output = adb::expect_no_error(command, output)?;
+    let mut result = IntentLaunchResult {
+        resolved_activity: None,
+        launched_activity: None,
+        status: None,
+        total_time: None,
+        output: output.clone(),
+    };
+    for line in output.lines() {
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match key.trim() {
+            "Activity" => result.launched_activity = Some(value.to_string()),
+            "Status" => result.status = Some(value.to_string()),
+            "TotalTime" => result.total_time = value.parse().ok(),
+            _ => {}
+        }
+    }
+    Ok(result)
+}
+
+/// 解析能处理该 Intent 的 Activity（参数与 `am start` 的 Intent 参数一致）
+async fn resolve_activity(device_id: &Option<String>, intent_args: &[&str]) -> Option<String> {
+    let mut args = vec!["cmd", "package", "resolve-activity", "--brief"];
+    args.extend_from_slice(intent_args);
+    let output = adb::adb_shell(device_id, &args).await.ok()?;
+    output
+        .lines()
+        .last()
+        .map(str::trim)
+        .filter(|line| line.contains('/'))
+        .map(str::to_string)
+}
+
+/// 以 `am start -W` 发送 Intent，并附带解析出的目标 Activity
+async fn start_activity(device_id: &Option<String>, intent_args: &[&str]) -> MdtResult<IntentLaunchResult> {
+    let resolved_activity = resolve_activity(device_id, intent_args).await;
+    let mut args = vec!["am", "start", "-W"];
+    args.extend_from_slice(intent_args);
+    let output = adb::adb_shell(device_id, &args).await?;
+    let mut result = parse_start_output(&format!("adb shell {}", args.join(" ")), output)?;
+    result.resolved_activity = resolved_activity;
+    Ok(result)
+}
+
+/// 通过 `am start -a VIEW -d <url>` 打开深链接（App Links / 自定义 scheme），可指定目标应用
+#[tauri::command]
+pub async fn adb_open_deeplink(
+    device_id: Option<String>,
+    url: String,
+    package: Option<String>,
+) -> MdtResult<IntentLaunchResult> {
+    let _history = history::track();
+    if url.trim().is_empty() || !url.contains(':') {
+        return Err(MdtError::invalid_argument("url", "链接需要包含 scheme，如 https:// 或 myapp://"));
+    }
+    let quoted_url = tools::shell_quote(url.trim());
+    let quoted_package = package.as_deref().map(tools::shell_quote);
+    let mut args = vec!["-a", ACTION_VIEW, "-d", &quoted_url];
+    if let Some(package) = quoted_package.as_deref() {
+        args.push(package);
+    }
+    println!("[intent] opening deeplink {}", url);
+    start_activity(&device_id, &args).await
+}
+
+/// 按 IntentSpec 构造并发送 `am start` Intent，支持 category、flag 及各类型 extra
+#[tauri::command]
+pub async fn adb_start_intent(device_id: Option<String>, intent: IntentSpec) -> MdtResult<IntentLaunchResult> {
+    let _history = history::track();
+    let args = intent.to_args()?;
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    start_activity(&device_id, &args).await
+}
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct LocaleState {
+    /// 如 `zh-CN`，未设置 `persist.sys.locale` 时取出厂默认值
+    pub locale: Option<String>,
+    /// 如 `Asia/Shanghai`
+    pub timezone: Option<String>,
+    /// 是否开启自动时区
+    pub auto_timezone: Option<bool>,
+}
+
+#[tauri::command]
+pub async fn adb_get_locale(device_id: Option<String>) -> MdtResult<LocaleState> {
+    let mut locale = adb::adb_getprop(&device_id, "persist.sys.locale").await;
+    if locale.is_none() {
+        locale = adb::adb_getprop(&device_id, "ro.product.locale").await;
+    }
+    let auto_timezone = adb::adb_settings_value(&device_id, "global", "auto_time_zone")
+        .await
+        .and_then(|value| value.parse::<u8>().ok())
+        .map(|value| value == 1);
+    Ok(LocaleState {
+        locale,
+        timezone: adb::adb_getprop(&device_id, "persist.sys.timezone").await,
+        auto_timezone,
+    })
+}
+
+/// 切换语言：指定 `package` 时通过 `cmd locale set-app-locales` 设置应用语言（Android 13+）；
+/// 否则写入 `persist.sys.locale`（需要 root），`restart` 为 true 时重启框架使其生效
+#[tauri::command]
+pub async fn adb_set_locale(
+    device_id: Option<String>,
+    locale: String,
+    package: Option<String>,
+    restart: Option<bool>,
+) -> MdtResult<LocaleState> {
+    let _history = history::track();
+    if locale.is_empty() || !locale.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_') {
+        return Err(MdtError::invalid_argument("locale", format!("无效的语言标签: {}", locale)));
+    }
+    let locale = locale.replace('_', "-");
+
+    if let Some(package) = package {
+        let output = adb::adb_shell(
+            &device_id,
+            &["cmd", "locale", "set-app-locales", &tools::shell_quote(&package), "--locales", &locale],
+        )
+        .await?;
+        if output.to_lowercase().contains("unknown command") || output.contains("Exception") {
+            return Err(MdtError::CommandFailed {
+                command: format!("cmd locale set-app-locales {}", package),
+                exit_code: None,
+                stderr: output,
+            });
+        }
+        println!("[locale] {} -> {}", package, locale);
+        return adb_get_locale(device_id).await;
+    }
+
+    let script = format!("setprop persist.sys.locale {}", locale);
+    if adb::adb_shell(&device_id, &["setprop", "persist.sys.locale", &locale]).await.is_err() {
+        adb::adb_su(&device_id, &script).await?;
+    }
+    if adb::adb_getprop(&device_id, "persist.sys.locale").await.as_deref() != Some(locale.as_str()) {
+        return Err(MdtError::PermissionDenied {
+            detail: "写入 persist.sys.locale 失败，需要 root 权限".to_string(),
+        });
+    }
+    if restart.unwrap_or(false) {
+        // 重启 zygote 会结束所有应用进程，设备连接保持不变
+        adb::adb_su(&device_id, "setprop ctl.restart zygote").await?;
+    }
+    println!("[locale] system locale -> {}", locale);
+    adb_get_locale(device_id).await
+}
+
+/// 切换时区：关闭自动时区后通过 AlarmManager（`service call alarm`）设置，无需 root
+#[tauri::command]
+pub async fn adb_set_timezone(device_id: Option<String>, timezone: String) -> MdtResult<LocaleState> {
+    let _history = history::track();
+    if timezone.is_empty()
+        || !timezone
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '/' | '_' | '-' | '+'))
+    {
+        return Err(MdtError::invalid_argument("timezone", format!("无效的时区: {}", timezone)));
+    }
+    adb::adb_shell(&device_id, &["settings", "put", "global", "auto_time_zone", "0"]).await?;
+    // IAlarmManager.setTimeZone 的事务号为 3
+    adb::adb_shell(&device_id, &["service", "call", "alarm", "3", "s16", &timezone]).await?;
+    if adb::adb_getprop(&device_id, "persist.sys.timezone").await.as_deref() != Some(timezone.as_str()) {
+        // 部分系统事务号不同，退回 root 写入属性
+        adb::adb_su(&device_id, &format!("setprop persist.sys.timezone {}", timezone)).await?;
+    }
+    println!("[locale] timezone -> {}", timezone);
+    adb_get_locale(device_id).await
+}
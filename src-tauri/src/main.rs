@@ -1,11 +1,52 @@
 // Prevents additional console window on Windows in release, DO NOT REMOVE!!
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod aliases;
+mod apk;
+mod batch;
+mod cert;
+mod clipboard;
+mod config;
+mod connectivity;
+mod convert;
+mod crash;
+mod display;
+mod error;
 mod executor;
+mod fastboot;
+mod groups;
 mod adb;
 mod hdc;
+mod hilog;
+mod hiperf;
+mod history;
+mod input;
+mod intent;
+mod locale;
+mod perf;
+mod permissions;
+mod ports;
+mod process;
+mod props;
+mod remote_fs;
+mod root;
+mod screenshot;
+mod sessions;
+mod settings;
+mod simctl;
+mod store;
+mod storage;
+mod testing;
 mod dependencies;
+mod device_info;
+mod dumpsys;
+mod devopts;
+mod emulator;
 mod tools;
+mod trace;
+mod transfer;
+mod ui;
+mod users;
 
 use tauri::Manager;
 
@@ -13,6 +54,7 @@ fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .setup(|app| {
+            store::init(app.path().app_data_dir()?);
             // 仅在 Debug 模式自动打开 DevTools
             if cfg!(debug_assertions) {
                 if let Some(window) = app.get_webview_window("main") {
@@ -24,32 +66,235 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             executor::execute_command,
             adb::adb_devices,
+            aliases::set_device_alias,
+            aliases::get_device_aliases,
+            groups::list_device_groups,
+            groups::create_device_group,
+            groups::delete_device_group,
+            groups::set_device_group_members,
+            groups::set_device_group_favorite,
+            history::history_query,
+            history::history_clear,
+            history::history_rerun,
+            config::get_settings,
+            config::update_settings,
             adb::adb_device_info,
+            adb::adb_battery_details,
+            adb::adb_battery_set,
+            adb::adb_battery_reset,
+            adb::adb_reboot,
+            adb::adb_sideload,
             adb::adb_install,
+            adb::adb_install_existing,
+            adb::adb_install_multiple,
+            adb::adb_install_batch,
+            adb::adb_install_aab,
+            adb::adb_install_archive,
+            adb::adb_push_obb,
+            adb::adb_export_apk,
+            apk::analyze_apk,
+            apk::apk_signature_info,
+            apk::compare_apk_with_installed,
+            perf::start_perf_monitor,
+            perf::stop_perf_monitor,
+            perf::adb_start_fps_monitor,
+            perf::adb_stop_fps_monitor,
+            perf::adb_app_network_stats,
+            perf::adb_start_network_monitor,
+            perf::adb_stop_network_monitor,
+            perf::adb_meminfo,
+            perf::adb_start_thermal_monitor,
+            perf::hdc_start_thermal_monitor,
+            perf::stop_thermal_monitor,
             adb::adb_uninstall,
+            adb::adb_uninstall_batch,
             adb::adb_list_packages,
+            adb::adb_package_info,
+            adb::adb_clear_app_data,
+            adb::adb_clear_app_cache,
+            adb::adb_force_stop,
+            adb::adb_list_processes,
+            adb::adb_kill_process,
+            adb::adb_launch_app,
+            adb::adb_measure_launch_time,
             adb::adb_screenshot,
+            adb::adb_screenshot_batch,
+            adb::adb_screenshot_preview,
+            adb::adb_save_screenshot,
+            adb::adb_copy_screenshot_to_clipboard,
+            adb::adb_long_screenshot,
+            screenshot::compare_screenshots,
+            adb::adb_start_screenshot_series,
+            adb::adb_stop_screenshot_series,
+            adb::adb_bugreport,
+            adb::adb_pull_anr_traces,
+            crash::adb_pull_tombstones,
+            crash::adb_watch_crashes,
+            crash::adb_stop_crash_watch,
+            trace::adb_start_trace,
+            trace::adb_stop_trace,
+            trace::adb_simpleperf_record,
+            ui::adb_dump_ui_hierarchy,
+            ui::hdc_dump_layout,
+            ui::adb_current_activity,
+            input::adb_input_tap,
+            input::adb_input_swipe,
+            input::adb_input_long_press,
+            input::adb_input_keyevent,
+            input::adb_input_text,
+            input::hdc_input_tap,
+            input::hdc_input_swipe,
+            input::hdc_input_long_press,
+            input::hdc_input_keyevent,
+            input::hdc_input_text,
+            testing::adb_run_monkey,
+            testing::adb_run_instrumentation,
+            intent::adb_open_deeplink,
+            intent::adb_start_intent,
+            permissions::adb_appops_get,
+            permissions::adb_appops_set,
+            permissions::adb_list_permissions,
+            permissions::adb_grant_permission,
+            permissions::adb_revoke_permission,
+            clipboard::adb_get_clipboard,
+            clipboard::adb_set_clipboard,
+            clipboard::hdc_get_clipboard,
+            clipboard::hdc_set_clipboard,
+            display::adb_wm_get,
+            display::adb_list_displays,
+            display::adb_wm_set,
+            display::adb_wm_reset,
+            display::adb_get_night_mode,
+            display::adb_set_night_mode,
+            display::adb_set_font_scale,
+            display::adb_reset_font_scale,
+            display::adb_set_stay_awake,
+            display::adb_set_brightness,
+            display::hdc_set_stay_awake,
+            display::hdc_set_brightness,
+            connectivity::adb_connectivity_status,
+            connectivity::adb_connectivity_set,
+            connectivity::adb_get_http_proxy,
+            connectivity::adb_set_http_proxy,
+            connectivity::adb_clear_http_proxy,
+            connectivity::setup_traffic_capture,
+            connectivity::teardown_traffic_capture,
+            cert::adb_install_system_cert,
+            root::adb_root_status,
+            root::adb_root,
+            root::adb_unroot,
+            root::adb_remount,
+            ports::adb_reverse_list,
+            ports::adb_reverse_add,
+            ports::adb_reverse_remove,
+            ports::adb_forward_list,
+            ports::adb_forward_add,
+            ports::adb_forward_remove,
+            ports::adb_forward_remove_all,
+            fastboot::fastboot_devices,
+            fastboot::fastboot_getvar,
+            fastboot::fastboot_unlock_status,
+            fastboot::fastboot_flash,
+            fastboot::fastboot_reboot,
+            users::adb_list_users,
+            users::adb_create_user,
+            users::adb_remove_user,
+            users::adb_switch_user,
+            emulator::emulator_list_avds,
+            emulator::emulator_start,
+            emulator::emulator_list_running,
+            emulator::emulator_stop,
+            emulator::harmony_emulator_list,
+            emulator::harmony_emulator_start,
+            emulator::harmony_emulator_stop,
+            simctl::simctl_list_devices,
+            simctl::simctl_boot,
+            simctl::simctl_shutdown,
+            simctl::simctl_install,
+            simctl::simctl_screenshot,
+            simctl::simctl_open_url,
+            settings::adb_settings_list,
+            settings::adb_settings_get,
+            settings::adb_settings_put,
+            settings::adb_settings_diff,
+            props::adb_getprop_all,
+            props::adb_setprop,
+            props::hdc_param_get_all,
+            props::hdc_param_set,
+            dumpsys::adb_dumpsys_services,
+            dumpsys::adb_dumpsys,
+            dumpsys::adb_list_wakelocks,
+            dumpsys::adb_list_alarms,
+            storage::adb_storage_info,
+            storage::hdc_storage_info,
+            locale::adb_get_locale,
+            locale::adb_set_locale,
+            locale::adb_set_timezone,
+            devopts::adb_get_animation_scale,
+            devopts::adb_set_animation_scale,
+            devopts::adb_demo_mode,
+            devopts::adb_dev_overlay,
+            devopts::adb_get_lifecycle_options,
+            devopts::adb_set_dont_keep_activities,
+            devopts::adb_set_background_process_limit,
             adb::adb_start_screenrecord,
+            adb::adb_start_scrcpy_record,
+            sessions::list_active_sessions,
+            convert::convert_recording,
             adb::adb_stop_screenrecord,
             adb::adb_start_mirror,
             adb::adb_stop_mirror,
+            adb::adb_mirror_clients,
             adb::adb_push_file,
+            adb::adb_media_scan,
             adb::adb_pull_file,
+            adb::adb_push_dir,
+            adb::adb_pull_dir,
+            adb::adb_fs_list,
+            adb::adb_fs_stat,
+            adb::adb_fs_mkdir,
+            adb::adb_fs_remove,
+            adb::adb_fs_move,
+            adb::adb_fs_chmod,
             adb::adb_push_certificate,
             adb::adb_open_cert_installer,
             hdc::hdc_list_targets,
+            hilog::hdc_export_hilog,
+            hiperf::hdc_start_profiling,
+            hiperf::hdc_stop_profiling,
             hdc::hdc_device_info,
+            hdc::hdc_battery_details,
+            hdc::hdc_reboot,
             hdc::hdc_install,
             hdc::hdc_uninstall,
+            hdc::hdc_uninstall_batch,
+            hdc::hdc_clean_app,
+            hdc::hdc_force_stop,
+            hdc::hdc_launch_app,
+            hdc::hdc_list_processes,
+            hdc::hdc_kill_process,
             hdc::hdc_list_packages,
             hdc::hdc_screenshot,
+            hdc::hdc_screenshot_batch,
+            hdc::hdc_screenshot_preview,
+            hdc::hdc_save_screenshot,
+            hdc::hdc_copy_screenshot_to_clipboard,
             hdc::hdc_start_screenrecord,
             hdc::hdc_stop_screenrecord,
             hdc::hdc_push_file,
             hdc::hdc_pull_file,
+            hdc::hdc_push_dir,
+            hdc::hdc_pull_dir,
+            hdc::hdc_fs_list,
+            hdc::hdc_fs_stat,
+            hdc::hdc_fs_mkdir,
+            hdc::hdc_fs_remove,
+            hdc::hdc_fs_move,
+            hdc::hdc_fs_chmod,
             hdc::hdc_push_certificate,
             hdc::hdc_open_cert_installer,
             dependencies::check_dependencies,
+            transfer::cancel_transfer,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,845 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::hdc;
+use crate::tools;
+use std::collections::HashMap;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex, OnceLock,
+};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+/// CPU / 内存采样事件名
+pub const PERF_SAMPLE_EVENT: &str = "perf-sample";
+
+const DEFAULT_SAMPLE_INTERVAL_MS: u64 = 1000;
+const MIN_SAMPLE_INTERVAL_MS: u64 = 500;
+/// 连续采样失败达到该次数时视为设备已断开，监控自动停止
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+
+/// 监控因采样连续失败而自动停止时推送的事件名
+pub const MONITOR_STOPPED_EVENT: &str = "monitor-stopped";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonitorStopped {
+    /// 如 `perf monitor`、`fps monitor`
+    pub kind: String,
+    pub device_id: Option<String>,
+    pub error: String,
+}
+
+/// 后台监控任务的停止标志，键为 `<类型>:<设备>`
+fn monitors() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    static STORE: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 登记一个监控任务，同一设备上同类任务只能存在一个
+pub fn register_monitor(kind: &str, device_key: &str) -> MdtResult<Arc<AtomicBool>> {
+    let mut store = monitors().lock().map_err(|_| MdtError::lock(kind))?;
+    let key = format!("{}:{}", kind, device_key);
+    if store.contains_key(&key) {
+        return Err(MdtError::session_exists(kind, device_key));
+    }
+    let stop_flag = Arc::new(AtomicBool::new(false));
+    store.insert(key, stop_flag.clone());
+    Ok(stop_flag)
+}
+
+/// 通知监控任务停止
+pub fn stop_monitor(kind: &str, device_key: &str) -> MdtResult<()> {
+    let stop_flag = monitors()
+        .lock()
+        .map_err(|_| MdtError::lock(kind))?
+        .remove(&format!("{}:{}", kind, device_key))
+        .ok_or_else(|| MdtError::session_not_found(kind, device_key))?;
+    stop_flag.store(true, Ordering::SeqCst);
+    Ok(())
+}
+
+/// 监控任务自行退出时移除登记（仅当登记的仍是自己的标志）
+pub fn finish_monitor(kind: &str, device_key: &str, stop_flag: &Arc<AtomicBool>) {
+    if let Ok(mut store) = monitors().lock() {
+        let key = format!("{}:{}", kind, device_key);
+        if store.get(&key).is_some_and(|flag| Arc::ptr_eq(flag, stop_flag)) {
+            store.remove(&key);
+        }
+    }
+}
+
+/// 记录一次采样失败，连续失败达到上限时推送 `monitor-stopped` 事件并返回 true，调用方应退出循环
+fn sampling_failed(app: &AppHandle, kind: &str, device_id: &Option<String>, failures: &mut u32, err: &MdtError) -> bool {
+    *failures += 1;
+    if *failures < MAX_CONSECUTIVE_FAILURES {
+        return false;
+    }
+    println!("[perf] {} stopped for {}: {}", kind, tools::device_key(device_id), err);
+    let payload = MonitorStopped {
+        kind: kind.to_string(),
+        device_id: device_id.clone(),
+        error: err.to_string(),
+    };
+    let _ = app.emit(MONITOR_STOPPED_EVENT, payload);
+    true
+}
+
+/// 采样间隔，未指定时为 1 秒，最小 500 毫秒
+pub fn sample_interval(interval_ms: Option<u64>) -> Duration {
+    Duration::from_millis(
+        interval_ms
+            .unwrap_or(DEFAULT_SAMPLE_INTERVAL_MS)
+            .max(MIN_SAMPLE_INTERVAL_MS),
+    )
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PerfSample {
+    pub device_id: Option<String>,
+    pub package: Option<String>,
+    pub timestamp: u64,
+    /// 整机 CPU 占用（%）
+    pub cpu_total: Option<f64>,
+    /// 应用各进程 CPU 占用之和（%）
+    pub app_cpu: Option<f64>,
+    pub mem_total_kb: Option<u64>,
+    pub mem_available_kb: Option<u64>,
+    /// 应用 PSS 总量（KB）
+    pub app_pss_kb: Option<u64>,
+}
+
+/// 解析 `dumpsys cpuinfo`：返回整机占用与指定应用进程的占用之和
+fn parse_cpuinfo(output: &str, package: Option<&str>) -> (Option<f64>, Option<f64>) {
+    let percent = |line: &str| -> Option<f64> {
+        line.split_once('%')?.0.trim().parse().ok()
+    };
+    let mut total = None;
+    let mut app: Option<f64> = None;
+    for line in output.lines() {
+        let line = line.trim();
+        if line.contains("% TOTAL:") {
+            total = percent(line);
+        } else if let Some(package) = package {
+            // 如 `5.2% 1234/com.demo:remote: 3% user + 2.2% kernel`
+            let process = line.split_whitespace().nth(1).and_then(|p| p.split_once('/'));
+            if let Some((_, name)) = process {
+                let name = name.trim_end_matches(':');
+                if name == package || name.starts_with(&format!("{}:", package)) {
+                    *app.get_or_insert(0.0) += percent(line).unwrap_or(0.0);
+                }
+            }
+        }
+    }
+    (total, app)
+}
+
+/// 解析 `/proc/meminfo` 中的 MemTotal 与 MemAvailable（KB）
+fn parse_proc_meminfo(output: &str) -> (Option<u64>, Option<u64>) {
+    let value = |key: &str| {
+        output
+            .lines()
+            .find_map(|line| line.strip_prefix(key))
+            .and_then(|rest| rest.split_whitespace().next())
+            .and_then(|v| v.parse().ok())
+    };
+    (value("MemTotal:"), value("MemAvailable:"))
+}
+
+async fn collect_sample(device_id: &Option<String>, package: &Option<String>) -> MdtResult<PerfSample> {
+    let cpuinfo = adb::adb_shell(device_id, &["dumpsys", "cpuinfo"]).await?;
+    let (cpu_total, app_cpu) = parse_cpuinfo(&cpuinfo, package.as_deref());
+    let meminfo = adb::adb_shell(device_id, &["cat", "/proc/meminfo"]).await?;
+    let (mem_total_kb, mem_available_kb) = parse_proc_meminfo(&meminfo);
+    let app_pss_kb = match package {
+        Some(package) => adb::adb_shell(device_id, &["dumpsys", "meminfo", &tools::shell_quote(package)])
+            .await
+            .ok()
+            .and_then(|output| parse_meminfo(None, &output).total_pss_kb),
+        None => None,
+    };
+
+    Ok(PerfSample {
+        device_id: device_id.clone(),
+        package: package.clone(),
+        timestamp: tools::now_millis(),
+        cpu_total,
+        app_cpu,
+        mem_total_kb,
+        mem_available_kb,
+        app_pss_kb,
+    })
+}
+
+/// 启动 CPU / 内存监控：按间隔在后台采样并通过 `perf-sample` 事件推送
+#[tauri::command]
+pub async fn start_perf_monitor(
+    app: AppHandle,
+    device_id: Option<String>,
+    package: Option<String>,
+    interval_ms: Option<u64>,
+) -> MdtResult<()> {
+    let device_key = tools::device_key(&device_id);
+    let interval = sample_interval(interval_ms);
+    let stop_flag = register_monitor("perf monitor", &device_key)?;
+
+    tauri::async_runtime::spawn(async move {
+        println!("[perf] monitor started for {}", device_key);
+        let mut failures = 0;
+        while !stop_flag.load(Ordering::SeqCst) {
+            match collect_sample(&device_id, &package).await {
+                Ok(sample) => {
+                    failures = 0;
+                    let _ = app.emit(PERF_SAMPLE_EVENT, sample);
+                }
+                Err(err) => {
+                    if sampling_failed(&app, "perf monitor", &device_id, &mut failures, &err) {
+                        break;
+                    }
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+        finish_monitor("perf monitor", &device_key, &stop_flag);
+        println!("[perf] monitor stopped for {}", device_key);
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn stop_perf_monitor(device_id: Option<String>) -> MdtResult<()> {
+    stop_monitor("perf monitor", &tools::device_key(&device_id))
+}
+
+/// 帧率采样事件名
+pub const FPS_SAMPLE_EVENT: &str = "fps-sample";
+
+/// 超过该耗时（约一个 60Hz 垂直同步周期）的帧计为卡顿
+const JANK_THRESHOLD_MS: f64 = 16.67;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FpsSample {
+    pub device_id: Option<String>,
+    pub package: String,
+    pub timestamp: u64,
+    pub frames: usize,
+    pub fps: f64,
+    pub jank_frames: usize,
+    pub jank_percent: f64,
+    pub avg_frame_ms: Option<f64>,
+    pub p90_frame_ms: Option<f64>,
+    pub p99_frame_ms: Option<f64>,
+}
+
+/// 解析 `dumpsys gfxinfo <pkg> framestats` 的 PROFILEDATA，返回 (IntendedVsync, 帧耗时 ms)
+fn parse_framestats(output: &str) -> Vec<(u64, f64)> {
+    let mut frames = Vec::new();
+    let mut columns: Option<(usize, usize, usize)> = None;
+    for line in output.lines() {
+        let line = line.trim();
+        if line.starts_with("---PROFILEDATA---") {
+            columns = None;
+            continue;
+        }
+        let fields: Vec<&str> = line.split(',').collect();
+        if fields.first() == Some(&"Flags") {
+            let index = |name: &str| fields.iter().position(|f| *f == name);
+            columns = index("Flags")
+                .zip(index("IntendedVsync"))
+                .zip(index("FrameCompleted"))
+                .map(|((flags, intended), completed)| (flags, intended, completed));
+            continue;
+        }
+        let Some((flags, intended, completed)) = columns else {
+            continue;
+        };
+        let value = |i: usize| fields.get(i).and_then(|f| f.parse::<u64>().ok());
+        // Flags 非 0 表示该帧数据无效（如窗口动画首帧）
+        if value(flags) != Some(0) {
+            continue;
+        }
+        if let (Some(start), Some(end)) = (value(intended), value(completed)) {
+            if end > start {
+                frames.push((start, (end - start) as f64 / 1_000_000.0));
+            }
+        }
+    }
+    frames
+}
+
+fn percentile(sorted: &[f64], p: f64) -> Option<f64> {
+    if sorted.is_empty() {
+        return None;
+    }
+    let index = ((sorted.len() as f64 * p).ceil() as usize).clamp(1, sorted.len()) - 1;
+    Some(sorted[index])
+}
+
+fn build_fps_sample(
+    device_id: &Option<String>,
+    package: &str,
+    frame_times: Vec<f64>,
+    elapsed: Duration,
+) -> FpsSample {
+    let mut sorted = frame_times;
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let frames = sorted.len();
+    let jank_frames = sorted.iter().filter(|t| **t > JANK_THRESHOLD_MS).count();
+    let seconds = elapsed.as_secs_f64().max(0.001);
+    FpsSample {
+        device_id: device_id.clone(),
+        package: package.to_string(),
+        timestamp: tools::now_millis(),
+        frames,
+        fps: frames as f64 / seconds,
+        jank_frames,
+        jank_percent: if frames == 0 {
+            0.0
+        } else {
+            jank_frames as f64 * 100.0 / frames as f64
+        },
+        avg_frame_ms: (frames > 0).then(|| sorted.iter().sum::<f64>() / frames as f64),
+        p90_frame_ms: percentile(&sorted, 0.9),
+        p99_frame_ms: percentile(&sorted, 0.99),
+    }
+}
+
+/// 启动帧率监控：定时读取 gfxinfo framestats，只统计上次采样之后的新帧
+#[tauri::command]
+pub async fn adb_start_fps_monitor(
+    app: AppHandle,
+    device_id: Option<String>,
+    package: String,
+    interval_ms: Option<u64>,
+) -> MdtResult<()> {
+    let device_key = tools::device_key(&device_id);
+    let interval = sample_interval(interval_ms);
+    let stop_flag = register_monitor("fps monitor", &device_key)?;
+
+    tauri::async_runtime::spawn(async move {
+        let quoted = tools::shell_quote(&package);
+        // 上次采样时最新一帧的 IntendedVsync，首次采样只记录基准，不统计历史帧
+        let mut last_vsync: Option<u64> = None;
+        let mut last_sample_at = std::time::Instant::now();
+        let mut failures = 0;
+        while !stop_flag.load(Ordering::SeqCst) {
+            tokio::time::sleep(interval).await;
+            let output = match adb::adb_shell(&device_id, &["dumpsys", "gfxinfo", &quoted, "framestats"]).await {
+                Ok(output) => {
+                    failures = 0;
+                    output
+                }
+                Err(err) => {
+                    if sampling_failed(&app, "fps monitor", &device_id, &mut failures, &err) {
+                        break;
+                    }
+                    continue;
+                }
+            };
+            let frames = parse_framestats(&output);
+            let newest = frames.iter().map(|(vsync, _)| *vsync).max().unwrap_or(0);
+            let Some(previous) = last_vsync else {
+                last_vsync = Some(newest);
+                last_sample_at = std::time::Instant::now();
+                continue;
+            };
+            let frame_times: Vec<f64> = frames
+                .into_iter()
+                .filter(|(vsync, _)| *vsync > previous)
+                .map(|(_, time)| time)
+                .collect();
+            last_vsync = Some(previous.max(newest));
+
+            let sample = build_fps_sample(&device_id, &package, frame_times, last_sample_at.elapsed());
+            last_sample_at = std::time::Instant::now();
+            let _ = app.emit(FPS_SAMPLE_EVENT, sample);
+        }
+        finish_monitor("fps monitor", &device_key, &stop_flag);
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn adb_stop_fps_monitor(device_id: Option<String>) -> MdtResult<()> {
+    stop_monitor("fps monitor", &tools::device_key(&device_id))
+}
+
+/// 流量采样事件名
+pub const NETWORK_SAMPLE_EVENT: &str = "network-sample";
+
+const DEFAULT_NETWORK_INTERVAL_MS: u64 = 3000;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStats {
+    pub package: String,
+    pub uid: u32,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    /// 数据来源：xt_qtaguid（Android 9 及以下）或 netstats
+    pub source: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkSample {
+    pub device_id: Option<String>,
+    pub package: String,
+    pub timestamp: u64,
+    pub rx_bytes: u64,
+    pub tx_bytes: u64,
+    /// 与上次采样相比的增量
+    pub rx_delta: u64,
+    pub tx_delta: u64,
+}
+
+/// 通过 `cmd package list packages -U` 查询应用 UID
+async fn resolve_uid(device_id: &Option<String>, package: &str) -> MdtResult<u32> {
+    let output = adb::adb_shell(
+        device_id,
+        &["cmd", "package", "list", "packages", "-U", &tools::shell_quote(package)],
+    )
+    .await?;
+    let target = format!("package:{}", package);
+    output
+        .lines()
+        .filter(|line| line.split_whitespace().next() == Some(target.as_str()))
+        .find_map(|line| line.split("uid:").nth(1)?.split([',', ' ']).next()?.parse().ok())
+        .ok_or_else(|| MdtError::invalid_argument("package", format!("设备上未安装 {}", package)))
+}
+
+/// 汇总 `/proc/net/xt_qtaguid/stats` 中指定 UID（tag 为 0x0）的收发字节
+fn parse_qtaguid(output: &str, uid: u32) -> Option<(u64, u64)> {
+    let mut lines = output.lines();
+    let header: Vec<&str> = lines.next()?.split_whitespace().collect();
+    let column = |name: &str| header.iter().position(|h| *h == name);
+    let (tag, uid_col, rx, tx) = (
+        column("acct_tag_hex")?,
+        column("uid_tag_int")?,
+        column("rx_bytes")?,
+        column("tx_bytes")?,
+    );
+    let mut totals = (0u64, 0u64);
+    for line in lines {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.get(tag) == Some(&"0x0") && fields.get(uid_col).and_then(|v| v.parse().ok()) == Some(uid) {
+            totals.0 += fields.get(rx).and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+            totals.1 += fields.get(tx).and_then(|v| v.parse::<u64>().ok()).unwrap_or(0);
+        }
+    }
+    Some(totals)
+}
+
+/// 汇总 `dumpsys netstats detail` 中 UID stats 小节里指定 UID 的 rb/tb
+fn parse_netstats(output: &str, uid: u32) -> (u64, u64) {
+    let uid_marker = format!("uid={} ", uid);
+    let mut in_uid_section = false;
+    let mut matching = false;
+    let mut totals = (0u64, 0u64);
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.ends_with("stats:") && !trimmed.contains('=') {
+            in_uid_section = trimmed == "UID stats:";
+            matching = false;
+            continue;
+        }
+        if !in_uid_section {
+            continue;
+        }
+        if trimmed.starts_with("ident=") {
+            matching = trimmed.contains(&uid_marker) && trimmed.contains("tag=0x0");
+        } else if matching && trimmed.starts_with("st=") {
+            for field in trimmed.split_whitespace() {
+                match field.split_once('=') {
+                    Some(("rb", value)) => totals.0 += value.parse().unwrap_or(0),
+                    Some(("tb", value)) => totals.1 += value.parse().unwrap_or(0),
+                    _ => {}
+                }
+            }
+        }
+    }
+    totals
+}
+
+async fn read_network_stats(device_id: &Option<String>, package: &str, uid: u32) -> MdtResult<NetworkStats> {
+    let qtaguid = adb::adb_shell(device_id, &["cat", "/proc/net/xt_qtaguid/stats"])
+        .await
+        .ok()
+        .and_then(|output| parse_qtaguid(&output, uid));
+    let ((rx_bytes, tx_bytes), source) = match qtaguid {
+        Some(totals) => (totals, "xt_qtaguid"),
+        None => {
+            // 先强制刷新统计，否则 netstats 只包含上次轮询时的数据
+            let _ = adb::adb_shell(device_id, &["dumpsys", "netstats", "--poll"]).await;
+            let output = adb::adb_shell(device_id, &["dumpsys", "netstats", "detail"]).await?;
+            (parse_netstats(&output, uid), "netstats")
+        }
+    };
+    Ok(NetworkStats {
+        package: package.to_string(),
+        uid,
+        rx_bytes,
+        tx_bytes,
+        source: source.to_string(),
+    })
+}
+
+/// 查询应用累计收发流量
+#[tauri::command]
+pub async fn adb_app_network_stats(device_id: Option<String>, package: String) -> MdtResult<NetworkStats> {
+    let uid = resolve_uid(&device_id, &package).await?;
+    read_network_stats(&device_id, &package, uid).await
+}
+
+/// 启动流量监控，按间隔（默认 3 秒）推送收发增量
+#[tauri::command]
+pub async fn adb_start_network_monitor(
+    app: AppHandle,
+    device_id: Option<String>,
+    package: String,
+    interval_ms: Option<u64>,
+) -> MdtResult<()> {
+    let uid = resolve_uid(&device_id, &package).await?;
+    let device_key = tools::device_key(&device_id);
+    let interval = sample_interval(interval_ms.or(Some(DEFAULT_NETWORK_INTERVAL_MS)));
+    let stop_flag = register_monitor("network monitor", &device_key)?;
+
+    tauri::async_runtime::spawn(async move {
+        // 第一次读取只作为基准，之后每次推送与上次读取的差值
+        let mut previous: Option<NetworkStats> = None;
+        let mut failures = 0;
+        while !stop_flag.load(Ordering::SeqCst) {
+            match read_network_stats(&device_id, &package, uid).await {
+                Ok(current) => {
+                    failures = 0;
+                    if let Some(previous) = previous.replace(current.clone()) {
+                        let sample = NetworkSample {
+                            device_id: device_id.clone(),
+                            package: package.clone(),
+                            timestamp: tools::now_millis(),
+                            rx_bytes: current.rx_bytes,
+                            tx_bytes: current.tx_bytes,
+                            // 计数器可能因重启归零，此时增量按 0 处理
+                            rx_delta: current.rx_bytes.saturating_sub(previous.rx_bytes),
+                            tx_delta: current.tx_bytes.saturating_sub(previous.tx_bytes),
+                        };
+                        let _ = app.emit(NETWORK_SAMPLE_EVENT, sample);
+                    }
+                }
+                Err(err) => {
+                    if sampling_failed(&app, "network monitor", &device_id, &mut failures, &err) {
+                        break;
+                    }
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+        finish_monitor("network monitor", &device_key, &stop_flag);
+    });
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn adb_stop_network_monitor(device_id: Option<String>) -> MdtResult<()> {
+    stop_monitor("network monitor", &tools::device_key(&device_id))
+}
+
+/// `dumpsys meminfo` 解析结果（KB）；应用字段来自 App Summary，整机字段来自 RAM 汇总
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MemInfo {
+    pub package: Option<String>,
+    pub java_heap_kb: Option<u64>,
+    pub native_heap_kb: Option<u64>,
+    pub code_kb: Option<u64>,
+    pub stack_kb: Option<u64>,
+    pub graphics_kb: Option<u64>,
+    pub private_other_kb: Option<u64>,
+    pub system_kb: Option<u64>,
+    pub total_pss_kb: Option<u64>,
+    pub total_rss_kb: Option<u64>,
+    pub total_swap_pss_kb: Option<u64>,
+    pub total_ram_kb: Option<u64>,
+    pub free_ram_kb: Option<u64>,
+    pub used_ram_kb: Option<u64>,
+    pub lost_ram_kb: Option<u64>,
+}
+
+/// 取出形如 `1,234,567K` 或 `12345` 的数值
+fn parse_kb(value: &str) -> Option<u64> {
+    value
+        .trim()
+        .trim_end_matches('K')
+        .replace(',', "")
+        .parse()
+        .ok()
+}
+
+fn parse_meminfo(package: Option<String>, output: &str) -> MemInfo {
+    let mut info = MemInfo {
+        package,
+        ..Default::default()
+    };
+    let mut in_summary = false;
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed == "App Summary" {
+            in_summary = true;
+            continue;
+        }
+
+        // 汇总行：`TOTAL PSS:  26000   TOTAL RSS:  45000   TOTAL SWAP PSS:  12`
+        if trimmed.starts_with("TOTAL PSS:") || trimmed.starts_with("TOTAL:") {
+            let mut rest = trimmed;
+            while let Some((key, tail)) = rest.split_once(':') {
+                let mut parts = tail.trim_start().splitn(2, char::is_whitespace);
+                let value = parts.next().and_then(parse_kb);
+                match key.trim() {
+                    "TOTAL PSS" => info.total_pss_kb = value,
+                    "TOTAL" => info.total_pss_kb = info.total_pss_kb.or(value),
+                    "TOTAL RSS" => info.total_rss_kb = value,
+                    "TOTAL SWAP PSS" | "TOTAL SWAP (KB)" => info.total_swap_pss_kb = value,
+                    _ => {}
+                }
+                rest = parts.next().unwrap_or("");
+            }
+            continue;
+        }
+
+        // 旧系统没有 App Summary，使用明细表中的 `TOTAL  26000 ...` 行
+        if let Some(rest) = trimmed.strip_prefix("TOTAL ") {
+            if info.total_pss_kb.is_none() {
+                info.total_pss_kb = rest.split_whitespace().next().and_then(parse_kb);
+            }
+            continue;
+        }
+
+        let Some((key, rest)) = trimmed.split_once(':') else {
+            continue;
+        };
+        let first = rest.split_whitespace().next().and_then(parse_kb);
+        if in_summary {
+            match key {
+                "Java Heap" => info.java_heap_kb = first,
+                "Native Heap" => info.native_heap_kb = first,
+                "Code" => info.code_kb = first,
+                "Stack" => info.stack_kb = first,
+                "Graphics" => info.graphics_kb = first,
+                "Private Other" => info.private_other_kb = first,
+                "System" => info.system_kb = first,
+                _ => {}
+            }
+        } else {
+            match key {
+                "Total RAM" => info.total_ram_kb = first,
+                "Free RAM" => info.free_ram_kb = first,
+                "Used RAM" => info.used_ram_kb = first,
+                "Lost RAM" => info.lost_ram_kb = first,
+                _ => {}
+            }
+        }
+    }
+    info
+}
+
+/// 解析整机或指定应用的 `dumpsys meminfo`
+#[tauri::command]
+pub async fn adb_meminfo(device_id: Option<String>, package: Option<String>) -> MdtResult<MemInfo> {
+    let output = match &package {
+        Some(package) => {
+            adb::adb_shell(&device_id, &["dumpsys", "meminfo", &tools::shell_quote(package)]).await?
+        }
+        None => adb::adb_shell(&device_id, &["dumpsys", "meminfo"]).await?,
+    };
+    if package.is_some() && output.contains("No process found") {
+        return Err(MdtError::invalid_argument(
+            "package",
+            format!("{} 未在运行", package.unwrap_or_default()),
+        ));
+    }
+    Ok(parse_meminfo(package, &output))
+}
+
+/// 温度采样事件名
+pub const THERMAL_SAMPLE_EVENT: &str = "thermal-sample";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThermalZone {
+    pub name: String,
+    pub temperature_c: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThermalSample {
+    pub device_id: Option<String>,
+    pub timestamp: u64,
+    /// 整机温控等级：none / light / moderate / severe / critical / emergency / shutdown
+    pub status: Option<String>,
+    pub throttling: bool,
+    pub zones: Vec<ThermalZone>,
+}
+
+const THERMAL_STATUS_NAMES: [&str; 7] = [
+    "none", "light", "moderate", "severe", "critical", "emergency", "shutdown",
+];
+
+fn thermal_status(level: usize) -> Option<String> {
+    THERMAL_STATUS_NAMES.get(level).map(|name| name.to_string())
+}
+
+/// 取出 `Temperature{mValue=36.2, mType=0, mName=cpu0, mStatus=0}` 中的字段
+fn temperature_field<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let start = line.find(&format!("{}=", key))? + key.len() + 1;
+    let rest = &line[start..];
+    Some(rest[..rest.find([',', '}']).unwrap_or(rest.len())].trim())
+}
+
+/// 解析 `dumpsys thermalservice`：优先使用 HAL 实时温度，缺失时使用缓存温度
+fn parse_thermalservice(output: &str) -> (Option<usize>, Vec<ThermalZone>) {
+    let mut status = None;
+    let mut cached = Vec::new();
+    let mut current = Vec::new();
+    let mut section = "";
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if let Some(value) = trimmed.strip_prefix("Thermal Status:") {
+            status = value.trim().parse().ok();
+        } else if trimmed.starts_with("Cached temperatures") {
+            section = "cached";
+        } else if trimmed.starts_with("Current temperatures from HAL") {
+            section = "current";
+        } else if trimmed.starts_with("Temperature{") {
+            let zone = temperature_field(trimmed, "mName")
+                .zip(temperature_field(trimmed, "mValue").and_then(|v| v.parse().ok()))
+                .map(|(name, temperature_c)| ThermalZone {
+                    name: name.to_string(),
+                    temperature_c,
+                });
+            match (section, zone) {
+                ("cached", Some(zone)) => cached.push(zone),
+                ("current", Some(zone)) => current.push(zone),
+                _ => {}
+            }
+        } else if !trimmed.is_empty() && !line.starts_with(char::is_whitespace) {
+            section = "";
+        }
+    }
+    (status, if current.is_empty() { cached } else { current })
+}
+
+/// 解析 HarmonyOS `hidumper -s ThermalService -a`，温度单位为毫摄氏度
+fn parse_hidumper_thermal(output: &str) -> (Option<usize>, Vec<ThermalZone>) {
+    let mut level = None;
+    let mut zones = Vec::new();
+    let mut name: Option<String> = None;
+    for line in output.lines() {
+        for field in line.split(['\t', ',']) {
+            let Some((key, value)) = field.split_once(':') else {
+                continue;
+            };
+            let value = value.trim();
+            match key.trim().to_lowercase().as_str() {
+                "name" | "type" => name = Some(value.to_string()),
+                "temperature" | "temp" => {
+                    if let (Some(zone), Ok(temp)) = (name.take(), value.parse::<f64>()) {
+                        zones.push(ThermalZone {
+                            name: zone,
+                            temperature_c: temp / 1000.0,
+                        });
+                    }
+                }
+                "level" | "thermal level" => level = value.parse().ok(),
+                _ => {}
+            }
+        }
+    }
+    (level, zones)
+}
+
+fn build_thermal_sample(device_id: &Option<String>, parsed: (Option<usize>, Vec<ThermalZone>)) -> ThermalSample {
+    let (level, zones) = parsed;
+    ThermalSample {
+        device_id: device_id.clone(),
+        timestamp: tools::now_millis(),
+        status: level.and_then(thermal_status),
+        throttling: level.is_some_and(|level| level > 0),
+        zones,
+    }
+}
+
+/// 启动温度监控（Android），推送各温区温度与温控等级
+#[tauri::command]
+pub async fn adb_start_thermal_monitor(
+    app: AppHandle,
+    device_id: Option<String>,
+    interval_ms: Option<u64>,
+) -> MdtResult<()> {
+    let device_key = tools::device_key(&device_id);
+    let interval = sample_interval(interval_ms);
+    let stop_flag = register_monitor("thermal monitor", &device_key)?;
+
+    tauri::async_runtime::spawn(async move {
+        let mut failures = 0;
+        while !stop_flag.load(Ordering::SeqCst) {
+            match adb::adb_shell(&device_id, &["dumpsys", "thermalservice"]).await {
+                Ok(output) => {
+                    failures = 0;
+                    let sample = build_thermal_sample(&device_id, parse_thermalservice(&output));
+                    let _ = app.emit(THERMAL_SAMPLE_EVENT, sample);
+                }
+                Err(err) => {
+                    if sampling_failed(&app, "thermal monitor", &device_id, &mut failures, &err) {
+                        break;
+                    }
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+        finish_monitor("thermal monitor", &device_key, &stop_flag);
+    });
+    Ok(())
+}
+
+/// 启动温度监控（HarmonyOS），推送各温区温度与温控等级
+#[tauri::command]
+pub async fn hdc_start_thermal_monitor(
+    app: AppHandle,
+    device_id: Option<String>,
+    interval_ms: Option<u64>,
+) -> MdtResult<()> {
+    let device_key = tools::device_key(&device_id);
+    let interval = sample_interval(interval_ms);
+    let stop_flag = register_monitor("thermal monitor", &device_key)?;
+
+    tauri::async_runtime::spawn(async move {
+        let mut failures = 0;
+        while !stop_flag.load(Ordering::SeqCst) {
+            match hdc::hdc_shell(&device_id, &["hidumper", "-s", "ThermalService", "-a"]).await {
+                Ok(output) => {
+                    failures = 0;
+                    let sample = build_thermal_sample(&device_id, parse_hidumper_thermal(&output));
+                    let _ = app.emit(THERMAL_SAMPLE_EVENT, sample);
+                }
+                Err(err) => {
+                    if sampling_failed(&app, "thermal monitor", &device_id, &mut failures, &err) {
+                        break;
+                    }
+                }
+            }
+            tokio::time::sleep(interval).await;
+        }
+        finish_monitor("thermal monitor", &device_key, &stop_flag);
+    });
+    Ok(())
+}
+
+/// 停止温度监控（adb / hdc 通用）
+#[tauri::command]
+pub async fn stop_thermal_monitor(device_id: Option<String>) -> MdtResult<()> {
+    stop_monitor("thermal monitor", &tools::device_key(&device_id))
+}
@@ -0,0 +1,241 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+
+const APPOPS_MODES: &[&str] = &["allow", "ignore", "deny", "default", "foreground"];
+
+/// `cmd appops get` 中的单项操作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppOpEntry {
+    /// 如 `MOCK_LOCATION`、`RUN_IN_BACKGROUND`
+    pub op: String,
+    /// allow / ignore / deny / default / foreground
+    pub mode: String,
+    /// 是否为按 UID 设置的模式（`Uid mode:`）
+    pub uid_mode: bool,
+    /// 最近一次允许访问，如 `+2h3m ago`
+    pub last_access: Option<String>,
+    pub last_reject: Option<String>,
+}
+
+/// 解析 `MOCK_LOCATION: allow; time=+1m2s ago; rejectTime=+5m ago`
+fn parse_appops(output: &str) -> Vec<AppOpEntry> {
+    let mut entries = Vec::new();
+    for line in output.lines() {
+        let trimmed = line.trim();
+        let (uid_mode, trimmed) = match trimmed.strip_prefix("Uid mode:") {
+            Some(rest) => (true, rest.trim()),
+            None => (false, trimmed),
+        };
+        let Some((op, rest)) = trimmed.split_once(':') else {
+            continue;
+        };
+        // 操作名均为大写字母与下划线，其余行（如 `No operations.`、包名标题）跳过
+        if op.is_empty() || !op.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_') {
+            continue;
+        }
+        let mut fields = rest.split(';').map(str::trim);
+        let mode = fields.next().unwrap_or_default().to_string();
+        if mode.is_empty() {
+            continue;
+        }
+        let mut entry = AppOpEntry {
+            op: op.to_string(),
+            mode,
+            uid_mode,
+            last_access: None,
+            last_reject: None,
+        };
+        for field in fields {
+            if let Some(value) = field.strip_prefix("time=") {
+                entry.last_access = Some(value.to_string());
+            } else if let Some(value) = field.strip_prefix("rejectTime=") {
+                entry.last_reject = Some(value.to_string());
+            }
+        }
+        entries.push(entry);
+    }
+    entries
+}
+
+#[tauri::command]
+pub async fn adb_appops_get(device_id: Option<String>, package: String) -> MdtResult<Vec<AppOpEntry>> {
+    let output = adb::adb_shell(&device_id, &["cmd", "appops", "get", &tools::shell_quote(&package)]).await?;
+    let output = adb::expect_no_error(&format!("cmd appops get {}", package), output)?;
+    Ok(parse_appops(&output))
+}
+
+/// 设置应用的 AppOps 模式，如允许 `MOCK_LOCATION` 或以 `ignore` 限制 `RUN_IN_BACKGROUND`
+#[tauri::command]
+pub async fn adb_appops_set(
+    device_id: Option<String>,
+    package: String,
+    op: String,
+    mode: String,
+) -> MdtResult<()> {
+    let _history = history::track();
+    if op.is_empty() || !op.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':' || c == '.') {
+        return Err(MdtError::invalid_argument("op", format!("无效的操作名: {}", op)));
+    }
+    if !APPOPS_MODES.contains(&mode.as_str()) {
+        return Err(MdtError::invalid_argument(
+            "mode",
+            format!("模式需为 {} 之一", APPOPS_MODES.join(" / ")),
+        ));
+    }
+    let output = adb::adb_shell(
+        &device_id,
+        &["cmd", "appops", "set", &tools::shell_quote(&package), &op, &mode],
+    )
+    .await?;
+    adb::expect_no_error(&format!("cmd appops set {} {} {}", package, op, mode), output)?;
+    println!("[appops] {} {} -> {}", package, op, mode);
+    Ok(())
+}
+
+/// 应用声明的单个权限及其授予状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionState {
+    pub name: String,
+    pub granted: bool,
+    /// 是否为运行时（危险）权限，仅运行时权限可 grant / revoke
+    pub runtime: bool,
+    /// 如 `USER_SET`、`USER_FIXED`、`POLICY_FIXED`
+    pub flags: Vec<String>,
+}
+
+/// 解析 `android.permission.CAMERA: granted=false, flags=[ USER_SET|USER_FIXED ]`
+fn parse_permission_line(line: &str, runtime: bool) -> Option<PermissionState> {
+    let (name, rest) = line.split_once(": granted=")?;
+    let flags = rest
+        .split_once("flags=[")
+        .and_then(|(_, flags)| flags.split(']').next())
+        .map(|flags| {
+            flags
+                .split('|')
+                .map(str::trim)
+                .filter(|flag| !flag.is_empty())
+                .map(str::to_string)
+                .collect()
+        })
+        .unwrap_or_default();
+    Some(PermissionState {
+        name: name.trim().to_string(),
+        granted: rest.starts_with("true"),
+        runtime,
+        flags,
+    })
+}
+
+/// 从 `dumpsys package <pkg>` 中汇总 requested / install / runtime 权限
+fn parse_permissions(package: &str, output: &str) -> Option<Vec<PermissionState>> {
+    let header = format!("Package [{}]", package);
+    let mut in_package = false;
+    let mut found = false;
+    let mut requested: Vec<String> = Vec::new();
+    let mut states: Vec<PermissionState> = Vec::new();
+    let mut section = "";
+    let mut section_indent = 0;
+
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("Package [") {
+            in_package = trimmed.starts_with(&header);
+            found |= in_package;
+            section = "";
+            continue;
+        }
+        if !in_package {
+            continue;
+        }
+        let indent = line.len() - line.trim_start().len();
+        if !section.is_empty() && indent <= section_indent {
+            section = "";
+        }
+        match trimmed {
+            "requested permissions:" | "install permissions:" | "runtime permissions:" => {
+                section = trimmed;
+                section_indent = indent;
+                continue;
+            }
+            _ => {}
+        }
+        match section {
+            "requested permissions:" => {
+                let name = trimmed.split(':').next().unwrap_or(trimmed).to_string();
+                if !requested.contains(&name) {
+                    requested.push(name);
+                }
+            }
+            "install permissions:" | "runtime permissions:" => {
+                let runtime = section == "runtime permissions:";
+                if let Some(state) = parse_permission_line(trimmed, runtime) {
+                    // 多用户时只保留首个用户（通常为当前用户）的状态
+                    if !states.iter().any(|s| s.name == state.name) {
+                        states.push(state);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if !found {
+        return None;
+    }
+
+    // 按声明顺序输出，未授予的安装权限不会出现在 install permissions 中
+    let mut permissions: Vec<PermissionState> = requested
+        .iter()
+        .map(|name| match states.iter().find(|s| &s.name == name) {
+            Some(state) => state.clone(),
+            None => PermissionState {
+                name: name.clone(),
+                granted: false,
+                runtime: false,
+                flags: Vec::new(),
+            },
+        })
+        .collect();
+    permissions.extend(states.into_iter().filter(|s| !requested.contains(&s.name)));
+    Some(permissions)
+}
+
+/// 列出应用声明的权限及授予状态（含运行时权限的 flags）
+#[tauri::command]
+pub async fn adb_list_permissions(device_id: Option<String>, package: String) -> MdtResult<Vec<PermissionState>> {
+    let output = adb::adb_shell(&device_id, &["dumpsys", "package", &tools::shell_quote(&package)]).await?;
+    parse_permissions(&package, &output)
+        .ok_or_else(|| MdtError::invalid_argument("package", format!("设备上未安装 {}", package)))
+}
+
+async fn change_permission(device_id: &Option<String>, action: &str, package: &str, permission: &str) -> MdtResult<()> {
+    if permission.is_empty() || permission.contains(char::is_whitespace) {
+        return Err(MdtError::invalid_argument("permission", format!("无效的权限名: {}", permission)));
+    }
+    let output = adb::adb_shell(
+        device_id,
+        &["pm", action, &tools::shell_quote(package), &tools::shell_quote(permission)],
+    )
+    .await?;
+    adb::expect_no_error(&format!("pm {} {} {}", action, package, permission), output)?;
+    println!("[permission] {} {} for {}", action, permission, package);
+    Ok(())
+}
+
+/// 授予运行时权限（`pm grant`）
+#[tauri::command]
+pub async fn adb_grant_permission(device_id: Option<String>, package: String, permission: String) -> MdtResult<()> {
+    let _history = history::track();
+    change_permission(&device_id, "grant", &package, &permission).await
+}
+
+/// 撤销运行时权限（`pm revoke`），应用进程会被系统结束
+#[tauri::command]
+pub async fn adb_revoke_permission(device_id: Option<String>, package: String, permission: String) -> MdtResult<()> {
+    let _history = history::track();
+    change_permission(&device_id, "revoke", &package, &permission).await
+}
@@ -0,0 +1,273 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::store;
+use crate::history;
+use std::collections::{HashMap, HashSet};
+use std::sync::{Mutex, OnceLock};
+
+/// 端口映射：`device` 为设备端地址，`host` 为电脑端地址，均为 adb 格式如 `tcp:8081`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortMapping {
+    pub device: String,
+    pub host: String,
+    /// 设备重新连接后是否自动恢复
+    pub persistent: bool,
+}
+
+const REVERSES_FILE: &str = "port-reverses";
+
+/// 需要在设备重连后恢复的 reverse 规则，按设备序列号保存，重启后从数据目录加载
+fn persistent_reverses() -> &'static Mutex<HashMap<String, Vec<PortMapping>>> {
+    static STORE: OnceLock<Mutex<HashMap<String, Vec<PortMapping>>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(store::load(REVERSES_FILE)))
+}
+
+/// 规则按真实序列号保存，未指定设备时通过 `adb get-serialno` 查询默认设备
+async fn resolve_serial(device_id: &Option<String>) -> MdtResult<String> {
+    match device_id {
+        Some(serial) => Ok(serial.clone()),
+        None => {
+            let serial = adb::adb_run(device_id, &["get-serialno"]).await?.trim().to_string();
+            if serial.is_empty() || serial == "unknown" {
+                return Err(MdtError::invalid_argument("deviceId", "无法确定默认设备的序列号"));
+            }
+            Ok(serial)
+        }
+    }
+}
+
+/// 上一次 `adb devices` 时在线的设备，用于识别重新连接
+fn online_devices() -> &'static Mutex<HashSet<String>> {
+    static STORE: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// 规范化端口描述：纯数字视为 `tcp:<port>`
+fn normalize_spec(field: &str, spec: &str) -> MdtResult<String> {
+    let spec = spec.trim();
+    if let Ok(port) = spec.parse::<u16>() {
+        return Ok(format!("tcp:{}", port));
+    }
+    let valid = spec
+        .split_once(':')
+        .map(|(kind, value)| {
+            matches!(kind, "tcp" | "localabstract" | "localreserved" | "localfilesystem" | "dev" | "jdwp" | "vsock")
+                && !value.is_empty()
+                && !value.contains(char::is_whitespace)
+        })
+        .unwrap_or(false);
+    if valid {
+        Ok(spec.to_string())
+    } else {
+        Err(MdtError::invalid_argument(field, format!("无效的端口描述: {}", spec)))
+    }
+}
+
+/// 解析 `--list` 输出：每行最后两列为 `<设备端/本地端> <对端>`
+fn parse_mapping_list(output: &str) -> Vec<(String, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let tokens: Vec<&str> = line.split_whitespace().collect();
+            match tokens.as_slice() {
+                [.., first, second] if first.contains(':') && second.contains(':') => {
+                    Some((first.to_string(), second.to_string()))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn adb_reverse_list(device_id: Option<String>) -> MdtResult<Vec<PortMapping>> {
+    let output = adb::adb_run(&device_id, &["reverse", "--list"]).await?;
+    let serial = resolve_serial(&device_id).await?;
+    let persisted = persistent_reverses()
+        .lock()
+        .map_err(|_| MdtError::lock("reverse"))?
+        .get(&serial)
+        .cloned()
+        .unwrap_or_default();
+    Ok(parse_mapping_list(&output)
+        .into_iter()
+        .map(|(device, host)| PortMapping {
+            persistent: persisted.iter().any(|rule| rule.device == device),
+            device,
+            host,
+        })
+        .collect())
+}
+
+/// 将设备端端口反向映射到电脑（如 Metro / Vite 的 localhost:8081），`persist` 默认开启
+#[tauri::command]
+pub async fn adb_reverse_add(
+    device_id: Option<String>,
+    device: String,
+    host: String,
+    persist: Option<bool>,
+) -> MdtResult<Vec<PortMapping>> {
+    let _history = history::track();
+    let device = normalize_spec("device", &device)?;
+    let host = normalize_spec("host", &host)?;
+    adb::adb_run(&device_id, &["reverse", &device, &host]).await?;
+
+    let persistent = persist.unwrap_or(true);
+    let serial = resolve_serial(&device_id).await?;
+    {
+        let mut rules_store = persistent_reverses()
+            .lock()
+            .map_err(|_| MdtError::lock("reverse"))?;
+        let rules = rules_store.entry(serial).or_default();
+        rules.retain(|rule| rule.device != device);
+        if persistent {
+            rules.push(PortMapping {
+                device: device.clone(),
+                host: host.clone(),
+                persistent,
+            });
+        }
+        rules_store.retain(|_, rules| !rules.is_empty());
+        store::save(REVERSES_FILE, &*rules_store)?;
+    }
+    println!("[ports] reverse {} -> {}", device, host);
+    adb_reverse_list(device_id).await
+}
+
+#[tauri::command]
+pub async fn adb_reverse_remove(device_id: Option<String>, device: String) -> MdtResult<Vec<PortMapping>> {
+    let _history = history::track();
+    let device = normalize_spec("device", &device)?;
+    let serial = resolve_serial(&device_id).await?;
+    {
+        let mut rules_store = persistent_reverses()
+            .lock()
+            .map_err(|_| MdtError::lock("reverse"))?;
+        if let Some(rules) = rules_store.get_mut(&serial) {
+            rules.retain(|rule| rule.device != device);
+        }
+        rules_store.retain(|_, rules| !rules.is_empty());
+        store::save(REVERSES_FILE, &*rules_store)?;
+    }
+    adb::adb_run(&device_id, &["reverse", "--remove", &device]).await?;
+    adb_reverse_list(device_id).await
+}
+
+/// 根据最新的在线设备列表，对重新连接的设备恢复已保存的 reverse 规则
+pub fn sync_online_devices(online: &[String]) {
+    let reconnected: Vec<String> = {
+        let Ok(mut known) = online_devices().lock() else {
+            return;
+        };
+        let current: HashSet<String> = online.iter().cloned().collect();
+        let reconnected = current.difference(&known).cloned().collect();
+        *known = current;
+        reconnected
+    };
+
+    for serial in reconnected {
+        let rules = match persistent_reverses().lock() {
+            Ok(store) => store.get(&serial).cloned().unwrap_or_default(),
+            Err(_) => continue,
+        };
+        if rules.is_empty() {
+            continue;
+        }
+        tauri::async_runtime::spawn(async move {
+            let device_id = Some(serial.clone());
+            for rule in rules {
+                match adb::adb_run(&device_id, &["reverse", &rule.device, &rule.host]).await {
+                    Ok(_) => println!("[ports] restored reverse {} -> {} on {}", rule.device, rule.host, serial),
+                    Err(err) => println!("[ports] restore reverse failed on {}: {}", serial, err),
+                }
+            }
+        });
+    }
+}
+
+/// `adb forward --list` 中的一项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForwardEntry {
+    pub serial: String,
+    pub host: String,
+    pub device: String,
+    /// 指向 scrcpy 但不属于任何运行中镜像会话的转发（镜像异常退出后的残留）
+    pub stale: bool,
+}
+
+async fn list_forwards(device_id: &Option<String>) -> MdtResult<Vec<ForwardEntry>> {
+    let output = adb::adb_run(&None, &["forward", "--list"]).await?;
+    let mirror_ports: Vec<String> = adb::active_mirror_ports()
+        .into_iter()
+        .map(|port| format!("tcp:{}", port))
+        .collect();
+    Ok(output
+        .lines()
+        .filter_map(|line| {
+            let mut tokens = line.split_whitespace();
+            let (serial, host, device) = (tokens.next()?, tokens.next()?, tokens.next()?);
+            Some(ForwardEntry {
+                serial: serial.to_string(),
+                host: host.to_string(),
+                device: device.to_string(),
+                stale: device.starts_with("localabstract:scrcpy") && !mirror_ports.iter().any(|p| p == host),
+            })
+        })
+        .filter(|entry| device_id.as_ref().is_none_or(|id| &entry.serial == id))
+        .collect())
+}
+
+/// 列出端口转发（未指定设备时列出全部设备），并标记残留的镜像转发
+#[tauri::command]
+pub async fn adb_forward_list(device_id: Option<String>) -> MdtResult<Vec<ForwardEntry>> {
+    list_forwards(&device_id).await
+}
+
+/// 将电脑端口转发到设备，`host` 为空时由 adb 分配空闲端口
+#[tauri::command]
+pub async fn adb_forward_add(
+    device_id: Option<String>,
+    host: Option<String>,
+    device: String,
+) -> MdtResult<Vec<ForwardEntry>> {
+    let _history = history::track();
+    let host = match host {
+        Some(host) => normalize_spec("host", &host)?,
+        None => "tcp:0".to_string(),
+    };
+    let device = normalize_spec("device", &device)?;
+    let output = adb::adb_run(&device_id, &["forward", &host, &device]).await?;
+    println!("[ports] forward {} -> {} {}", host, device, output.trim());
+    list_forwards(&device_id).await
+}
+
+#[tauri::command]
+pub async fn adb_forward_remove(device_id: Option<String>, host: String) -> MdtResult<Vec<ForwardEntry>> {
+    let _history = history::track();
+    let host = normalize_spec("host", &host)?;
+    adb::adb_run(&device_id, &["forward", "--remove", &host]).await?;
+    list_forwards(&device_id).await
+}
+
+/// 移除设备的全部转发；`stale_only` 为 true 时只清理残留的镜像转发
+#[tauri::command]
+pub async fn adb_forward_remove_all(
+    device_id: Option<String>,
+    stale_only: Option<bool>,
+) -> MdtResult<Vec<ForwardEntry>> {
+    let _history = history::track();
+    let stale_only = stale_only.unwrap_or(false);
+    for entry in list_forwards(&device_id).await? {
+        if stale_only && !entry.stale {
+            continue;
+        }
+        let serial = Some(entry.serial.clone());
+        if let Err(err) = adb::adb_run(&serial, &["forward", "--remove", &entry.host]).await {
+            println!("[ports] remove forward {} failed: {}", entry.host, err);
+        }
+    }
+    list_forwards(&device_id).await
+}
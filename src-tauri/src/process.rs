@@ -0,0 +1,34 @@
+use serde::{Deserialize, Serialize};
+
+/// 设备端进程信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub ppid: u32,
+    pub user: String,
+    /// 常驻内存（KB）
+    pub rss_kb: u64,
+    pub name: String,
+}
+
+/// 列出全部进程的 shell 参数（toybox ps，Android 与 OpenHarmony 通用）
+pub const PS_ARGS: [&str; 4] = ["ps", "-A", "-o", "PID,PPID,USER,RSS,NAME"];
+
+/// 解析 `PS_ARGS` 的输出，跳过表头
+pub fn parse_ps(output: &str) -> Vec<ProcessInfo> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut parts = line.split_whitespace();
+            Some(ProcessInfo {
+                pid: parts.next()?.parse().ok()?,
+                ppid: parts.next()?.parse().ok()?,
+                user: parts.next()?.to_string(),
+                rss_kb: parts.next()?.parse().ok()?,
+                name: parts.collect::<Vec<_>>().join(" "),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,110 @@
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::hdc;
+use crate::tools;
+use std::collections::BTreeMap;
+
+fn validate_key(key: &str) -> MdtResult<()> {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | '@' | ':')) {
+        Ok(())
+    } else {
+        Err(MdtError::invalid_argument("key", format!("无效的属性名: {}", key)))
+    }
+}
+
+fn filter_prefix(props: BTreeMap<String, String>, prefix: Option<&str>) -> BTreeMap<String, String> {
+    match prefix.filter(|p| !p.is_empty()) {
+        Some(prefix) => props.into_iter().filter(|(key, _)| key.starts_with(prefix)).collect(),
+        None => props,
+    }
+}
+
+/// 解析 `getprop` 的 `[ro.product.model]: [Pixel 7]` 格式（值可能跨行）
+fn parse_getprop(output: &str) -> BTreeMap<String, String> {
+    let mut props = BTreeMap::new();
+    let mut pending: Option<(String, String)> = None;
+    for line in output.lines() {
+        if let Some((key, value)) = pending.take() {
+            let mut value = value;
+            value.push('\n');
+            value.push_str(line);
+            match value.strip_suffix(']') {
+                Some(done) => {
+                    props.insert(key, done.to_string());
+                }
+                None => pending = Some((key, value)),
+            }
+            continue;
+        }
+        let Some(rest) = line.trim_start().strip_prefix('[') else {
+            continue;
+        };
+        let Some((key, value)) = rest.split_once("]: [") else {
+            continue;
+        };
+        match value.strip_suffix(']') {
+            Some(value) => {
+                props.insert(key.to_string(), value.to_string());
+            }
+            None => pending = Some((key.to_string(), value.to_string())),
+        }
+    }
+    props
+}
+
+/// 读取全部系统属性，可按前缀过滤（如 `ro.build.`）
+#[tauri::command]
+pub async fn adb_getprop_all(device_id: Option<String>, prefix: Option<String>) -> MdtResult<BTreeMap<String, String>> {
+    let output = adb::adb_shell(&device_id, &["getprop"]).await?;
+    Ok(filter_prefix(parse_getprop(&output), prefix.as_deref()))
+}
+
+/// 设置系统属性并回读确认；`ro.*` 只读属性及受 SELinux 限制的属性会设置失败
+#[tauri::command]
+pub async fn adb_setprop(device_id: Option<String>, key: String, value: String) -> MdtResult<String> {
+    let _history = history::track();
+    validate_key(&key)?;
+    adb::adb_shell(&device_id, &["setprop", &key, &tools::shell_quote(&value)]).await?;
+    let current = adb::adb_shell(&device_id, &["getprop", &key]).await?;
+    if current != value {
+        return Err(MdtError::PermissionDenied {
+            detail: format!("设置 {} 失败（只读属性或需要 root），当前值: {}", key, current),
+        });
+    }
+    println!("[props] {} -> {}", key, value);
+    Ok(current)
+}
+
+/// 解析 `param get` 的 `const.product.model = ALN-AL00` 格式
+fn parse_param(output: &str) -> BTreeMap<String, String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once(" = ")?;
+            let key = key.trim();
+            (!key.is_empty() && !key.contains(' ')).then(|| (key.to_string(), value.trim().to_string()))
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn hdc_param_get_all(device_id: Option<String>, prefix: Option<String>) -> MdtResult<BTreeMap<String, String>> {
+    let output = hdc::hdc_shell(&device_id, &["param", "get"]).await?;
+    Ok(filter_prefix(parse_param(&output), prefix.as_deref()))
+}
+
+#[tauri::command]
+pub async fn hdc_param_set(device_id: Option<String>, key: String, value: String) -> MdtResult<String> {
+    let _history = history::track();
+    validate_key(&key)?;
+    let output = hdc::hdc_shell(&device_id, &["param", "set", &key, &tools::shell_quote(&value)]).await?;
+    // 失败时输出 `Set parameter ... fail! errNum is:...`
+    if output.to_lowercase().contains("fail") {
+        return Err(MdtError::PermissionDenied {
+            detail: format!("设置 {} 失败: {}", key, output.trim()),
+        });
+    }
+    println!("[props] hdc {} -> {}", key, value);
+    hdc::hdc_shell(&device_id, &["param", "get", &key]).await
+}
@@ -0,0 +1,113 @@
+use serde::{Deserialize, Serialize};
+use crate::error::{MdtError, MdtResult};
+use crate::tools;
+
+/// 设备端文件条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteFileEntry {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    pub modified: u64,
+    pub permissions: String,
+    pub is_dir: bool,
+    pub is_symlink: bool,
+}
+
+const STAT_FORMAT: &str = "'%A|%s|%Y|%F|%n'";
+
+/// 列出目录直接子项的 shell 参数，输出格式为 `权限|大小|修改时间|类型|路径`
+pub fn list_args(path: &str) -> Vec<String> {
+    vec![
+        "find".to_string(),
+        tools::shell_quote(path),
+        "-mindepth".to_string(),
+        "1".to_string(),
+        "-maxdepth".to_string(),
+        "1".to_string(),
+        "-exec".to_string(),
+        "stat".to_string(),
+        "-c".to_string(),
+        STAT_FORMAT.to_string(),
+        "{}".to_string(),
+        "+".to_string(),
+    ]
+}
+
+/// 查询单个路径信息的 shell 参数
+pub fn stat_args(path: &str) -> Vec<String> {
+    vec![
+        "stat".to_string(),
+        "-c".to_string(),
+        STAT_FORMAT.to_string(),
+        tools::shell_quote(path),
+    ]
+}
+
+fn parse_line(line: &str) -> Option<RemoteFileEntry> {
+    let mut parts = line.trim_end_matches('\r').splitn(5, '|');
+    let permissions = parts.next()?.to_string();
+    let size = parts.next()?.parse().ok()?;
+    let modified = parts.next()?.parse().ok()?;
+    let kind = parts.next()?;
+    let path = parts.next()?.to_string();
+    let name = path
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or(&path)
+        .to_string();
+    Some(RemoteFileEntry {
+        name,
+        is_dir: kind == "directory",
+        is_symlink: kind == "symbolic link",
+        path,
+        size,
+        modified,
+        permissions,
+    })
+}
+
+/// 解析 `list_args` / `stat_args` 的输出，目录排在前面
+pub fn parse_entries(output: &str) -> Vec<RemoteFileEntry> {
+    let mut entries: Vec<RemoteFileEntry> = output.lines().filter_map(parse_line).collect();
+    entries.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    entries
+}
+
+/// 拒绝对根目录等危险路径执行删除
+pub fn validate_removable(path: &str) -> MdtResult<()> {
+    let trimmed = path.trim().trim_end_matches('/');
+    if trimmed.is_empty() || !path.trim().starts_with('/') {
+        return Err(MdtError::invalid_argument("path", "需要以 / 开头的完整路径，且不能为根目录"));
+    }
+    Ok(())
+}
+
+/// 校验 chmod 模式：八进制（如 755）或符号形式（如 u+x,go-w）
+pub fn validate_mode(mode: &str) -> MdtResult<()> {
+    let octal = !mode.is_empty()
+        && mode.len() <= 4
+        && mode.chars().all(|c| ('0'..='7').contains(&c));
+    let symbolic = !mode.is_empty()
+        && mode.split(',').all(|clause| {
+            let op = clause.find(['+', '-', '=']);
+            match op {
+                Some(index) => {
+                    clause[..index].chars().all(|c| "ugoa".contains(c))
+                        && clause[index + 1..].chars().all(|c| "rwxXst+-=".contains(c))
+                }
+                None => false,
+            }
+        });
+    if octal || symbolic {
+        Ok(())
+    } else {
+        Err(MdtError::invalid_argument("mode", format!("无效的权限模式: {}", mode)))
+    }
+}
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+use std::time::Duration;
+
+/// adbd 重启后等待设备重新上线的最长时间
+const ADBD_RESTART_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RootStatus {
+    /// 是否可通过 su 获得 root
+    pub su_available: bool,
+    pub su_path: Option<String>,
+    /// adbd 是否以 root 运行（`adb root` 后 shell 即为 uid 0）
+    pub adbd_root: bool,
+    /// user / userdebug / eng，仅后两者支持 `adb root`
+    pub build_type: Option<String>,
+    pub debuggable: bool,
+    /// dm-verity 模式：enforcing / logging / disabled
+    pub verity_mode: Option<String>,
+    /// green / yellow / orange（已解锁）/ red
+    pub verified_boot_state: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RootCommandResult {
+    pub success: bool,
+    /// 需要重启设备才能生效（如 remount 时关闭了 verity）
+    pub reboot_required: bool,
+    pub message: String,
+}
+
+/// 汇总 root 能力，供界面决定是否启用需要 root 的功能
+#[tauri::command]
+pub async fn adb_root_status(device_id: Option<String>) -> MdtResult<RootStatus> {
+    let uid = adb::adb_shell(&device_id, &["id", "-u"]).await?;
+    let su_path = adb::adb_shell(&device_id, &["command", "-v", "su"])
+        .await
+        .ok()
+        .map(|path| path.trim().to_string())
+        .filter(|path| path.starts_with('/'));
+    // su 存在但可能未授权 shell，需实际执行确认
+    let su_available = match su_path {
+        Some(_) => adb::adb_su(&device_id, "id -u")
+            .await
+            .map(|uid| uid.trim() == "0")
+            .unwrap_or(false),
+        None => false,
+    };
+    Ok(RootStatus {
+        su_available,
+        su_path,
+        adbd_root: uid.trim() == "0",
+        build_type: adb::adb_getprop(&device_id, "ro.build.type").await,
+        debuggable: adb::adb_getprop(&device_id, "ro.debuggable").await.as_deref() == Some("1"),
+        verity_mode: adb::adb_getprop(&device_id, "ro.boot.veritymode").await,
+        verified_boot_state: adb::adb_getprop(&device_id, "ro.boot.verifiedbootstate").await,
+    })
+}
+
+/// 执行会重启 adbd 的命令（root / unroot），返回合并后的输出
+async fn run_adbd_command(device_id: &Option<String>, subcommand: &str) -> MdtResult<String> {
+    let mut cmd = adb::adb_command(device_id);
+    cmd.arg(subcommand);
+    // 生产版本上 `adb root` 以非零状态退出，但输出仍需展示给用户
+    let output = tools::output(cmd).await.map_err(|e| MdtError::spawn("adb", e))?;
+    let message = format!(
+        "{}{}",
+        String::from_utf8_lossy(&output.stdout),
+        String::from_utf8_lossy(&output.stderr)
+    )
+    .trim()
+    .to_string();
+    Ok(message)
+}
+
+/// 等待 adbd 重启后设备重新上线
+async fn wait_for_device(device_id: &Option<String>) {
+    let mut cmd = adb::adb_command(device_id);
+    cmd.arg("wait-for-device");
+    let _ = tokio::time::timeout(ADBD_RESTART_TIMEOUT, tools::output(cmd)).await;
+}
+
+fn parse_root_output(message: String) -> RootCommandResult {
+    let lower = message.to_lowercase();
+    let success = !(lower.contains("cannot") || lower.contains("error") || lower.contains("failed"));
+    RootCommandResult {
+        success,
+        reboot_required: false,
+        message,
+    }
+}
+
+/// 以 root 重启 adbd（仅 userdebug / eng 版本）
+#[tauri::command]
+pub async fn adb_root(device_id: Option<String>) -> MdtResult<RootCommandResult> {
+    let _history = history::track();
+    let result = parse_root_output(run_adbd_command(&device_id, "root").await?);
+    if result.success {
+        wait_for_device(&device_id).await;
+    }
+    println!("[root] adb root: {}", result.message);
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn adb_unroot(device_id: Option<String>) -> MdtResult<RootCommandResult> {
+    let _history = history::track();
+    let result = parse_root_output(run_adbd_command(&device_id, "unroot").await?);
+    if result.success {
+        wait_for_device(&device_id).await;
+    }
+    println!("[root] adb unroot: {}", result.message);
+    Ok(result)
+}
+
+/// 以读写方式重新挂载系统分区；verity 开启时 adb 会自动关闭 verity 并提示重启
+#[tauri::command]
+pub async fn adb_remount(device_id: Option<String>) -> MdtResult<RootCommandResult> {
+    let _history = history::track();
+    let message = run_adbd_command(&device_id, "remount").await?;
+    let lower = message.to_lowercase();
+    let reboot_required = lower.contains("reboot");
+    let success = !["failed", "error", "not running as root", "cannot"]
+        .iter()
+        .any(|keyword| lower.contains(keyword));
+    println!("[root] adb remount: {}", message);
+    Ok(RootCommandResult {
+        success,
+        reboot_required,
+        message,
+    })
+}
@@ -0,0 +1,398 @@
+use crate::error::{MdtError, MdtResult};
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use image::codecs::png::PngEncoder;
+use image::codecs::webp::WebPEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView};
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::sync::{Mutex, OnceLock};
+
+const DEFAULT_QUALITY: u8 = 85;
+
+/// 截图输出格式
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ScreenshotFormat {
+    #[default]
+    Png,
+    Jpeg,
+    /// image crate 仅支持无损 WebP 编码，`quality` 对其无效
+    Webp,
+}
+
+impl ScreenshotFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ScreenshotFormat::Png => "png",
+            ScreenshotFormat::Jpeg => "jpg",
+            ScreenshotFormat::Webp => "webp",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ScreenshotFormat::Png => "image/png",
+            ScreenshotFormat::Jpeg => "image/jpeg",
+            ScreenshotFormat::Webp => "image/webp",
+        }
+    }
+}
+
+/// 截图后在本地进行的格式转换与缩放
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ScreenshotOptions {
+    pub format: ScreenshotFormat,
+    /// JPEG 质量 1-100，默认 85
+    pub quality: Option<u8>,
+    /// 长边的最大像素数，超出时等比缩小（不放大）
+    pub max_dimension: Option<u32>,
+}
+
+impl ScreenshotOptions {
+    fn validate(&self) -> MdtResult<()> {
+        if self.quality.is_some_and(|quality| quality == 0 || quality > 100) {
+            return Err(MdtError::invalid_argument("quality", "质量应在 1-100 之间"));
+        }
+        Ok(())
+    }
+}
+
+/// 转换后的截图数据
+pub struct EncodedScreenshot {
+    pub bytes: Vec<u8>,
+    pub width: u32,
+    pub height: u32,
+    pub original_width: u32,
+    pub original_height: u32,
+}
+
+/// 不落盘的截图预览，`data` 为 base64 编码的图片
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotPreview {
+    pub data: String,
+    pub mime_type: String,
+    pub width: u32,
+    pub height: u32,
+    /// 缩放前的原始尺寸
+    pub original_width: u32,
+    pub original_height: u32,
+}
+
+fn encode_image(image: &DynamicImage, options: &ScreenshotOptions) -> MdtResult<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let result = match options.format {
+        ScreenshotFormat::Png => image.write_with_encoder(PngEncoder::new(&mut bytes)),
+        // JPEG 不支持透明通道
+        ScreenshotFormat::Jpeg => DynamicImage::ImageRgb8(image.to_rgb8()).write_with_encoder(
+            JpegEncoder::new_with_quality(&mut bytes, options.quality.unwrap_or(DEFAULT_QUALITY)),
+        ),
+        ScreenshotFormat::Webp => DynamicImage::ImageRgba8(image.to_rgba8())
+            .write_with_encoder(WebPEncoder::new_lossless(&mut bytes)),
+    };
+    result.map_err(|e| MdtError::io("编码截图", e))?;
+    Ok(bytes)
+}
+
+fn encode_blocking(png: Vec<u8>, options: &ScreenshotOptions) -> MdtResult<EncodedScreenshot> {
+    let image = image::load_from_memory(&png).map_err(|e| MdtError::io("解析截图", e))?;
+    let (original_width, original_height) = image.dimensions();
+    let max_dimension = options
+        .max_dimension
+        .filter(|max| *max > 0 && original_width.max(original_height) > *max);
+
+    // PNG 且无需缩放时直接使用原始数据，避免重新编码
+    if options.format == ScreenshotFormat::Png && max_dimension.is_none() {
+        return Ok(EncodedScreenshot {
+            bytes: png,
+            width: original_width,
+            height: original_height,
+            original_width,
+            original_height,
+        });
+    }
+
+    let image = match max_dimension {
+        Some(max) => image.resize(max, max, FilterType::Triangle),
+        None => image,
+    };
+    Ok(EncodedScreenshot {
+        bytes: encode_image(&image, options)?,
+        width: image.width(),
+        height: image.height(),
+        original_width,
+        original_height,
+    })
+}
+
+/// 按选项转换设备截取的 PNG（格式、质量、缩放）
+pub async fn encode(png: Vec<u8>, options: ScreenshotOptions) -> MdtResult<EncodedScreenshot> {
+    options.validate()?;
+    tauri::async_runtime::spawn_blocking(move || encode_blocking(png, &options))
+        .await
+        .map_err(|e| MdtError::io("转换截图", e))?
+}
+
+/// 将截图转换后编码为 base64 预览
+pub async fn build_preview(png: Vec<u8>, options: ScreenshotOptions) -> MdtResult<ScreenshotPreview> {
+    let mime_type = options.format.mime_type().to_string();
+    let encoded = encode(png, options).await?;
+    Ok(ScreenshotPreview {
+        data: base64::engine::general_purpose::STANDARD.encode(encoded.bytes),
+        mime_type,
+        width: encoded.width,
+        height: encoded.height,
+        original_width: encoded.original_width,
+        original_height: encoded.original_height,
+    })
+}
+
+/// 将预览的 base64 数据写入本地文件
+pub fn write_preview(data: &str, path: &str) -> MdtResult<()> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(data.trim())
+        .map_err(|e| MdtError::invalid_argument("data", format!("无效的 base64 数据: {}", e)))?;
+    std::fs::write(path, bytes).map_err(|e| MdtError::io("写入截图文件", e))
+}
+
+/// 复用同一个剪贴板句柄：Linux 下剪贴板内容由持有句柄的进程提供，句柄释放后粘贴会失效
+fn host_clipboard() -> &'static Mutex<Option<arboard::Clipboard>> {
+    static CLIPBOARD: OnceLock<Mutex<Option<arboard::Clipboard>>> = OnceLock::new();
+    CLIPBOARD.get_or_init(|| Mutex::new(None))
+}
+
+fn copy_blocking(png: &[u8]) -> MdtResult<()> {
+    let image = image::load_from_memory(png).map_err(|e| MdtError::io("解析截图", e))?;
+    let rgba = image.to_rgba8();
+    let data = arboard::ImageData {
+        width: rgba.width() as usize,
+        height: rgba.height() as usize,
+        bytes: Cow::Owned(rgba.into_raw()),
+    };
+
+    let mut guard = host_clipboard().lock().map_err(|_| MdtError::lock("clipboard"))?;
+    if guard.is_none() {
+        *guard = Some(arboard::Clipboard::new().map_err(|e| MdtError::io("打开系统剪贴板", e))?);
+    }
+    let clipboard = guard.as_mut().ok_or_else(|| MdtError::io("打开系统剪贴板", "剪贴板不可用"))?;
+    clipboard.set_image(data).map_err(|e| MdtError::io("写入系统剪贴板", e))
+}
+
+/// 将截图以图片形式写入本机剪贴板
+pub async fn copy_to_host_clipboard(png: Vec<u8>) -> MdtResult<()> {
+    tauri::async_runtime::spawn_blocking(move || copy_blocking(&png))
+        .await
+        .map_err(|e| MdtError::io("复制截图到剪贴板", e))?
+}
+
+/// 重叠区域中匹配行的最低比例，低于该值视为两帧之间没有可靠的重叠
+const MIN_OVERLAP_MATCH: f64 = 0.9;
+
+fn row_hashes(image: &image::RgbaImage) -> Vec<u64> {
+    image
+        .rows()
+        .map(|row| {
+            // FNV-1a
+            row.flat_map(|pixel| pixel.0).fold(0xcbf29ce484222325u64, |hash, byte| {
+                (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+            })
+        })
+        .collect()
+}
+
+/// 在可滚动区域内寻找 `next` 相对 `previous` 的滚动距离（行数），没有可靠重叠时返回 None
+fn find_scroll_offset(previous: &[u64], next: &[u64]) -> Option<usize> {
+    let height = previous.len().min(next.len());
+    // 至少保留 1/8 高度的重叠用于比对
+    let min_overlap = (height / 8).max(1);
+    let mut best: Option<(usize, f64)> = None;
+    for offset in 0..height.saturating_sub(min_overlap) {
+        let overlap = height - offset;
+        let matched = (0..overlap).filter(|&row| previous[offset + row] == next[row]).count();
+        let ratio = matched as f64 / overlap as f64;
+        if best.is_none_or(|(_, best_ratio)| ratio > best_ratio) {
+            best = Some((offset, ratio));
+        }
+        if ratio >= 0.999 {
+            break;
+        }
+    }
+    best.filter(|(_, ratio)| *ratio >= MIN_OVERLAP_MATCH).map(|(offset, _)| offset)
+}
+
+/// 两帧之间首尾保持不变的行数（状态栏、导航栏等固定区域）
+fn fixed_margins(first: &[u64], second: &[u64]) -> (usize, usize) {
+    let height = first.len().min(second.len());
+    let top = (0..height).take_while(|&row| first[row] == second[row]).count();
+    if top == height {
+        return (height, 0);
+    }
+    let bottom = (0..height - top)
+        .take_while(|&row| first[first.len() - 1 - row] == second[second.len() - 1 - row])
+        .count();
+    (top, bottom)
+}
+
+fn stitch_blocking(frames: Vec<Vec<u8>>) -> MdtResult<(Vec<u8>, u32, u32)> {
+    let images: Vec<image::RgbaImage> = frames
+        .iter()
+        .map(|png| image::load_from_memory(png).map(|image| image.to_rgba8()))
+        .collect::<Result<_, _>>()
+        .map_err(|e| MdtError::io("解析截图", e))?;
+    let first = images.first().ok_or_else(|| MdtError::invalid_argument("frames", "没有可拼接的截图"))?;
+    let (width, height) = first.dimensions();
+    if images.iter().any(|image| image.dimensions() != (width, height)) {
+        return Err(MdtError::invalid_argument("frames", "截图尺寸不一致（滚动过程中屏幕可能发生了旋转）"));
+    }
+
+    let hashes: Vec<Vec<u64>> = images.iter().map(row_hashes).collect();
+    let (top, bottom) = match hashes.get(1) {
+        Some(second) => fixed_margins(&hashes[0], second),
+        None => (0, 0),
+    };
+    let content_end = height as usize - bottom;
+
+    // 每帧中需要追加的新内容：(帧序号, 起始行, 结束行)
+    let mut segments = vec![(0usize, 0usize, content_end)];
+    for index in 1..images.len() {
+        if top >= content_end {
+            break;
+        }
+        let previous = &hashes[index - 1][top..content_end];
+        let next = &hashes[index][top..content_end];
+        match find_scroll_offset(previous, next) {
+            // 画面没有移动，说明已经滚动到底
+            Some(0) => break,
+            Some(offset) => segments.push((index, content_end - offset, content_end)),
+            // 找不到重叠区域时整段追加会产生重复或断层，只保留已对齐的部分
+            None => {
+                println!("[screenshot] no overlap between frame {} and {}, stop stitching", index - 1, index);
+                break;
+            }
+        }
+    }
+    let last = segments.last().map(|(index, _, _)| *index).unwrap_or(0);
+    segments.push((last, content_end, height as usize));
+
+    let total_height: usize = segments.iter().map(|(_, start, end)| end - start).sum();
+    let mut output = image::RgbaImage::new(width, total_height as u32);
+    let mut y = 0u32;
+    for (index, start, end) in segments {
+        for row in start..end {
+            for x in 0..width {
+                output.put_pixel(x, y, *images[index].get_pixel(x, row as u32));
+            }
+            y += 1;
+        }
+    }
+
+    let mut bytes = Vec::new();
+    DynamicImage::ImageRgba8(output)
+        .write_with_encoder(PngEncoder::new(&mut bytes))
+        .map_err(|e| MdtError::io("编码长截图", e))?;
+    Ok((bytes, width, total_height as u32))
+}
+
+/// 将连续滚动截取的帧按重叠区域纵向拼接为一张 PNG，返回 (数据, 宽, 高)
+pub async fn stitch_vertical(frames: Vec<Vec<u8>>) -> MdtResult<(Vec<u8>, u32, u32)> {
+    tauri::async_runtime::spawn_blocking(move || stitch_blocking(frames))
+        .await
+        .map_err(|e| MdtError::io("拼接长截图", e))?
+}
+
+/// 默认的像素差异阈值（0-255，按亮度加权的颜色距离）
+const DEFAULT_DIFF_THRESHOLD: u8 = 16;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenshotDiff {
+    pub width: u32,
+    pub height: u32,
+    pub different_pixels: u64,
+    pub total_pixels: u64,
+    /// 差异像素占比（0-100）
+    pub diff_percent: f64,
+    /// 两张图尺寸不同，B 已缩放到 A 的尺寸后再比较
+    pub resized: bool,
+    /// 差异图：A 的灰度底图上以红色标出差异像素
+    pub diff_image_path: String,
+}
+
+/// 按人眼对 RGB 的敏感度加权的颜色距离，范围 0-255
+fn pixel_distance(a: &image::Rgba<u8>, b: &image::Rgba<u8>) -> f64 {
+    let channel = |i: usize| (a.0[i] as f64 - b.0[i] as f64).abs();
+    let alpha = channel(3);
+    (0.299 * channel(0) + 0.587 * channel(1) + 0.114 * channel(2)).max(alpha)
+}
+
+fn compare_blocking(path_a: &str, path_b: &str, threshold: u8, diff_path: &str) -> MdtResult<ScreenshotDiff> {
+    let open = |path: &str| {
+        image::open(path)
+            .map(|image| image.to_rgba8())
+            .map_err(|e| MdtError::invalid_argument("path", format!("无法读取图片 {}: {}", path, e)))
+    };
+    let a = open(path_a)?;
+    let mut b = open(path_b)?;
+    let (width, height) = a.dimensions();
+    let resized = b.dimensions() != (width, height);
+    if resized {
+        b = image::imageops::resize(&b, width, height, FilterType::Triangle);
+    }
+
+    let mut diff = image::RgbaImage::new(width, height);
+    let mut different_pixels = 0u64;
+    for (x, y, pixel_a) in a.enumerate_pixels() {
+        let pixel_b = b.get_pixel(x, y);
+        let output = if pixel_distance(pixel_a, pixel_b) > threshold as f64 {
+            different_pixels += 1;
+            image::Rgba([255, 0, 0, 255])
+        } else {
+            // 未变化区域淡化显示，便于定位差异
+            let luma = (0.299 * pixel_a.0[0] as f64 + 0.587 * pixel_a.0[1] as f64 + 0.114 * pixel_a.0[2] as f64) as u8;
+            let faded = 255 - (255 - luma) / 3;
+            image::Rgba([faded, faded, faded, 255])
+        };
+        diff.put_pixel(x, y, output);
+    }
+    DynamicImage::ImageRgba8(diff)
+        .save_with_format(diff_path, image::ImageFormat::Png)
+        .map_err(|e| MdtError::io("写入差异图", e))?;
+
+    let total_pixels = width as u64 * height as u64;
+    Ok(ScreenshotDiff {
+        width,
+        height,
+        different_pixels,
+        total_pixels,
+        diff_percent: if total_pixels == 0 {
+            0.0
+        } else {
+            different_pixels as f64 * 100.0 / total_pixels as f64
+        },
+        resized,
+        diff_image_path: diff_path.to_string(),
+    })
+}
+
+/// 比较两张截图，返回差异像素占比并生成差异图；`threshold` 为单像素容差（0-255，默认 16），
+/// 差异图默认保存为 A 同目录下的 `<A 文件名>_diff.png`
+#[tauri::command]
+pub async fn compare_screenshots(
+    path_a: String,
+    path_b: String,
+    threshold: Option<u8>,
+    diff_output_path: Option<String>,
+) -> MdtResult<ScreenshotDiff> {
+    let diff_path = diff_output_path.unwrap_or_else(|| {
+        let path = std::path::Path::new(&path_a);
+        let stem = path.file_stem().map(|stem| stem.to_string_lossy().to_string()).unwrap_or_default();
+        path.with_file_name(format!("{}_diff.png", stem)).to_string_lossy().to_string()
+    });
+    let threshold = threshold.unwrap_or(DEFAULT_DIFF_THRESHOLD);
+    tauri::async_runtime::spawn_blocking(move || compare_blocking(&path_a, &path_b, threshold, &diff_path))
+        .await
+        .map_err(|e| MdtError::io("比较截图", e))?
+}
@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::MdtResult;
+use crate::hdc;
+use std::time::SystemTime;
+
+/// 后台会话类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SessionKind {
+    /// 设备端 screenrecord / hdc 录屏
+    Screenrecord,
+    /// scrcpy 客户端录制到本地
+    ScrcpyRecord,
+    Mirror,
+}
+
+/// 正在运行的录屏或投屏会话，供前端刷新后恢复界面状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveSession {
+    /// `android` 或 `harmony`
+    pub platform: String,
+    /// 设备序列号，未指定设备时为 `default`
+    pub device: String,
+    pub kind: SessionKind,
+    /// 开始时间（秒级时间戳）
+    pub start_time: u64,
+    pub elapsed_secs: u64,
+    /// 录屏文件路径：设备端录制为设备上的路径，scrcpy 录制为本地路径
+    pub output: Option<String>,
+    /// 投屏流地址
+    pub url: Option<String>,
+}
+
+impl ActiveSession {
+    pub(crate) fn new(platform: &str, device: &str, kind: SessionKind, start_time: u64) -> Self {
+        let now = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(start_time);
+        Self {
+            platform: platform.to_string(),
+            device: device.to_string(),
+            kind,
+            start_time,
+            elapsed_secs: now.saturating_sub(start_time),
+            output: None,
+            url: None,
+        }
+    }
+}
+
+/// 列出所有正在运行的录屏与投屏会话，按开始时间排序
+#[tauri::command]
+pub async fn list_active_sessions() -> MdtResult<Vec<ActiveSession>> {
+    let mut sessions = adb::active_sessions()?;
+    sessions.extend(hdc::active_sessions()?);
+    sessions.sort_by_key(|session| session.start_time);
+    Ok(sessions)
+}
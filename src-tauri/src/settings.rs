@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+
+const NAMESPACES: &[&str] = &["system", "secure", "global"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingEntry {
+    pub key: String,
+    /// 按内容推断类型：整数 / 小数 / 字符串，`null` 为未设置
+    pub value: Value,
+    pub raw: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingDiff {
+    pub key: String,
+    pub value: Option<String>,
+    /// 系统默认值，未声明默认值的项为 None
+    pub default: Option<String>,
+    /// 最后写入该项的包名
+    pub package: Option<String>,
+}
+
+fn validate_namespace(namespace: &str) -> MdtResult<()> {
+    if NAMESPACES.contains(&namespace) {
+        Ok(())
+    } else {
+        Err(MdtError::invalid_argument("namespace", "命名空间需为 system / secure / global"))
+    }
+}
+
+fn validate_key(key: &str) -> MdtResult<()> {
+    if !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-' | ':')) {
+        Ok(())
+    } else {
+        Err(MdtError::invalid_argument("key", format!("无效的设置项: {}", key)))
+    }
+}
+
+fn typed_value(raw: Option<&str>) -> Value {
+    let Some(raw) = raw else {
+        return Value::Null;
+    };
+    if let Ok(number) = raw.parse::<i64>() {
+        return Value::from(number);
+    }
+    if let Ok(number) = raw.parse::<f64>() {
+        if number.is_finite() {
+            return Value::from(number);
+        }
+    }
+    Value::from(raw)
+}
+
+fn entry(key: &str, raw: Option<&str>) -> SettingEntry {
+    let raw = raw.filter(|value| *value != "null");
+    SettingEntry {
+        key: key.to_string(),
+        value: typed_value(raw),
+        raw: raw.map(str::to_string),
+    }
+}
+
+#[tauri::command]
+pub async fn adb_settings_list(device_id: Option<String>, namespace: String) -> MdtResult<Vec<SettingEntry>> {
+    validate_namespace(&namespace)?;
+    let output = adb::adb_shell(&device_id, &["settings", "list", &namespace]).await?;
+    let mut entries: Vec<SettingEntry> = output
+        .lines()
+        .filter_map(|line| {
+            let (key, value) = line.split_once('=')?;
+            Some(entry(key.trim(), Some(value)))
+        })
+        .collect();
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(entries)
+}
+
+#[tauri::command]
+pub async fn adb_settings_get(device_id: Option<String>, namespace: String, key: String) -> MdtResult<SettingEntry> {
+    validate_namespace(&namespace)?;
+    validate_key(&key)?;
+    let output = adb::adb_shell(&device_id, &["settings", "get", &namespace, &key]).await?;
+    Ok(entry(&key, Some(output.trim())))
+}
+
+/// 写入设置项，`value` 为 None 时删除该项
+#[tauri::command]
+pub async fn adb_settings_put(
+    device_id: Option<String>,
+    namespace: String,
+    key: String,
+    value: Option<String>,
+) -> MdtResult<SettingEntry> {
+    let _history = history::track();
+    validate_namespace(&namespace)?;
+    validate_key(&key)?;
+    match &value {
+        Some(value) => {
+            adb::adb_shell(&device_id, &["settings", "put", &namespace, &key, &tools::shell_quote(value)]).await?;
+        }
+        None => {
+            adb::adb_shell(&device_id, &["settings", "delete", &namespace, &key]).await?;
+        }
+    }
+    println!("[settings] {} {} -> {:?}", namespace, key, value);
+    adb_settings_get(device_id, namespace, key).await
+}
+
+/// 解析 `dumpsys settings` 中的 `_id:12 name:adb_enabled pkg:android value:1 default:1 ...`
+fn parse_dump_line(line: &str) -> Option<SettingDiff> {
+    let line = line.trim();
+    if !line.starts_with("_id:") {
+        return None;
+    }
+    // 值中可能包含空格，按已知字段名切分
+    let field = |name: &str| -> Option<String> {
+        let marker = format!(" {}:", name);
+        let start = line.find(&marker)? + marker.len();
+        let rest = &line[start..];
+        let end = [" name:", " pkg:", " value:", " default:", " defaultSystemSet:", " tag:"]
+            .iter()
+            .filter_map(|next| rest.find(next))
+            .min()
+            .unwrap_or(rest.len());
+        Some(rest[..end].trim().to_string()).filter(|v| v != "null")
+    };
+    Some(SettingDiff {
+        key: field("name")?,
+        value: field("value"),
+        default: field("default"),
+        package: field("pkg"),
+    })
+}
+
+/// 对比当前值与系统默认值，只返回被修改过的设置项（来自 `dumpsys settings`）
+#[tauri::command]
+pub async fn adb_settings_diff(device_id: Option<String>, namespace: String) -> MdtResult<Vec<SettingDiff>> {
+    validate_namespace(&namespace)?;
+    let output = adb::adb_shell(&device_id, &["dumpsys", "settings"]).await?;
+    let header = format!("{} SETTINGS", namespace.to_uppercase());
+    let mut in_section = false;
+    let mut diffs = Vec::new();
+    for line in output.lines() {
+        let trimmed = line.trim();
+        if trimmed.ends_with(')') && trimmed.contains(" SETTINGS (user") {
+            // 只取第一个用户（通常为当前用户）的小节
+            if in_section {
+                break;
+            }
+            in_section = trimmed.starts_with(&header);
+            continue;
+        }
+        if !in_section {
+            continue;
+        }
+        if let Some(diff) = parse_dump_line(trimmed) {
+            if diff.default.is_some() && diff.value != diff.default {
+                diffs.push(diff);
+            }
+        }
+    }
+    diffs.sort_by(|a, b| a.key.cmp(&b.key));
+    Ok(diffs)
+}
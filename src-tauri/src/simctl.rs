@@ -0,0 +1,130 @@
+use serde::{Deserialize, Serialize};
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+use std::path::Path;
+use std::process::Command;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Simulator {
+    pub udid: String,
+    pub name: String,
+    /// Booted / Shutdown / Booting 等
+    pub state: String,
+    /// 如 `iOS 17.2`
+    pub runtime: String,
+    pub available: bool,
+}
+
+/// `simctl list devices --json` 中的单个设备
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimctlDevice {
+    udid: String,
+    name: String,
+    state: String,
+    #[serde(default)]
+    is_available: bool,
+}
+
+#[derive(Deserialize)]
+struct SimctlDeviceList {
+    devices: std::collections::HashMap<String, Vec<SimctlDevice>>,
+}
+
+/// 构造 `xcrun simctl` 命令，仅 macOS 可用
+fn simctl_command(args: &[&str]) -> MdtResult<Command> {
+    if !cfg!(target_os = "macos") {
+        return Err(MdtError::ToolNotFound {
+            tool: "xcrun simctl".to_string(),
+        });
+    }
+    let mut cmd = tools::command_for("xcrun");
+    cmd.arg("simctl").args(args);
+    Ok(cmd)
+}
+
+async fn simctl_run(args: &[&str]) -> MdtResult<String> {
+    let output = tools::run_checked("xcrun", &None, simctl_command(args)?).await?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// 将 `com.apple.CoreSimulator.SimRuntime.iOS-17-2` 转换为 `iOS 17.2`
+fn runtime_name(identifier: &str) -> String {
+    let name = identifier.rsplit('.').next().unwrap_or(identifier);
+    match name.split_once('-') {
+        Some((os, version)) => format!("{} {}", os, version.replace('-', ".")),
+        None => name.to_string(),
+    }
+}
+
+#[tauri::command]
+pub async fn simctl_list_devices() -> MdtResult<Vec<Simulator>> {
+    let output = simctl_run(&["list", "devices", "--json"]).await?;
+    let list: SimctlDeviceList = serde_json::from_str(&output)
+        .map_err(|e| MdtError::io("解析 simctl 输出", e))?;
+    let mut simulators: Vec<Simulator> = list
+        .devices
+        .into_iter()
+        .flat_map(|(runtime, devices)| {
+            let runtime = runtime_name(&runtime);
+            devices.into_iter().map(move |device| Simulator {
+                udid: device.udid,
+                name: device.name,
+                state: device.state,
+                runtime: runtime.clone(),
+                available: device.is_available,
+            })
+        })
+        .collect();
+    simulators.sort_by(|a, b| a.runtime.cmp(&b.runtime).then(a.name.cmp(&b.name)));
+    Ok(simulators)
+}
+
+#[tauri::command]
+pub async fn simctl_boot(udid: String) -> MdtResult<()> {
+    let _history = history::track();
+    match simctl_run(&["boot", &udid]).await {
+        // 已启动时 simctl 返回错误，视为成功
+        Err(MdtError::CommandFailed { stderr, .. }) if stderr.contains("current state: Booted") => Ok(()),
+        other => other.map(|_| ()),
+    }
+}
+
+/// 关闭模拟器，`udid` 为 `all` 时关闭全部
+#[tauri::command]
+pub async fn simctl_shutdown(udid: String) -> MdtResult<()> {
+    let _history = history::track();
+    match simctl_run(&["shutdown", &udid]).await {
+        Err(MdtError::CommandFailed { stderr, .. }) if stderr.contains("current state: Shutdown") => Ok(()),
+        other => other.map(|_| ()),
+    }
+}
+
+/// 安装 .app 包（模拟器构建产物，为目录）
+#[tauri::command]
+pub async fn simctl_install(udid: String, app_path: String) -> MdtResult<()> {
+    let _history = history::track();
+    let path = Path::new(&app_path);
+    if !path.is_dir() || path.extension().is_none_or(|ext| ext != "app") {
+        return Err(MdtError::invalid_argument("appPath", "需要模拟器构建的 .app 目录"));
+    }
+    simctl_run(&["install", &udid, &app_path]).await?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn simctl_screenshot(udid: String, output_path: String) -> MdtResult<String> {
+    let _history = history::track();
+    simctl_run(&["io", &udid, "screenshot", &output_path]).await?;
+    Ok(output_path)
+}
+
+/// 在模拟器中打开 URL（网页或自定义 scheme 的深链接）
+#[tauri::command]
+pub async fn simctl_open_url(udid: String, url: String) -> MdtResult<()> {
+    let _history = history::track();
+    simctl_run(&["openurl", &udid, &url]).await?;
+    Ok(())
+}
@@ -0,0 +1,125 @@
+use crate::adb;
+use crate::error::MdtResult;
+use crate::hdc;
+use crate::tools;
+use serde::{Deserialize, Serialize};
+
+const ADB_USAGE_PATHS: &[&str] = &["/sdcard", "/data/local/tmp"];
+/// shell 用户无权读取，仅在设备已 root 时通过 su 统计
+const ADB_ROOT_USAGE_PATHS: &[&str] = &["/data/app", "/data/data"];
+const HDC_USAGE_PATHS: &[&str] = &[
+    "/storage/media/100/local/files",
+    "/data/app/el1/bundle/public",
+    "/data/local/tmp",
+];
+/// du 失败（多为部分子目录无权限）时追加的标记
+const DU_PARTIAL_MARKER: &str = "!partial";
+
+/// `df -h` 中的一个挂载点
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MountUsage {
+    pub filesystem: String,
+    pub size: String,
+    pub used: String,
+    pub available: String,
+    pub use_percent: Option<u8>,
+    pub mounted_on: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryUsage {
+    pub path: String,
+    /// `du -sh` 的大小，目录不存在或无权限时为 None
+    pub size: Option<String>,
+    /// 部分子目录无权限读取，大小可能偏小
+    pub partial: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StorageInfo {
+    pub mounts: Vec<MountUsage>,
+    pub directories: Vec<DirectoryUsage>,
+}
+
+/// 解析 `Filesystem Size Used Avail Use% Mounted on` 表格，跳过 tmpfs 等无实际容量的挂载
+fn parse_df(output: &str) -> Vec<MountUsage> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            if columns.len() < 6 {
+                return None;
+            }
+            let size = columns[1];
+            if size == "0" || size == "0K" {
+                return None;
+            }
+            Some(MountUsage {
+                filesystem: columns[0].to_string(),
+                size: size.to_string(),
+                used: columns[2].to_string(),
+                available: columns[3].to_string(),
+                use_percent: columns[4].trim_end_matches('%').parse().ok(),
+                mounted_on: columns[5..].join(" "),
+            })
+        })
+        .collect()
+}
+
+fn parse_du(path: &str, output: &str) -> DirectoryUsage {
+    let size = output
+        .lines()
+        .find_map(|line| line.split_once('\t').map(|(size, _)| size.trim().to_string()))
+        .filter(|size| !size.is_empty());
+    DirectoryUsage {
+        path: path.to_string(),
+        partial: size.is_some() && output.contains(DU_PARTIAL_MARKER),
+        size,
+    }
+}
+
+/// 路径末尾加 `/`，使 `/sdcard` 这类符号链接统计的是目标目录而不是链接本身
+fn du_script(path: &str) -> String {
+    let target = format!("{}/", path.trim_end_matches('/'));
+    format!("du -sh {} 2>/dev/null || echo {}", tools::shell_quote(&target), DU_PARTIAL_MARKER)
+}
+
+/// 设备存储占用：各挂载点的 df 与常见目录的 du，用于排查安装时空间不足
+#[tauri::command]
+pub async fn adb_storage_info(device_id: Option<String>) -> MdtResult<StorageInfo> {
+    let df = adb::adb_shell(&device_id, &["df", "-h"]).await?;
+    let mut directories = Vec::new();
+    for path in ADB_USAGE_PATHS {
+        let output = adb::adb_shell(&device_id, &[&du_script(path)]).await.unwrap_or_default();
+        directories.push(parse_du(path, &output));
+    }
+    for path in ADB_ROOT_USAGE_PATHS {
+        match adb::adb_su(&device_id, &du_script(path)).await {
+            Ok(output) => directories.push(parse_du(path, &output)),
+            // 未 root 时无法读取，其余目录也无需再尝试
+            Err(_) => break,
+        }
+    }
+    Ok(StorageInfo {
+        mounts: parse_df(&df),
+        directories,
+    })
+}
+
+#[tauri::command]
+pub async fn hdc_storage_info(device_id: Option<String>) -> MdtResult<StorageInfo> {
+    let df = hdc::hdc_shell(&device_id, &["df", "-h"]).await?;
+    let mut directories = Vec::new();
+    for path in HDC_USAGE_PATHS {
+        let output = hdc::hdc_shell(&device_id, &[&du_script(path)]).await.unwrap_or_default();
+        directories.push(parse_du(path, &output));
+    }
+    Ok(StorageInfo {
+        mounts: parse_df(&df),
+        directories,
+    })
+}
@@ -0,0 +1,46 @@
+use crate::error::{MdtError, MdtResult};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// 应用数据目录，启动时由 `setup` 写入
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+pub fn init(data_dir: PathBuf) {
+    println!("[store] data dir: {}", data_dir.display());
+    let _ = DATA_DIR.set(data_dir);
+}
+
+fn file_path(name: &str) -> MdtResult<PathBuf> {
+    DATA_DIR
+        .get()
+        .map(|dir| dir.join(format!("{}.json", name)))
+        .ok_or_else(|| MdtError::io("读取数据目录", "数据目录尚未初始化"))
+}
+
+/// 读取 `<数据目录>/<name>.json`，文件不存在或内容损坏时返回默认值
+pub fn load<T: DeserializeOwned + Default>(name: &str) -> T {
+    let Ok(path) = file_path(name) else {
+        return T::default();
+    };
+    match std::fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|err| {
+            println!("[store] ignore corrupted {}: {}", path.display(), err);
+            T::default()
+        }),
+        Err(_) => T::default(),
+    }
+}
+
+/// 写入 `<数据目录>/<name>.json`：先写临时文件再重命名，避免中途退出留下半截文件
+pub fn save<T: Serialize>(name: &str, value: &T) -> MdtResult<()> {
+    let path = file_path(name)?;
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| MdtError::io("创建数据目录", e))?;
+    }
+    let json = serde_json::to_vec_pretty(value).map_err(|e| MdtError::io("序列化配置", e))?;
+    let temp = path.with_extension("json.tmp");
+    std::fs::write(&temp, json).map_err(|e| MdtError::io("写入配置文件", e))?;
+    std::fs::rename(&temp, &path).map_err(|e| MdtError::io("保存配置文件", e))
+}
@@ -0,0 +1,415 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+use std::process::{ExitStatus, Output, Stdio};
+use std::time::Instant;
+use tauri::{AppHandle, Emitter};
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+/// monkey 输出行事件名
+pub const MONKEY_OUTPUT_EVENT: &str = "monkey-output";
+
+/// 每个崩溃 / ANR 最多保留的详情行数
+const MAX_ISSUE_LINES: usize = 40;
+
+/// 执行 `adb shell` 命令并逐行回调 stdout，返回退出状态与 stderr
+async fn stream_shell<F>(device_id: &Option<String>, args: &[&str], mut on_line: F) -> MdtResult<(ExitStatus, String)>
+where
+    F: FnMut(&str),
+{
+    let mut cmd = adb::adb_command(device_id);
+    cmd.arg("shell").args(args);
+    let invocation = history::capture(&cmd);
+    let started = Instant::now();
+    let mut child = tokio::process::Command::from(cmd)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| MdtError::spawn("adb", e))?;
+
+    let stderr_task = tools::drain_pipe(child.stderr.take());
+    if let Some(stdout) = child.stdout.take() {
+        let mut lines = BufReader::new(stdout).lines();
+        while let Some(line) = lines
+            .next_line()
+            .await
+            .map_err(|e| MdtError::io("读取命令输出", e))?
+        {
+            on_line(line.trim_end_matches('\r'));
+        }
+    }
+
+    let status = child.wait().await.map_err(|e| MdtError::io("等待命令结束", e))?;
+    let output = Output {
+        status,
+        stdout: Vec::new(),
+        stderr: stderr_task.await.unwrap_or_default(),
+    };
+    history::record(invocation, started.elapsed(), Ok(&output));
+    Ok((status, String::from_utf8_lossy(&output.stderr).trim().to_string()))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonkeyOutput {
+    pub device_id: Option<String>,
+    pub line: String,
+}
+
+/// monkey 检测到的崩溃或 ANR
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MonkeyIssue {
+    pub process: String,
+    pub pid: Option<u32>,
+    /// `// Long Msg:`、调用栈或 ANR 原因等详情行
+    pub details: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MonkeySummary {
+    pub package: String,
+    /// 用于复现的随机种子
+    pub seed: u64,
+    pub event_count: u32,
+    pub events_injected: Option<u32>,
+    pub elapsed_ms: Option<u64>,
+    pub crashes: Vec<MonkeyIssue>,
+    pub anrs: Vec<MonkeyIssue>,
+    /// monkey 是否正常跑完（输出了 `// Monkey finished`）
+    pub finished: bool,
+    /// monkey 因错误中止时的原因
+    pub aborted: Option<String>,
+}
+
+/// 解析 `com.demo (pid 1234)`
+fn parse_issue_head(rest: &str) -> MonkeyIssue {
+    let (process, pid) = match rest.split_once("(pid ") {
+        Some((process, pid)) => (process.trim(), pid.trim_end_matches(')').trim().parse().ok()),
+        None => (rest.trim(), None),
+    };
+    MonkeyIssue {
+        process: process.to_string(),
+        pid,
+        details: Vec::new(),
+    }
+}
+
+/// 逐行解析 monkey 输出
+#[derive(Default)]
+struct MonkeyParser {
+    summary: MonkeySummary,
+    /// 当前正在收集详情的问题：true 为崩溃，false 为 ANR
+    current: Option<bool>,
+}
+
+impl MonkeyParser {
+    fn push(&mut self, line: &str) {
+        let trimmed = line.trim();
+        if let Some(rest) = trimmed.strip_prefix("// CRASH:") {
+            self.summary.crashes.push(parse_issue_head(rest));
+            self.current = Some(true);
+            return;
+        }
+        if let Some(rest) = trimmed.strip_prefix("// NOT RESPONDING:") {
+            self.summary.anrs.push(parse_issue_head(rest));
+            self.current = Some(false);
+            return;
+        }
+
+        if let Some(is_crash) = self.current {
+            let issue = if is_crash {
+                self.summary.crashes.last_mut()
+            } else {
+                self.summary.anrs.last_mut()
+            };
+            // 崩溃详情以 `// ` 开头；ANR 详情为紧随其后的 Reason / Load 等行
+            let detail = if is_crash {
+                trimmed.strip_prefix("//").map(str::trim).filter(|d| !d.is_empty())
+            } else {
+                Some(trimmed).filter(|d| !d.is_empty() && !d.starts_with("//") && !d.starts_with(':'))
+            };
+            match (issue, detail) {
+                (Some(issue), Some(detail)) => {
+                    if issue.details.len() < MAX_ISSUE_LINES {
+                        issue.details.push(detail.to_string());
+                    }
+                    return;
+                }
+                _ => self.current = None,
+            }
+        }
+
+        if let Some(rest) = trimmed.strip_prefix("Events injected:") {
+            self.summary.events_injected = rest.trim().parse().ok();
+        } else if let Some(rest) = trimmed.strip_prefix("## Network stats: elapsed time=") {
+            self.summary.elapsed_ms = rest.split("ms").next().and_then(|ms| ms.trim().parse().ok());
+        } else if trimmed.starts_with("// Monkey finished") {
+            self.summary.finished = true;
+        } else if trimmed.starts_with("** ") && trimmed.contains("aborted") {
+            self.summary.aborted = Some(trimmed.trim_start_matches("** ").to_string());
+        }
+    }
+}
+
+/// 对指定应用执行 monkey 压力测试，逐行推送输出，返回崩溃 / ANR 汇总（含复现用的 seed）
+#[tauri::command]
+pub async fn adb_run_monkey(
+    app: AppHandle,
+    device_id: Option<String>,
+    package: String,
+    event_count: u32,
+    throttle: Option<u32>,
+    seed: Option<u64>,
+) -> MdtResult<MonkeySummary> {
+    let _history = history::track();
+    if event_count == 0 {
+        return Err(MdtError::invalid_argument("eventCount", "事件数必须大于 0"));
+    }
+    // 未指定 seed 时自动生成并返回，便于复现
+    let seed = seed.unwrap_or_else(tools::now_millis);
+    let seed_arg = seed.to_string();
+    let throttle_arg = throttle.unwrap_or(0).to_string();
+    let count_arg = event_count.to_string();
+    let package_arg = tools::shell_quote(&package);
+    let args = [
+        "monkey",
+        "-p",
+        &package_arg,
+        "-s",
+        &seed_arg,
+        "--throttle",
+        &throttle_arg,
+        "-v",
+        "-v",
+        &count_arg,
+    ];
+
+    println!("[monkey] running {} events on {} (seed {})", event_count, package, seed);
+    let mut parser = MonkeyParser::default();
+    let (status, stderr) = stream_shell(&device_id, &args, |line| {
+        parser.push(line);
+        let payload = MonkeyOutput {
+            device_id: device_id.clone(),
+            line: line.to_string(),
+        };
+        let _ = app.emit(MONKEY_OUTPUT_EVENT, payload);
+    })
+    .await?;
+
+    let mut summary = parser.summary;
+    // 出现崩溃 / ANR 时 monkey 以非零状态退出，此时仍返回汇总
+    if !status.success() && summary.crashes.is_empty() && summary.anrs.is_empty() && summary.aborted.is_none() {
+        let output = std::process::Output {
+            status,
+            stdout: Vec::new(),
+            stderr: stderr.into_bytes(),
+        };
+        return Err(MdtError::from_output(format!("adb shell {}", args.join(" ")), &device_id, &output));
+    }
+    summary.package = package;
+    summary.seed = seed;
+    summary.event_count = event_count;
+    Ok(summary)
+}
+
+/// 单个用例结束事件名
+pub const INSTRUMENTATION_PROGRESS_EVENT: &str = "instrumentation-progress";
+
+const DEFAULT_TEST_RUNNER: &str = "androidx.test.runner.AndroidJUnitRunner";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentationTestResult {
+    pub class_name: String,
+    pub test_name: String,
+    /// passed / failed / error / ignored / assumption_failed
+    pub status: String,
+    pub stack: Option<String>,
+    pub duration_ms: u64,
+    /// 当前序号与总数（来自 `current` / `numtests`）
+    pub current: Option<u32>,
+    pub total: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentationProgress {
+    pub device_id: Option<String>,
+    pub result: InstrumentationTestResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct InstrumentationSummary {
+    pub total: u32,
+    pub passed: u32,
+    pub failed: u32,
+    pub ignored: u32,
+    pub duration_ms: u64,
+    pub tests: Vec<InstrumentationTestResult>,
+    /// 运行器异常结束（如进程崩溃、找不到 runner）时的说明
+    pub error: Option<String>,
+    /// `INSTRUMENTATION_RESULT: stream=` 中的文本汇总
+    pub output: Option<String>,
+}
+
+fn instrumentation_status(code: i32) -> Option<&'static str> {
+    match code {
+        0 => Some("passed"),
+        -1 => Some("error"),
+        -2 => Some("failed"),
+        -3 => Some("ignored"),
+        -4 => Some("assumption_failed"),
+        _ => None,
+    }
+}
+
+/// 解析 `am instrument -r` 的原始输出：`INSTRUMENTATION_STATUS: key=value` 可跨多行，
+/// 以 `INSTRUMENTATION_STATUS_CODE` 结束一个状态块
+#[derive(Default)]
+struct InstrumentationParser {
+    summary: InstrumentationSummary,
+    fields: Vec<(String, String)>,
+    results: Vec<(String, String)>,
+    in_result: bool,
+    test_started: Option<std::time::Instant>,
+}
+
+impl InstrumentationParser {
+    fn field(&self, key: &str) -> Option<&str> {
+        self.fields.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    fn push_field(target: &mut Vec<(String, String)>, rest: &str) {
+        let (key, value) = rest.split_once('=').unwrap_or((rest, ""));
+        target.push((key.trim().to_string(), value.to_string()));
+    }
+
+    /// 处理一行输出，一个用例结束时返回其结果
+    fn push(&mut self, line: &str) -> Option<InstrumentationTestResult> {
+        if let Some(rest) = line.strip_prefix("INSTRUMENTATION_STATUS: ") {
+            self.in_result = false;
+            Self::push_field(&mut self.fields, rest);
+        } else if let Some(rest) = line.strip_prefix("INSTRUMENTATION_STATUS_CODE: ") {
+            let code: i32 = rest.trim().parse().unwrap_or(0);
+            let result = self.finish_status(code);
+            self.fields.clear();
+            return result;
+        } else if let Some(rest) = line.strip_prefix("INSTRUMENTATION_RESULT: ") {
+            self.in_result = true;
+            Self::push_field(&mut self.results, rest);
+        } else if let Some(rest) = line.strip_prefix("INSTRUMENTATION_CODE: ") {
+            // -1 为正常结束
+            if rest.trim() != "-1" && self.summary.error.is_none() {
+                self.summary.error = Some(format!("instrumentation 结束码 {}", rest.trim()));
+            }
+        } else if let Some(rest) = line.strip_prefix("INSTRUMENTATION_FAILED: ") {
+            self.summary.error = Some(format!("无法启动 instrumentation: {}", rest.trim()));
+        } else {
+            // 多行值（如 stack / stream）的续行
+            let target = if self.in_result {
+                self.results.last_mut()
+            } else {
+                self.fields.last_mut()
+            };
+            if let Some((_, value)) = target {
+                value.push('\n');
+                value.push_str(line);
+            }
+        }
+        None
+    }
+
+    fn finish_status(&mut self, code: i32) -> Option<InstrumentationTestResult> {
+        if code == 1 {
+            self.test_started = Some(std::time::Instant::now());
+            return None;
+        }
+        let status = instrumentation_status(code)?;
+        let result = InstrumentationTestResult {
+            class_name: self.field("class").unwrap_or_default().to_string(),
+            test_name: self.field("test").unwrap_or_default().to_string(),
+            status: status.to_string(),
+            stack: self.field("stack").map(|s| s.trim().to_string()).filter(|s| !s.is_empty()),
+            duration_ms: self
+                .test_started
+                .take()
+                .map(|start| start.elapsed().as_millis() as u64)
+                .unwrap_or(0),
+            current: self.field("current").and_then(|v| v.trim().parse().ok()),
+            total: self.field("numtests").and_then(|v| v.trim().parse().ok()),
+        };
+        self.summary.total += 1;
+        match status {
+            "passed" => self.summary.passed += 1,
+            "ignored" | "assumption_failed" => self.summary.ignored += 1,
+            _ => self.summary.failed += 1,
+        }
+        self.summary.tests.push(result.clone());
+        Some(result)
+    }
+
+    fn finish(mut self, duration_ms: u64) -> InstrumentationSummary {
+        for (key, value) in self.results.drain(..) {
+            let value = value.trim().to_string();
+            match key.as_str() {
+                "stream" if !value.is_empty() => self.summary.output = Some(value),
+                // 如 `shortMsg=Process crashed.`
+                "shortMsg" if self.summary.error.is_none() => self.summary.error = Some(value),
+                _ => {}
+            }
+        }
+        self.summary.duration_ms = duration_ms;
+        self.summary
+    }
+}
+
+/// 执行 `am instrument -w -r`，逐个用例推送进度事件并返回结果汇总
+#[tauri::command]
+pub async fn adb_run_instrumentation(
+    app: AppHandle,
+    device_id: Option<String>,
+    test_package: String,
+    runner: Option<String>,
+    class_filter: Option<String>,
+) -> MdtResult<InstrumentationSummary> {
+    let _history = history::track();
+    let runner = runner.unwrap_or_else(|| DEFAULT_TEST_RUNNER.to_string());
+    let component = tools::shell_quote(&format!("{}/{}", test_package, runner));
+    let class_arg = class_filter.as_deref().map(tools::shell_quote);
+    let mut args = vec!["am", "instrument", "-w", "-r"];
+    if let Some(class_arg) = class_arg.as_deref() {
+        args.extend(["-e", "class", class_arg]);
+    }
+    args.push(&component);
+
+    println!("[instrument] running {}/{}", test_package, runner);
+    let started = std::time::Instant::now();
+    let mut parser = InstrumentationParser::default();
+    let (status, stderr) = stream_shell(&device_id, &args, |line| {
+        if let Some(result) = parser.push(line) {
+            let payload = InstrumentationProgress {
+                device_id: device_id.clone(),
+                result,
+            };
+            let _ = app.emit(INSTRUMENTATION_PROGRESS_EVENT, payload);
+        }
+    })
+    .await?;
+
+    let summary = parser.finish(started.elapsed().as_millis() as u64);
+    if !status.success() && summary.total == 0 && summary.error.is_none() {
+        let output = std::process::Output {
+            status,
+            stdout: Vec::new(),
+            stderr: stderr.into_bytes(),
+        };
+        return Err(MdtError::from_output(format!("adb shell {}", args.join(" ")), &device_id, &output));
+    }
+    Ok(summary)
+}
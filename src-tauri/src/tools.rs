@@ -1,6 +1,13 @@
 use std::env;
+use std::io;
 use std::path::PathBuf;
-use std::process::Command;
+use std::process::{Command, Output};
+use std::time::{Instant, SystemTime};
+use tauri::async_runtime::JoinHandle;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use crate::config;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
 
 fn tool_filename(tool: &str) -> String {
     if cfg!(target_os = "windows") {
@@ -70,6 +77,10 @@ pub fn resolve_tool_path(tool: &str) -> Option<PathBuf> {
         return Some(path);
     }
 
+    if let Some(path) = config::tool_path(tool) {
+        return Some(path);
+    }
+
     for candidate in bundled_tool_candidates(tool) {
         if candidate.exists() {
             return Some(candidate);
@@ -87,3 +98,146 @@ pub fn command_for(tool: &str) -> Command {
     }
 }
 
+/// 构造 bundletool 命令：优先使用可执行的 bundletool，其次通过 `java -jar` 运行 bundletool.jar
+///
+/// `MDT_BUNDLETOOL_PATH` 可指向可执行文件或 jar 包。
+pub fn bundletool_command() -> Command {
+    let jar_command = |jar: PathBuf| {
+        let mut cmd = command_for("java");
+        cmd.arg("-jar").arg(jar);
+        cmd
+    };
+
+    if let Some(path) = env_override("bundletool") {
+        if path.extension().is_some_and(|ext| ext == "jar") {
+            return jar_command(path);
+        }
+        return Command::new(path);
+    }
+
+    if let Some(path) = resolve_tool_path("bundletool") {
+        return Command::new(path);
+    }
+
+    for candidate in bundled_tool_candidates("bundletool") {
+        let jar = candidate.with_file_name("bundletool.jar");
+        if jar.exists() {
+            return jar_command(jar);
+        }
+    }
+
+    Command::new("bundletool")
+}
+
+/// 在 tokio 运行时中异步执行命令并收集输出，避免长耗时命令阻塞其他 Tauri 命令；
+/// 在用户操作中执行时写入操作历史
+pub async fn output(cmd: Command) -> io::Result<Output> {
+    let invocation = history::capture(&cmd);
+    let started = Instant::now();
+    let result = tokio::process::Command::from(cmd).output().await;
+    history::record(invocation, started.elapsed(), result.as_ref().map_err(|e| e.to_string()));
+    result
+}
+
+/// 执行命令并检查退出状态，失败时按输出内容归类为结构化错误
+pub async fn run_checked(
+    tool: &str,
+    device_id: &Option<String>,
+    cmd: Command,
+) -> MdtResult<Output> {
+    let command = describe(tool, &cmd);
+    let output = output(cmd).await.map_err(|e| MdtError::spawn(tool, e))?;
+    if output.status.success() {
+        Ok(output)
+    } else {
+        Err(MdtError::from_output(command, device_id, &output))
+    }
+}
+
+/// 在后台读完子进程的输出管道，避免输出较多时子进程阻塞在写入上
+pub fn drain_pipe<R>(pipe: Option<R>) -> JoinHandle<Vec<u8>>
+where
+    R: AsyncRead + Unpin + Send + 'static,
+{
+    tauri::async_runtime::spawn(async move {
+        let mut buf = Vec::new();
+        if let Some(mut pipe) = pipe {
+            let _ = pipe.read_to_end(&mut buf).await;
+        }
+        buf
+    })
+}
+
+/// 隐藏后的参数值
+pub const REDACTED: &str = "***";
+
+/// 紧跟在这些参数序列之后的一个参数为用户输入的文本、剪贴板内容或代理地址
+const SENSITIVE_ARG_PREFIXES: &[&[&str]] = &[
+    &["input", "text"],
+    &["uiInput", "text"],
+    &["ADB_INPUT_B64", "--es", "msg"],
+    &["clipboard", "set-primary-clip"],
+    &["global", "http_proxy"],
+];
+
+/// 隐藏命令参数中的敏感内容，用于错误信息与操作历史
+pub fn redact_args(args: &[String]) -> Vec<String> {
+    args.iter()
+        .enumerate()
+        .map(|(index, arg)| {
+            let follows_sensitive = SENSITIVE_ARG_PREFIXES.iter().any(|prefix| {
+                index >= prefix.len()
+                    && args[index - prefix.len()..index].iter().zip(prefix.iter()).all(|(a, b)| a == b)
+            });
+            if follows_sensitive {
+                return REDACTED.to_string();
+            }
+            // 隐藏 bundletool 等工具的 `--ks-pass=pass:xxx` 密码参数
+            match arg.split_once("pass:") {
+                Some((prefix, _)) => format!("{}pass:{}", prefix, REDACTED),
+                None => arg.clone(),
+            }
+        })
+        .collect()
+}
+
+/// 生成用于错误信息的命令描述，如 `adb -s <device> shell ls`
+pub fn describe(tool: &str, cmd: &Command) -> String {
+    let args: Vec<String> = cmd.get_args().map(|arg| arg.to_string_lossy().to_string()).collect();
+    format!("{} {}", tool, redact_args(&args).join(" "))
+}
+
+/// 为设备端 shell 参数加单引号，避免路径中的空格和特殊字符被解析
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+/// 当前毫秒时间戳
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// 会话表的键，未指定设备时为 `default`
+pub fn device_key(device_id: &Option<String>) -> String {
+    device_id.clone().unwrap_or_else(|| "default".to_string())
+}
+
+/// 使用系统默认程序打开本地文件或目录
+pub fn open_path(path: &str) -> MdtResult<()> {
+    let mut cmd = if cfg!(target_os = "windows") {
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", "start", ""]);
+        cmd
+    } else if cfg!(target_os = "macos") {
+        Command::new("open")
+    } else {
+        Command::new("xdg-open")
+    };
+    cmd.arg(path);
+    cmd.spawn()
+        .map(|_| ())
+        .map_err(|e| MdtError::io("打开文件", e))
+}
@@ -0,0 +1,285 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+const DEFAULT_TRACE_CATEGORIES: [&str; 6] = ["gfx", "view", "input", "sched", "freq", "am"];
+/// perfetto 输出目录（Android 9+ 中 shell 用户可写，且 traced 有权限访问）
+const PERFETTO_TRACE_DIR: &str = "/data/misc/perfetto-traces";
+
+/// 抓取 trace 的参数
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TraceConfig {
+    /// atrace 类别，如 gfx / view / sched，未指定时使用常用类别
+    pub categories: Vec<String>,
+    /// 仅抓取指定应用的 atrace 标记
+    pub app: Option<String>,
+    /// 缓冲区大小（MB）
+    pub buffer_size_mb: Option<u32>,
+    /// 最长抓取时长（秒），到时 perfetto 自动结束
+    pub max_duration_secs: Option<u32>,
+}
+
+enum TraceMode {
+    Perfetto { pid: String },
+    Atrace,
+}
+
+struct TraceSession {
+    mode: TraceMode,
+    remote_path: String,
+}
+
+fn trace_sessions() -> &'static Mutex<HashMap<String, TraceSession>> {
+    static STORE: OnceLock<Mutex<HashMap<String, TraceSession>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+fn validate_categories(categories: &[String]) -> MdtResult<()> {
+    for category in categories {
+        if category.is_empty() || !category.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(MdtError::invalid_argument(
+                "categories",
+                format!("无效的 trace 类别: {}", category),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 开始抓取系统 trace：优先使用 perfetto 后台模式，不支持时（Android 8 及以下）退回 atrace
+#[tauri::command]
+pub async fn adb_start_trace(device_id: Option<String>, config: Option<TraceConfig>) -> MdtResult<()> {
+    let _history = history::track();
+    let device_key = tools::device_key(&device_id);
+    if trace_sessions()
+        .lock()
+        .map_err(|_| MdtError::lock("trace"))?
+        .contains_key(&device_key)
+    {
+        return Err(MdtError::session_exists("trace", &device_key));
+    }
+
+    let config = config.unwrap_or_default();
+    validate_categories(&config.categories)?;
+    let categories: Vec<String> = if config.categories.is_empty() {
+        DEFAULT_TRACE_CATEGORIES.iter().map(|c| c.to_string()).collect()
+    } else {
+        config.categories.clone()
+    };
+    let buffer = format!("{}mb", config.buffer_size_mb.unwrap_or(32));
+    let duration = format!("{}s", config.max_duration_secs.unwrap_or(600));
+    let app = config.app.as_deref().map(tools::shell_quote);
+
+    let remote_path = format!("{}/mdt_{}.perfetto-trace", PERFETTO_TRACE_DIR, tools::now_millis());
+    let mut args = vec!["perfetto", "--background", "-o", &remote_path, "-t", &duration, "-b", &buffer];
+    if let Some(app) = &app {
+        args.extend(["-a", app.as_str()]);
+    }
+    args.extend(categories.iter().map(String::as_str));
+
+    let session = match adb::adb_shell(&device_id, &args).await {
+        // --background 模式输出后台进程的 PID
+        Ok(output) if output.lines().last().is_some_and(|pid| pid.trim().parse::<u32>().is_ok()) => {
+            TraceSession {
+                mode: TraceMode::Perfetto {
+                    pid: output.lines().last().unwrap_or_default().trim().to_string(),
+                },
+                remote_path,
+            }
+        }
+        _ => {
+            let buffer_kb = (config.buffer_size_mb.unwrap_or(32) * 1024).to_string();
+            let mut args = vec!["atrace", "--async_start", "-b", &buffer_kb];
+            if let Some(app) = &app {
+                args.extend(["-a", app.as_str()]);
+            }
+            args.extend(categories.iter().map(String::as_str));
+            adb::adb_shell(&device_id, &args).await?;
+            TraceSession {
+                mode: TraceMode::Atrace,
+                remote_path: format!("/data/local/tmp/mdt_{}.atrace", tools::now_millis()),
+            }
+        }
+    };
+
+    let mut store = trace_sessions().lock().map_err(|_| MdtError::lock("trace"))?;
+    if store.contains_key(&device_key) {
+        return Err(MdtError::session_exists("trace", &device_key));
+    }
+    store.insert(device_key, session);
+    Ok(())
+}
+
+/// 停止抓取并把 trace 文件拉取到 `output_path`，`open` 为 true 时用系统默认程序打开
+#[tauri::command]
+pub async fn adb_stop_trace(
+    device_id: Option<String>,
+    output_path: String,
+    open: Option<bool>,
+) -> MdtResult<String> {
+    let _history = history::track();
+    let device_key = tools::device_key(&device_id);
+    let session = trace_sessions()
+        .lock()
+        .map_err(|_| MdtError::lock("trace"))?
+        .remove(&device_key)
+        .ok_or_else(|| MdtError::session_not_found("trace", &device_key))?;
+
+    match &session.mode {
+        TraceMode::Perfetto { pid } => {
+            // SIGTERM 后 perfetto 会写完缓冲区再退出，等待进程结束
+            let _ = adb::adb_shell(&device_id, &["kill", "-TERM", pid]).await;
+            for _ in 0..40 {
+                let alive = adb::adb_shell(&device_id, &["kill", "-0", pid]).await.is_ok();
+                if !alive {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(250)).await;
+            }
+        }
+        TraceMode::Atrace => {
+            adb::adb_shell(
+                &device_id,
+                &["atrace", "--async_stop", "-z", "-o", &session.remote_path],
+            )
+            .await?;
+        }
+    }
+
+    if let Some(parent) = Path::new(&output_path).parent() {
+        if !parent.as_os_str().is_empty() {
+            std::fs::create_dir_all(parent).map_err(|e| MdtError::io("创建输出目录", e))?;
+        }
+    }
+    let pulled = adb::adb_run(&device_id, &["pull", &session.remote_path, &output_path]).await;
+    let _ = adb::adb_shell(&device_id, &["rm", "-f", &session.remote_path]).await;
+    pulled?;
+
+    if open.unwrap_or(false) {
+        tools::open_path(&output_path)?;
+    }
+    Ok(output_path)
+}
+
+const MAX_SIMPLEPERF_DURATION_SECS: u32 = 600;
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimpleperfResult {
+    pub perf_data_path: String,
+    pub report_path: Option<String>,
+}
+
+/// 使用主机端 report_html.py（来自 NDK simpleperf 目录，通过 `MDT_REPORT_HTML_PATH` 或 PATH 查找）生成 HTML 报告
+async fn simpleperf_report_html(perf_data: &Path, output: &Path) -> MdtResult<()> {
+    let script = tools::resolve_tool_path("report_html.py")
+        .or_else(|| tools::resolve_tool_path("report_html"))
+        .ok_or_else(|| MdtError::ToolNotFound {
+            tool: "report_html.py".to_string(),
+        })?;
+    let mut cmd = if script.extension().is_some_and(|ext| ext == "py") {
+        let mut cmd = tools::command_for("python3");
+        cmd.arg(&script);
+        cmd
+    } else {
+        std::process::Command::new(&script)
+    };
+    cmd.arg("-i").arg(perf_data).arg("-o").arg(output).arg("--no_browser");
+    tools::run_checked("report_html.py", &None, cmd).await?;
+    Ok(())
+}
+
+/// 对指定应用执行 `simpleperf record`，拉取 perf.data，可选生成文本（设备端 report）或 HTML 报告
+#[tauri::command]
+pub async fn adb_simpleperf_record(
+    device_id: Option<String>,
+    package: String,
+    duration_secs: u32,
+    output_dir: String,
+    report: Option<String>,
+) -> MdtResult<SimpleperfResult> {
+    let _history = history::track();
+    if duration_secs == 0 || duration_secs > MAX_SIMPLEPERF_DURATION_SECS {
+        return Err(MdtError::invalid_argument(
+            "durationSecs",
+            format!("时长需在 1 到 {} 秒之间", MAX_SIMPLEPERF_DURATION_SECS),
+        ));
+    }
+    if let Some(kind) = report.as_deref() {
+        if kind != "text" && kind != "html" {
+            return Err(MdtError::invalid_argument("report", format!("不支持的报告格式: {}", kind)));
+        }
+    }
+    std::fs::create_dir_all(&output_dir).map_err(|e| MdtError::io("创建输出目录", e))?;
+
+    let stamp = tools::now_millis();
+    let remote_data = format!("/data/local/tmp/mdt_{}.perf.data", stamp);
+    let duration = duration_secs.to_string();
+    adb::adb_shell(
+        &device_id,
+        &[
+            "simpleperf",
+            "record",
+            "--app",
+            &tools::shell_quote(&package),
+            "-g",
+            "--duration",
+            &duration,
+            "-o",
+            &remote_data,
+        ],
+    )
+    .await?;
+
+    let local_data = Path::new(&output_dir).join(format!("{}_{}.perf.data", package, stamp));
+    let local_data_str = local_data.to_string_lossy().to_string();
+    let pulled = adb::adb_run(&device_id, &["pull", &remote_data, &local_data_str]).await;
+
+    let mut report_path = None;
+    if pulled.is_ok() && report.as_deref() == Some("text") {
+        let remote_report = format!("/data/local/tmp/mdt_{}.report.txt", stamp);
+        let local_report = Path::new(&output_dir).join(format!("{}_{}.report.txt", package, stamp));
+        let local_report_str = local_report.to_string_lossy().to_string();
+        let generated = adb::adb_shell(
+            &device_id,
+            &[
+                "simpleperf",
+                "report",
+                "-i",
+                &remote_data,
+                "--sort",
+                "comm,dso,symbol",
+                "-o",
+                &remote_report,
+            ],
+        )
+        .await;
+        let result = match generated {
+            Ok(_) => adb::adb_run(&device_id, &["pull", &remote_report, &local_report_str]).await,
+            Err(e) => Err(e),
+        };
+        let _ = adb::adb_shell(&device_id, &["rm", "-f", &remote_report]).await;
+        result?;
+        report_path = Some(local_report_str);
+    }
+    let _ = adb::adb_shell(&device_id, &["rm", "-f", &remote_data]).await;
+    pulled?;
+
+    if report.as_deref() == Some("html") {
+        let local_report = Path::new(&output_dir).join(format!("{}_{}.report.html", package, stamp));
+        simpleperf_report_html(&local_data, &local_report).await?;
+        report_path = Some(local_report.to_string_lossy().to_string());
+    }
+
+    Ok(SimpleperfResult {
+        perf_data_path: local_data_str,
+        report_path,
+    })
+}
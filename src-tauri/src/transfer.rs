@@ -0,0 +1,604 @@
+use serde::{Deserialize, Serialize};
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::{
+    atomic::{AtomicBool, AtomicU64, Ordering},
+    Arc, Mutex, OnceLock,
+};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Emitter};
+
+/// 文件传输进度事件名
+pub const TRANSFER_PROGRESS_EVENT: &str = "transfer-progress";
+/// 目录传输整体进度事件名
+pub const DIRECTORY_TRANSFER_PROGRESS_EVENT: &str = "directory-transfer-progress";
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferProgress {
+    pub transfer_id: String,
+    pub device_id: Option<String>,
+    pub direction: String, // "push" | "pull"
+    pub local_path: String,
+    pub remote_path: String,
+    pub transferred_bytes: u64,
+    pub total_bytes: Option<u64>,
+    pub percent: Option<f64>,
+    pub status: String, // "running" | "completed" | "failed" | "cancelled"
+}
+
+/// 单次传输的描述信息
+pub struct TransferRequest {
+    pub transfer_id: String,
+    pub device_id: Option<String>,
+    pub tool: &'static str,
+    pub direction: &'static str,
+    pub local_path: String,
+    pub remote_path: String,
+    pub total_bytes: Option<u64>,
+}
+
+/// 统计已传输字节数的方式
+pub enum SizeProbe {
+    /// 拉取时读取本地目标文件大小
+    Local(PathBuf),
+    /// 推送时在设备端查询目标文件大小，闭包返回查询命令（输出为字节数）
+    Remote(Box<dyn Fn() -> Command + Send + Sync>),
+}
+
+impl SizeProbe {
+    async fn current_size(&self) -> Option<u64> {
+        match self {
+            SizeProbe::Local(path) => std::fs::metadata(path).ok().map(|m| m.len()),
+            SizeProbe::Remote(build) => {
+                // 进度轮询不经过 `tools::output`，避免写入操作历史
+                let output = tokio::process::Command::from(build()).output().await.ok()?;
+                if !output.status.success() {
+                    return None;
+                }
+                String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+            }
+        }
+    }
+}
+
+fn transfers() -> &'static Mutex<HashMap<String, Arc<AtomicBool>>> {
+    static STORE: OnceLock<Mutex<HashMap<String, Arc<AtomicBool>>>> = OnceLock::new();
+    STORE.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 生成传输 ID，前端未指定时使用
+pub fn new_transfer_id() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let timestamp = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|d| d.as_millis())
+        .unwrap_or(0);
+    format!(
+        "transfer-{}-{}",
+        timestamp,
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+fn emit_progress(app: &AppHandle, request: &TransferRequest, transferred: u64, status: &str) {
+    let percent = request
+        .total_bytes
+        .filter(|total| *total > 0)
+        .map(|total| (transferred as f64 / total as f64 * 100.0).min(100.0));
+    let payload = TransferProgress {
+        transfer_id: request.transfer_id.clone(),
+        device_id: request.device_id.clone(),
+        direction: request.direction.to_string(),
+        local_path: request.local_path.clone(),
+        remote_path: request.remote_path.clone(),
+        transferred_bytes: transferred,
+        total_bytes: request.total_bytes,
+        percent,
+        status: status.to_string(),
+    };
+    let _ = app.emit(TRANSFER_PROGRESS_EVENT, payload);
+}
+
+fn register_transfer(transfer_id: &str) -> MdtResult<Arc<AtomicBool>> {
+    let cancel_flag = Arc::new(AtomicBool::new(false));
+    transfers()
+        .lock()
+        .map_err(|_| MdtError::lock("transfer"))?
+        .insert(transfer_id.to_string(), cancel_flag.clone());
+    Ok(cancel_flag)
+}
+
+fn unregister_transfer(transfer_id: &str) {
+    if let Ok(mut store) = transfers().lock() {
+        store.remove(transfer_id);
+    }
+}
+
+/// 执行传输命令，期间定时发送进度事件，并支持通过 `cancel_transfer` 取消
+pub async fn run_transfer(
+    app: &AppHandle,
+    request: TransferRequest,
+    cmd: Command,
+    probe: SizeProbe,
+) -> MdtResult<String> {
+    let cancel_flag = register_transfer(&request.transfer_id)?;
+
+    emit_progress(app, &request, 0, "running");
+    let mut transferred = 0u64;
+    let result = drive_transfer(app, &request, cmd, &probe, &cancel_flag, &mut transferred).await;
+
+    unregister_transfer(&request.transfer_id);
+
+    match &result {
+        Ok(_) => {
+            let total = request.total_bytes.unwrap_or(transferred);
+            emit_progress(app, &request, total, "completed");
+        }
+        Err(MdtError::Cancelled { .. }) => emit_progress(app, &request, transferred, "cancelled"),
+        Err(_) => emit_progress(app, &request, transferred, "failed"),
+    }
+    result
+}
+
+async fn drive_transfer(
+    app: &AppHandle,
+    request: &TransferRequest,
+    cmd: Command,
+    probe: &SizeProbe,
+    cancel_flag: &AtomicBool,
+    transferred: &mut u64,
+) -> MdtResult<String> {
+    let command = tools::describe(request.tool, &cmd);
+    let invocation = history::capture(&cmd);
+    let started = std::time::Instant::now();
+    let mut child = tokio::process::Command::from(cmd)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|e| MdtError::spawn(request.tool, e))?;
+
+    // 传输期间持续读取输出，避免输出超过管道缓冲区时子进程阻塞在写入上
+    let stdout_task = tools::drain_pipe(child.stdout.take());
+    let stderr_task = tools::drain_pipe(child.stderr.take());
+
+    let status = loop {
+        match tokio::time::timeout(PROGRESS_INTERVAL, child.wait()).await {
+            Ok(status) => break status.map_err(|e| MdtError::io("等待传输进程", e))?,
+            Err(_) => {
+                if cancel_flag.load(Ordering::SeqCst) {
+                    let _ = child.kill().await;
+                    // 拉取时删除本地的不完整文件；推送时设备端文件无法统一清理，在错误中注明
+                    let operation = match probe {
+                        SizeProbe::Local(path) => {
+                            let _ = std::fs::remove_file(path);
+                            format!("传输 {}", request.transfer_id)
+                        }
+                        SizeProbe::Remote(_) => format!(
+                            "传输 {}（设备端 {} 可能不完整）",
+                            request.transfer_id, request.remote_path
+                        ),
+                    };
+                    return Err(MdtError::Cancelled { operation });
+                }
+                if let Some(size) = probe.current_size().await {
+                    *transferred = size;
+                    emit_progress(app, request, size, "running");
+                }
+            }
+        }
+    };
+
+    let stdout = stdout_task.await.unwrap_or_default();
+    let stderr = stderr_task.await.unwrap_or_default();
+    let output = Output {
+        status,
+        stdout,
+        stderr,
+    };
+    history::record(invocation, started.elapsed(), Ok(&output));
+
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        Err(MdtError::from_output(command, &request.device_id, &output))
+    }
+}
+
+/// 目录传输中的单个文件
+pub struct DirectoryEntry {
+    pub relative_path: String,
+    pub local_path: PathBuf,
+    pub remote_path: String,
+    pub size: u64,
+}
+
+/// 本地文件信息：相对路径、绝对路径、大小、修改时间（秒）
+pub struct LocalFile {
+    pub relative_path: String,
+    pub path: PathBuf,
+    pub size: u64,
+    pub modified: u64,
+}
+
+/// 设备端文件的大小与修改时间（秒）
+pub struct RemoteFile {
+    pub size: u64,
+    pub modified: u64,
+}
+
+pub struct DirectoryTransferRequest {
+    pub transfer_id: String,
+    pub device_id: Option<String>,
+    pub direction: &'static str,
+    pub local_dir: String,
+    pub remote_dir: String,
+    pub skipped: Vec<String>,
+    pub parallel: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryTransferFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryTransferProgress {
+    pub transfer_id: String,
+    pub device_id: Option<String>,
+    pub direction: String,
+    pub local_dir: String,
+    pub remote_dir: String,
+    pub total_files: usize,
+    pub completed_files: usize,
+    pub skipped_files: usize,
+    pub failed_files: usize,
+    pub total_bytes: u64,
+    pub transferred_bytes: u64,
+    pub current_file: Option<String>,
+    pub status: String, // "running" | "completed" | "failed" | "cancelled"
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DirectoryTransferResult {
+    pub transfer_id: String,
+    pub transferred: Vec<String>,
+    pub skipped: Vec<String>,
+    pub failed: Vec<DirectoryTransferFailure>,
+    pub transferred_bytes: u64,
+    pub cancelled: bool,
+}
+
+/// 文件修改时间（Unix 秒）
+pub fn modified_secs(metadata: &std::fs::Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+/// 递归列出本地目录下的所有文件
+pub fn walk_local_dir(root: &Path) -> MdtResult<Vec<LocalFile>> {
+    if !root.is_dir() {
+        return Err(MdtError::invalid_argument(
+            "localDir",
+            format!("{} 不是目录", root.display()),
+        ));
+    }
+
+    let mut files = Vec::new();
+    let mut pending = vec![root.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = std::fs::read_dir(&dir).map_err(|e| MdtError::io("读取本地目录", e))?;
+        for entry in entries {
+            let entry = entry.map_err(|e| MdtError::io("读取本地目录", e))?;
+            let path = entry.path();
+            let metadata = entry.metadata().map_err(|e| MdtError::io("读取文件信息", e))?;
+            if metadata.is_dir() {
+                pending.push(path);
+                continue;
+            }
+            let relative_path = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push(LocalFile {
+                relative_path,
+                size: metadata.len(),
+                modified: modified_secs(&metadata),
+                path,
+            });
+        }
+    }
+    files.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
+    Ok(files)
+}
+
+/// 设备端列出目录文件的 shell 参数，输出格式为 `<size> <mtime> <path>`
+pub fn remote_listing_args(remote_dir: &str) -> Vec<String> {
+    vec![
+        "find".to_string(),
+        tools::shell_quote(remote_dir),
+        "-type".to_string(),
+        "f".to_string(),
+        "-exec".to_string(),
+        "stat".to_string(),
+        "-c".to_string(),
+        "'%s %Y %n'".to_string(),
+        "{}".to_string(),
+        "+".to_string(),
+    ]
+}
+
+/// 解析 `remote_listing_args` 的输出，键为相对 `remote_dir` 的路径
+pub fn parse_remote_listing(remote_dir: &str, output: &str) -> HashMap<String, RemoteFile> {
+    let prefix = format!("{}/", remote_dir.trim_end_matches('/'));
+    let mut files = HashMap::new();
+    for line in output.lines() {
+        let mut parts = line.trim_end().splitn(3, ' ');
+        let (Some(size), Some(modified), Some(path)) = (parts.next(), parts.next(), parts.next())
+        else {
+            continue;
+        };
+        let (Ok(size), Ok(modified)) = (size.parse::<u64>(), modified.parse::<u64>()) else {
+            continue;
+        };
+        let relative = path.strip_prefix(&prefix).unwrap_or(path).to_string();
+        files.insert(relative, RemoteFile { size, modified });
+    }
+    files
+}
+
+/// 大小一致且目标端不比源端旧时视为未变化
+pub fn is_unchanged(source_size: u64, source_modified: u64, target: Option<&RemoteFile>) -> bool {
+    target
+        .map(|t| t.size == source_size && t.modified >= source_modified)
+        .unwrap_or(false)
+}
+
+struct DirectoryTransferState {
+    progress: DirectoryTransferProgress,
+    result: DirectoryTransferResult,
+}
+
+/// 为单个文件构建传输命令与进度统计方式
+pub type EntryCommandBuilder = dyn Fn(&DirectoryEntry) -> (Command, SizeProbe) + Send + Sync;
+
+/// 按给定并发度逐个传输目录中的文件，发送单文件与整体进度事件
+pub async fn run_directory_transfer(
+    app: &AppHandle,
+    request: DirectoryTransferRequest,
+    tool: &'static str,
+    entries: Vec<DirectoryEntry>,
+    build: Arc<EntryCommandBuilder>,
+) -> MdtResult<DirectoryTransferResult> {
+    let cancel_flag = register_transfer(&request.transfer_id)?;
+    let total_bytes = entries.iter().map(|e| e.size).sum();
+    let state = Arc::new(Mutex::new(DirectoryTransferState {
+        progress: DirectoryTransferProgress {
+            transfer_id: request.transfer_id.clone(),
+            device_id: request.device_id.clone(),
+            direction: request.direction.to_string(),
+            local_dir: request.local_dir.clone(),
+            remote_dir: request.remote_dir.clone(),
+            total_files: entries.len() + request.skipped.len(),
+            completed_files: 0,
+            skipped_files: request.skipped.len(),
+            failed_files: 0,
+            total_bytes,
+            transferred_bytes: 0,
+            current_file: None,
+            status: "running".to_string(),
+        },
+        result: DirectoryTransferResult {
+            transfer_id: request.transfer_id.clone(),
+            transferred: Vec::new(),
+            skipped: request.skipped.clone(),
+            failed: Vec::new(),
+            transferred_bytes: 0,
+            cancelled: false,
+        },
+    }));
+    emit_directory_progress(app, &state);
+
+    let semaphore = Arc::new(tokio::sync::Semaphore::new(request.parallel.max(1)));
+    let mut handles = Vec::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let permit = match semaphore.clone().acquire_owned().await {
+            Ok(permit) => permit,
+            Err(_) => break,
+        };
+        if cancel_flag.load(Ordering::SeqCst) {
+            break;
+        }
+
+        let (cmd, probe) = build(&entry);
+        let file_request = TransferRequest {
+            transfer_id: format!("{}/{}", request.transfer_id, index),
+            device_id: request.device_id.clone(),
+            tool,
+            direction: request.direction,
+            local_path: entry.local_path.to_string_lossy().to_string(),
+            remote_path: entry.remote_path.clone(),
+            total_bytes: Some(entry.size),
+        };
+        if let Ok(mut guard) = state.lock() {
+            guard.progress.current_file = Some(entry.relative_path.clone());
+        }
+
+        let app = app.clone();
+        let state = state.clone();
+        handles.push(tauri::async_runtime::spawn(async move {
+            let _permit = permit;
+            let result = run_transfer(&app, file_request, cmd, probe).await;
+            if let Ok(mut guard) = state.lock() {
+                match result {
+                    Ok(_) => {
+                        guard.progress.completed_files += 1;
+                        guard.progress.transferred_bytes += entry.size;
+                        guard.result.transferred_bytes += entry.size;
+                        guard.result.transferred.push(entry.relative_path);
+                    }
+                    Err(MdtError::Cancelled { .. }) => {}
+                    Err(err) => {
+                        guard.progress.failed_files += 1;
+                        guard.result.failed.push(DirectoryTransferFailure {
+                            path: entry.relative_path,
+                            error: err.to_string(),
+                        });
+                    }
+                }
+            }
+            emit_directory_progress(&app, &state);
+        }));
+    }
+
+    for handle in handles {
+        let _ = handle.await;
+    }
+    unregister_transfer(&request.transfer_id);
+
+    let cancelled = cancel_flag.load(Ordering::SeqCst);
+    let result = {
+        let mut guard = state.lock().map_err(|_| MdtError::lock("transfer"))?;
+        guard.progress.current_file = None;
+        guard.progress.status = if cancelled {
+            "cancelled"
+        } else if guard.progress.failed_files > 0 {
+            "failed"
+        } else {
+            "completed"
+        }
+        .to_string();
+        guard.result.cancelled = cancelled;
+        guard.result.clone()
+    };
+    emit_directory_progress(app, &state);
+    Ok(result)
+}
+
+fn emit_directory_progress(app: &AppHandle, state: &Mutex<DirectoryTransferState>) {
+    let payload = match state.lock() {
+        Ok(guard) => guard.progress.clone(),
+        Err(_) => return,
+    };
+    let _ = app.emit(DIRECTORY_TRANSFER_PROGRESS_EVENT, payload);
+}
+
+/// 取消传输；目录传输会同时取消其中正在进行的单文件传输
+#[tauri::command]
+pub async fn cancel_transfer(transfer_id: String) -> MdtResult<()> {
+    let store = transfers().lock().map_err(|_| MdtError::lock("transfer"))?;
+    let child_prefix = format!("{}/", transfer_id);
+    let mut found = false;
+    for (id, flag) in store.iter() {
+        if *id == transfer_id || id.starts_with(&child_prefix) {
+            flag.store(true, Ordering::SeqCst);
+            found = true;
+        }
+    }
+    if !found {
+        return Err(MdtError::session_not_found("transfer", &transfer_id));
+    }
+    Ok(())
+}
+
+/// 传输校验使用的摘要算法，按优先级排列
+#[derive(Debug, Clone, Copy)]
+pub enum ChecksumAlgorithm {
+    Sha256,
+    Md5,
+}
+
+impl ChecksumAlgorithm {
+    pub const ALL: [ChecksumAlgorithm; 2] = [ChecksumAlgorithm::Sha256, ChecksumAlgorithm::Md5];
+
+    /// 设备端对应的计算命令
+    pub fn device_command(self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Sha256 => "sha256sum",
+            ChecksumAlgorithm::Md5 => "md5sum",
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Sha256 => "sha256",
+            ChecksumAlgorithm::Md5 => "md5",
+        }
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// 计算本地文件摘要
+pub fn local_checksum(path: &Path, algorithm: ChecksumAlgorithm) -> MdtResult<String> {
+    use md5::Md5;
+    use sha2::{Digest, Sha256};
+    use std::io::Read;
+
+    let mut file = std::fs::File::open(path).map_err(|e| MdtError::io("读取本地文件", e))?;
+    let mut buf = vec![0u8; 1024 * 1024];
+    let mut sha256 = Sha256::new();
+    let mut md5 = Md5::new();
+    loop {
+        let n = file.read(&mut buf).map_err(|e| MdtError::io("读取本地文件", e))?;
+        if n == 0 {
+            break;
+        }
+        match algorithm {
+            ChecksumAlgorithm::Sha256 => sha256.update(&buf[..n]),
+            ChecksumAlgorithm::Md5 => md5.update(&buf[..n]),
+        }
+    }
+    Ok(match algorithm {
+        ChecksumAlgorithm::Sha256 => hex(&sha256.finalize()),
+        ChecksumAlgorithm::Md5 => hex(&md5.finalize()),
+    })
+}
+
+/// 将设备端 `sha256sum`/`md5sum` 的输出与本地文件摘要对比
+pub async fn compare_checksum(
+    algorithm: ChecksumAlgorithm,
+    local_path: PathBuf,
+    remote_path: &str,
+    remote_output: &str,
+) -> MdtResult<()> {
+    let remote = remote_output
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    let local = tauri::async_runtime::spawn_blocking(move || local_checksum(&local_path, algorithm))
+        .await
+        .map_err(|e| MdtError::io("计算本地校验和", e))??;
+
+    if local == remote {
+        Ok(())
+    } else {
+        Err(MdtError::ChecksumMismatch {
+            path: remote_path.to_string(),
+            algorithm: algorithm.name().to_string(),
+            local,
+            remote,
+        })
+    }
+}
@@ -0,0 +1,319 @@
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::batch;
+use crate::error::{MdtError, MdtResult};
+use crate::hdc;
+
+/// 控件边界（像素）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct Bounds {
+    pub left: i32,
+    pub top: i32,
+    pub right: i32,
+    pub bottom: i32,
+}
+
+/// UI 层级中的单个控件
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct UiNode {
+    pub class: String,
+    pub package: String,
+    pub text: String,
+    pub resource_id: String,
+    pub content_desc: String,
+    pub bounds: Bounds,
+    pub clickable: bool,
+    pub long_clickable: bool,
+    pub scrollable: bool,
+    pub enabled: bool,
+    pub focused: bool,
+    pub selected: bool,
+    pub checked: bool,
+    pub children: Vec<UiNode>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UiHierarchy {
+    pub rotation: u32,
+    pub nodes: Vec<UiNode>,
+}
+
+/// 解析 `[0,0][1080,2340]` 形式的边界
+fn parse_bounds(value: &str) -> Bounds {
+    let numbers: Vec<i32> = value
+        .split(|c: char| !(c.is_ascii_digit() || c == '-'))
+        .filter(|part| !part.is_empty())
+        .filter_map(|part| part.parse().ok())
+        .collect();
+    match numbers.as_slice() {
+        [left, top, right, bottom] => Bounds {
+            left: *left,
+            top: *top,
+            right: *right,
+            bottom: *bottom,
+        },
+        _ => Bounds::default(),
+    }
+}
+
+fn node_from_element(element: &BytesStart) -> UiNode {
+    let mut node = UiNode::default();
+    for attr in element.attributes().flatten() {
+        let value = attr
+            .unescape_value()
+            .map(|v| v.to_string())
+            .unwrap_or_default();
+        let flag = value == "true";
+        match attr.key.as_ref() {
+            b"class" => node.class = value,
+            b"package" => node.package = value,
+            b"text" => node.text = value,
+            b"resource-id" => node.resource_id = value,
+            b"content-desc" => node.content_desc = value,
+            b"bounds" => node.bounds = parse_bounds(&value),
+            b"clickable" => node.clickable = flag,
+            b"long-clickable" => node.long_clickable = flag,
+            b"scrollable" => node.scrollable = flag,
+            b"enabled" => node.enabled = flag,
+            b"focused" => node.focused = flag,
+            b"selected" => node.selected = flag,
+            b"checked" => node.checked = flag,
+            _ => {}
+        }
+    }
+    node
+}
+
+/// 把 uiautomator 输出的 XML 转换为控件树
+fn parse_hierarchy(xml: &str) -> MdtResult<UiHierarchy> {
+    let mut reader = Reader::from_str(xml);
+    let mut rotation = 0;
+    // 栈底为虚拟根节点，收集顶层控件
+    let mut stack: Vec<UiNode> = vec![UiNode::default()];
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(element)) if element.name().as_ref() == b"node" => {
+                stack.push(node_from_element(&element));
+            }
+            Ok(Event::Empty(element)) if element.name().as_ref() == b"node" => {
+                let node = node_from_element(&element);
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(node);
+                }
+            }
+            Ok(Event::End(element)) if element.name().as_ref() == b"node" && stack.len() > 1 => {
+                let node = stack.pop().unwrap_or_default();
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(node);
+                }
+            }
+            Ok(Event::Start(element)) | Ok(Event::Empty(element))
+                if element.name().as_ref() == b"hierarchy" =>
+            {
+                rotation = element
+                    .try_get_attribute("rotation")
+                    .ok()
+                    .flatten()
+                    .and_then(|attr| String::from_utf8_lossy(&attr.value).parse().ok())
+                    .unwrap_or(0);
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(MdtError::io("解析 UI 层级", e)),
+            _ => {}
+        }
+    }
+    let root = stack.into_iter().next().unwrap_or_default();
+    Ok(UiHierarchy {
+        rotation,
+        nodes: root.children,
+    })
+}
+
+/// 通过 `uiautomator dump` 获取当前界面的控件树
+#[tauri::command]
+pub async fn adb_dump_ui_hierarchy(device_id: Option<String>) -> MdtResult<UiHierarchy> {
+    let remote_path = "/data/local/tmp/mdt_window_dump.xml";
+    let output = adb::adb_shell(&device_id, &["uiautomator", "dump", remote_path]).await?;
+    if !output.contains("dumped to") {
+        return Err(MdtError::CommandFailed {
+            command: "uiautomator dump".to_string(),
+            exit_code: None,
+            stderr: output,
+        });
+    }
+    let xml = adb::adb_shell(&device_id, &["cat", remote_path]).await;
+    let _ = adb::adb_shell(&device_id, &["rm", "-f", remote_path]).await;
+    parse_hierarchy(&xml?)
+}
+
+/// 把 uitest 布局 JSON 中的节点转换为与 uiautomator 相同的控件结构，属性值均为字符串
+fn node_from_layout(value: &serde_json::Value) -> UiNode {
+    let attributes = &value["attributes"];
+    let text = |key: &str| attributes[key].as_str().unwrap_or_default().to_string();
+    let flag = |key: &str| attributes[key].as_str() == Some("true");
+    let resource_id = match text("id") {
+        id if id.is_empty() => text("key"),
+        id => id,
+    };
+    UiNode {
+        class: text("type"),
+        package: text("bundleName"),
+        text: text("text"),
+        resource_id,
+        content_desc: text("description"),
+        bounds: parse_bounds(&text("bounds")),
+        clickable: flag("clickable"),
+        long_clickable: flag("longClickable"),
+        scrollable: flag("scrollable"),
+        enabled: flag("enabled"),
+        focused: flag("focused"),
+        selected: flag("selected"),
+        checked: flag("checked"),
+        children: value["children"]
+            .as_array()
+            .map(|children| children.iter().map(node_from_layout).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// 通过 `uitest dumpLayout` 获取 HarmonyOS 当前界面的控件树，与 `adb_dump_ui_hierarchy` 结构一致
+#[tauri::command]
+pub async fn hdc_dump_layout(device_id: Option<String>) -> MdtResult<UiHierarchy> {
+    let remote_path = "/data/local/tmp/mdt_layout.json";
+    let output = hdc::hdc_shell(&device_id, &["uitest", "dumpLayout", "-p", remote_path]).await?;
+    if !output.contains("saved to") {
+        return Err(MdtError::CommandFailed {
+            command: "uitest dumpLayout".to_string(),
+            exit_code: None,
+            stderr: output,
+        });
+    }
+    let local_path = std::env::temp_dir().join(format!(
+        "mdt_layout_{}.json",
+        batch::file_safe_name(device_id.as_deref().unwrap_or("default"))
+    ));
+    let local = local_path.to_string_lossy().to_string();
+    let received = hdc::hdc_run(&device_id, &["file", "recv", remote_path, &local]).await;
+    let _ = hdc::hdc_shell(&device_id, &["rm", "-f", remote_path]).await;
+    received?;
+    let content = std::fs::read_to_string(&local_path).map_err(|e| MdtError::io("读取布局文件", e));
+    let _ = std::fs::remove_file(&local_path);
+    let layout: serde_json::Value =
+        serde_json::from_str(&content?).map_err(|e| MdtError::io("解析布局 JSON", e))?;
+
+    // 顶层为 type=root 的虚拟节点，与 uiautomator 一致只返回其子控件
+    let root = node_from_layout(&layout);
+    let nodes = if root.class == "root" { root.children } else { vec![root] };
+    Ok(UiHierarchy { rotation: 0, nodes })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskInfo {
+    pub task_id: Option<u32>,
+    /// 自栈顶到栈底的 Activity
+    pub activities: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrentActivity {
+    pub package: Option<String>,
+    pub activity: Option<String>,
+    pub task_id: Option<u32>,
+    /// 当前获得焦点的窗口（`dumpsys window` 中的 mCurrentFocus）
+    pub focused_window: Option<String>,
+    pub tasks: Vec<TaskInfo>,
+}
+
+/// 从 `ActivityRecord{1a2b u0 com.demo/.MainActivity t42}` 中取出包名、完整类名和任务 ID
+fn parse_activity_record(line: &str) -> Option<(String, String, Option<u32>)> {
+    let start = line.find('{')?;
+    let end = line[start..].find('}').map(|i| start + i).unwrap_or(line.len());
+    let tokens: Vec<&str> = line[start + 1..end].split_whitespace().collect();
+    let component = tokens.iter().find(|token| token.contains('/'))?;
+    let (package, class) = component.split_once('/')?;
+    let class = if class.starts_with('.') {
+        format!("{}{}", package, class)
+    } else {
+        class.to_string()
+    };
+    let task_id = tokens
+        .iter()
+        .find_map(|token| token.strip_prefix('t').and_then(|id| id.parse().ok()));
+    Some((package.to_string(), class, task_id))
+}
+
+fn parse_activities(output: &str) -> CurrentActivity {
+    let mut current = CurrentActivity {
+        package: None,
+        activity: None,
+        task_id: None,
+        focused_window: None,
+        tasks: Vec::new(),
+    };
+    for line in output.lines() {
+        let trimmed = line.trim();
+        let resumed = trimmed.starts_with("mResumedActivity")
+            || trimmed.starts_with("topResumedActivity")
+            || trimmed.starts_with("ResumedActivity");
+        if resumed && current.activity.is_none() {
+            if let Some((package, activity, task_id)) = parse_activity_record(trimmed) {
+                current.package = Some(package);
+                current.activity = Some(activity);
+                current.task_id = task_id;
+            }
+        } else if trimmed.starts_with("* Task{") || trimmed.starts_with("* TaskRecord{") {
+            // * Task{8f1 #42 type=standard A=10123:com.demo ...}
+            let task_id = trimmed
+                .split_whitespace()
+                .find_map(|token| token.strip_prefix('#').and_then(|id| id.parse().ok()));
+            current.tasks.push(TaskInfo {
+                task_id,
+                activities: Vec::new(),
+            });
+        } else if trimmed.starts_with("* Hist") || trimmed.starts_with("Hist #") {
+            if let (Some(task), Some((_, activity, _))) =
+                (current.tasks.last_mut(), parse_activity_record(trimmed))
+            {
+                task.activities.push(activity);
+            }
+        }
+    }
+    current
+}
+
+/// 查询前台应用、Activity 与任务栈
+#[tauri::command]
+pub async fn adb_current_activity(device_id: Option<String>) -> MdtResult<CurrentActivity> {
+    let activities = adb::adb_shell(&device_id, &["dumpsys", "activity", "activities"]).await?;
+    let mut current = parse_activities(&activities);
+
+    let window = adb::adb_shell(&device_id, &["dumpsys", "window", "windows"])
+        .await
+        .unwrap_or_default();
+    for line in window.lines() {
+        let trimmed = line.trim();
+        if let Some(rest) = trimmed.strip_prefix("mCurrentFocus=") {
+            // mCurrentFocus=Window{abc u0 com.demo/com.demo.MainActivity}
+            current.focused_window = rest
+                .trim_start_matches("Window{")
+                .trim_end_matches('}')
+                .split_whitespace()
+                .last()
+                .map(str::to_string);
+        } else if trimmed.starts_with("mFocusedApp=") && current.activity.is_none() {
+            if let Some((package, activity, task_id)) = parse_activity_record(trimmed) {
+                current.package = Some(package);
+                current.activity = Some(activity);
+                current.task_id = task_id;
+            }
+        }
+    }
+    Ok(current)
+}
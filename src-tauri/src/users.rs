@@ -0,0 +1,117 @@
+use serde::{Deserialize, Serialize};
+use crate::adb;
+use crate::error::{MdtError, MdtResult};
+use crate::history;
+use crate::tools;
+
+/// `UserInfo.FLAG_MANAGED_PROFILE`
+const FLAG_MANAGED_PROFILE: u32 = 0x20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AndroidUser {
+    pub id: u32,
+    pub name: String,
+    /// UserInfo 标志位（十六进制原值）
+    pub flags: u32,
+    pub running: bool,
+    pub current: bool,
+    /// 工作资料（managed profile）
+    pub managed_profile: bool,
+}
+
+/// 解析 `UserInfo{10:Work profile:1030} running`
+fn parse_users(output: &str, current: Option<u32>) -> Vec<AndroidUser> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            let inner = line.strip_prefix("UserInfo{")?;
+            let (info, rest) = inner.split_once('}')?;
+            let mut parts = info.split(':');
+            let id: u32 = parts.next()?.parse().ok()?;
+            // 用户名可能包含冒号，标志位总在最后
+            let fields: Vec<&str> = parts.collect();
+            let (flags, name) = fields.split_last()?;
+            let flags = u32::from_str_radix(flags, 16).unwrap_or(0);
+            Some(AndroidUser {
+                id,
+                name: name.join(":"),
+                flags,
+                running: rest.contains("running"),
+                current: current == Some(id),
+                managed_profile: flags & FLAG_MANAGED_PROFILE != 0,
+            })
+        })
+        .collect()
+}
+
+#[tauri::command]
+pub async fn adb_list_users(device_id: Option<String>) -> MdtResult<Vec<AndroidUser>> {
+    let current = adb::adb_shell(&device_id, &["am", "get-current-user"])
+        .await
+        .ok()
+        .and_then(|id| id.trim().parse().ok());
+    let output = adb::adb_shell(&device_id, &["pm", "list", "users"]).await?;
+    Ok(parse_users(&output, current))
+}
+
+/// 创建用户；`managed_profile` 为 true 时为当前用户创建工作资料并启动
+#[tauri::command]
+pub async fn adb_create_user(
+    device_id: Option<String>,
+    name: String,
+    managed_profile: Option<bool>,
+) -> MdtResult<Vec<AndroidUser>> {
+    let _history = history::track();
+    if name.trim().is_empty() {
+        return Err(MdtError::invalid_argument("name", "用户名不能为空"));
+    }
+    let quoted_name = tools::shell_quote(name.trim());
+    let managed = managed_profile.unwrap_or(false);
+    let parent = if managed {
+        adb::adb_shell(&device_id, &["am", "get-current-user"]).await?.trim().to_string()
+    } else {
+        String::new()
+    };
+    let mut args = vec!["pm", "create-user"];
+    if managed {
+        args.extend(["--profileOf", &parent, "--managed"]);
+    }
+    args.push(&quoted_name);
+    let output = adb::adb_shell(&device_id, &args).await?;
+    let output = adb::expect_pm_success(&format!("pm create-user {}", name), output)?;
+
+    // 输出形如 `Success: created user id 11`
+    let created = output
+        .split_whitespace()
+        .last()
+        .and_then(|id| id.parse::<u32>().ok());
+    if let (true, Some(id)) = (managed, created) {
+        adb::adb_shell(&device_id, &["am", "start-user", &id.to_string()]).await?;
+    }
+    println!("[users] created user {:?} ({})", created, name);
+    adb_list_users(device_id).await
+}
+
+/// 删除用户及其全部数据（系统用户 0 不可删除）
+#[tauri::command]
+pub async fn adb_remove_user(device_id: Option<String>, user_id: u32) -> MdtResult<Vec<AndroidUser>> {
+    let _history = history::track();
+    if user_id == 0 {
+        return Err(MdtError::invalid_argument("userId", "不能删除系统用户"));
+    }
+    let output = adb::adb_shell(&device_id, &["pm", "remove-user", &user_id.to_string()]).await?;
+    adb::expect_pm_success(&format!("pm remove-user {}", user_id), output)?;
+    println!("[users] removed user {}", user_id);
+    adb_list_users(device_id).await
+}
+
+#[tauri::command]
+pub async fn adb_switch_user(device_id: Option<String>, user_id: u32) -> MdtResult<Vec<AndroidUser>> {
+    let _history = history::track();
+    adb::adb_shell(&device_id, &["am", "switch-user", &user_id.to_string()]).await?;
+    // 切换用户是异步的，稍等后再读取状态
+    tokio::time::sleep(std::time::Duration::from_millis(1000)).await;
+    adb_list_users(device_id).await
+}
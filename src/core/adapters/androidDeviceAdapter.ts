@@ -129,7 +129,11 @@ export class AndroidDeviceAdapter implements DeviceAdapter {
   async stopScreenRecord(deviceId: string, outputPath?: string | null): Promise<string> {
     const invokeFn = await getInvoke();
     ensureInvoke(invokeFn);
-    return invokeFn("adb_stop_screenrecord", { deviceId, outputPath: outputPath ?? null });
+    const result = await invokeFn<{ path: string }>("adb_stop_screenrecord", {
+      deviceId,
+      outputPath: outputPath ?? null,
+    });
+    return result.path;
   }
 
   async pushFile(deviceId: string, localPath: string, remotePath: string): Promise<string> {